@@ -6,8 +6,38 @@ fn criterion_benchmark(c: &mut Criterion) {
     for n in (20..27).step_by(2) {
         let program = qft(n);
         let mut state = StateVector::new(n);
-        c.bench_function(&*format!("qft{}", n), |b| b.iter(|| program.run(&mut state)));
+        c.bench_function(&format!("qft{}", n), |b| b.iter(|| program.run(&mut state).unwrap()));
     };
 }
-criterion_group!(benches, criterion_benchmark);
-criterion_main!(benches);
\ No newline at end of file
+
+/// a hardware efficient ansatz style program, with long runs of gates on the same qubits
+fn layered_program(n: usize, layers: usize) -> Program {
+    let mut program = Program::new();
+    for layer in 0..layers {
+        for qubit in 0..n {
+            program.rx(qubit, 0.1 * layer as Real);
+            program.rz(qubit, 0.2 * layer as Real);
+            program.ry(qubit, 0.3 * layer as Real);
+        }
+        for qubit in (layer % 2..n - 1).step_by(2) {
+            program.cnot(qubit, qubit + 1);
+            program.rz(qubit + 1, 0.4);
+            program.cnot(qubit, qubit + 1);
+        }
+    }
+    program
+}
+
+fn fusion_benchmark(c: &mut Criterion) {
+    for n in (16..23).step_by(2) {
+        let mut program = layered_program(n, 4);
+        for fusion in [Fusion::None, Fusion::SingleQubit, Fusion::TwoQubit] {
+            program.set_fusion(fusion);
+            let mut state = StateVector::new(n);
+            c.bench_function(&format!("layered{} {:?}", n, fusion), |b| b.iter(|| program.run(&mut state).unwrap()));
+        }
+    };
+}
+
+criterion_group!(benches, criterion_benchmark, fusion_benchmark);
+criterion_main!(benches);
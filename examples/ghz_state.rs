@@ -14,7 +14,7 @@ fn main() {
 
     // running the program to evolve the state vector from its
     //initial state of |000> to (1 / SQRT_2) *(|000> + |111>)
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
     // printing the final state
     println!("{}", state);
@@ -8,7 +8,7 @@ fn time_and_average<T: StateTraits>(program: Program, state: &mut T) -> (usize,
     let mut times_ns:Vec<u128> = Vec::new();
     while start_time.elapsed().as_secs() < 1 && times_ns.len() < 100 {
         let t0 = Instant::now();
-        program.run(state);
+        program.run(state).unwrap();
         times_ns.push(t0.elapsed().as_nanos())
     }
 
@@ -18,7 +18,7 @@ fn time_and_average<T: StateTraits>(program: Program, state: &mut T) -> (usize,
     let sum: f32 = times_s.iter().sum();
     let squared_sum: f32 = times_s.iter().map(|x| x * x).sum();
     let mean = sum / number_of_averages as f32;
-    let squared_mean = squared_sum as f32 / number_of_averages as f32;
+    let squared_mean = squared_sum / number_of_averages as f32;
 
     let std = match number_of_averages {
         1 => None,
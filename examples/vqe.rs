@@ -2,7 +2,7 @@ use itertools_num::linspace;
 use bra_ket::*;
 use gnuplot::{Figure, Caption, Color, AxesCommon};
 
-///https://journals.aps.org/prx/pdf/10.1103/PhysRevX.6.031007
+// https://journals.aps.org/prx/pdf/10.1103/PhysRevX.6.031007
 
 /// makes the ansatz circuit to prepare the quantum state
 fn make_ansatz(theta: &Real) -> Program {
@@ -34,7 +34,7 @@ fn evaluate_energy(theta: &Real) -> Real {
     energy += { // adding the energy from the Z0 components of the hamiltonian
         let coefficient = 0.3435; // the weighting for the pauli term in the hamiltonian
         // running the ansatz circuit
-        ansatz.run(&mut state);
+        ansatz.run(&mut state).unwrap();
         // measuring the expectation value of the 0th qubit in the Z basis
         let expectation = state.get_expectation(&0);
         coefficient * expectation
@@ -42,7 +42,7 @@ fn evaluate_energy(theta: &Real) -> Real {
 
     energy += { // adding the energy from the Z1 components of the hamiltonian
         let coefficient = -0.4347; // the weighting for the pauli term in the hamiltonian
-        ansatz.run(&mut state); // running the ansatz circuit and then the measurement
+        ansatz.run(&mut state).unwrap(); // running the ansatz circuit and then the measurement
         // measuring the expectation value of the 1st qubit in the Z basis
         let expectation = state.get_expectation(&1);
         coefficient * expectation
@@ -56,7 +56,7 @@ fn evaluate_energy(theta: &Real) -> Real {
 
         let coefficient = 0.5716; // the weighting for the pauli term in the hamiltonian
         // running the ansatz circuit and then the measurement program
-        (ansatz.to_owned() + measure_z0_z1).run(&mut state);
+        (ansatz.to_owned() + measure_z0_z1).run(&mut state).unwrap();
         // measuring the expectation value of the 0th qubit in the Z basis
         let expectation = state.get_expectation(&0);
         coefficient * expectation
@@ -72,7 +72,7 @@ fn evaluate_energy(theta: &Real) -> Real {
 
         let coefficient = 0.0910; // the weighting for the pauli term in the hamiltonian
         // running the ansatz circuit and then the measurement program
-        (ansatz.to_owned() + measure_x0_x1).run(&mut state);
+        (ansatz.to_owned() + measure_x0_x1).run(&mut state).unwrap();
         // measuring the expectation value of the 0th qubit in the Z basis
         let expectation = state.get_expectation(&0);
         coefficient * expectation
@@ -91,7 +91,7 @@ fn evaluate_energy(theta: &Real) -> Real {
 
         let coefficient = 0.0910; // the weighting for the pauli term in the hamiltonian
         // running the ansatz circuit and then the measurement program
-        (ansatz.to_owned() + measure_y0_y1).run(&mut state);
+        (ansatz.to_owned() + measure_y0_y1).run(&mut state).unwrap();
         // measuring the expectation value of the 0th qubit in the Z basis
         let expectation = state.get_expectation(&0);
         coefficient * expectation
@@ -104,7 +104,7 @@ fn main() {
     let theta_s = RVector::from_iterator(n, linspace::<Real>(- PI, PI, n));
     let energies =  RVector::from_iterator(n,theta_s
         .iter()
-        .map(|theta| evaluate_energy(theta))
+        .map(evaluate_energy)
     );
 
 
@@ -0,0 +1,62 @@
+use crate::error::BraKetError;
+
+/// The order in which qubits are written in the labels of basis states, as bitstrings and as integers.
+/// With little endian qubit 0 is the least significant bit, the rightmost character of a bitstring. With big
+/// endian qubit 0 is the most significant bit, the leftmost character. Either way a bitstring read as a
+/// binary number is its integer label. The order only affects labels, the state is stored with qubit k as
+/// bit k of the index in both cases.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// Converts between an integer label and the index of the basis state in the state vector. Reversing
+    /// the bits is its own inverse, so this also converts indices into labels.
+    pub fn convert(&self, value: usize, number_of_qubits: usize) -> usize {
+        match self {
+            Endianness::Little => value,
+            Endianness::Big => (0..number_of_qubits).map(|qubit| ((value >> qubit) & 1) << (number_of_qubits - 1 - qubit)).sum(),
+        }
+    }
+
+    /// The bitstring labelling the basis state with the given index.
+    pub fn bitstring(&self, index: usize, number_of_qubits: usize) -> String {
+        format!("{:0width$b}", self.convert(index, number_of_qubits), width = number_of_qubits)
+    }
+}
+
+/// A label of a basis state, either a bitstring or an integer, read with the endianness of the state.
+pub trait BasisLabel {
+    /// The index of the basis state in the state vector, or an error if the label is not a basis state of
+    /// number_of_qubits qubits.
+    fn index(&self, number_of_qubits: usize, endianness: Endianness) -> Result<usize, BraKetError>;
+}
+
+impl BasisLabel for &str {
+    fn index(&self, number_of_qubits: usize, endianness: Endianness) -> Result<usize, BraKetError> {
+        let invalid = || BraKetError::InvalidBasisLabel { label: self.to_string(), number_of_qubits };
+        if self.len() != number_of_qubits || number_of_qubits >= usize::BITS as usize {
+            return Err(invalid());
+        }
+        let value = self.chars().try_fold(0, |value, bit| match bit {
+            '0' => Ok(value << 1),
+            '1' => Ok((value << 1) | 1),
+            _ => Err(invalid()),
+        })?;
+        Ok(endianness.convert(value, number_of_qubits))
+    }
+}
+
+impl BasisLabel for u64 {
+    fn index(&self, number_of_qubits: usize, endianness: Endianness) -> Result<usize, BraKetError> {
+        // states of usize::BITS qubits or more have too many basis states to index
+        if number_of_qubits >= usize::BITS as usize || *self >= 1 << number_of_qubits {
+            return Err(BraKetError::InvalidBasisLabel { label: self.to_string(), number_of_qubits });
+        }
+        Ok(endianness.convert(*self as usize, number_of_qubits))
+    }
+}
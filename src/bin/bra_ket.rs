@@ -0,0 +1,307 @@
+//! Runs a circuit written in OpenQASM 2.0 or in the JSON schema of the serde feature from the command line,
+//! printing the sampled counts, the final state, expectation values or a drawing of the circuit.
+
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::process::ExitCode;
+
+use bra_ket::*;
+use serde::Serialize;
+use serde_json::json;
+
+const USAGE: &str = "\
+Usage: bra_ket [options] <circuit>
+
+Runs a circuit read from an OpenQASM 2.0 file, or from a JSON file holding a serialized Program.
+
+Options:
+  --backend <statevector|density>  the state the circuit is run on [default: statevector]
+  --shots <n>                      the number of times the measurements are sampled [default: 1024]
+  --seed <n>                       seeds the sampling and the measurements, for reproducible results
+  --show <what>                    what to print [default: counts]:
+                                     counts       how often each outcome of the final measurements occurs
+                                     state        the state after running the circuit
+                                     expectation  the expectation values of the observables before the final measurements
+                                     circuit      a drawing of the circuit
+  --noise <single,two,readout>     depolarizing errors after single and two qubit gates and a readout error on
+                                   every qubit, with the given probabilities, such as 0.001,0.01,0.02
+  --observable <pauli>             a Pauli string such as Z0Z1 or X0Y2, for --show expectation, may be repeated
+  --endianness <little|big>        the order of the qubits in bitstrings, and of the elements of the registers
+                                   of OpenQASM circuits [default: little]
+  --output <human|json>            the format of the output [default: human]
+  -h, --help                       prints this message
+";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Backend {
+    StateVector,
+    DensityMatrix,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Show {
+    Counts,
+    State,
+    Expectation,
+    Circuit,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    Human,
+    Json,
+}
+
+struct Options {
+    file: String,
+    backend: Backend,
+    shots: usize,
+    seed: Option<u64>,
+    noise: Option<[Real; 3]>,
+    show: Show,
+    observables: Vec<String>,
+    endianness: Endianness,
+    output: Output,
+}
+
+/// Reads the options, returning None if the usage was asked for.
+fn parse_arguments<I: Iterator<Item = String>>(mut arguments: I) -> Result<Option<Options>, String> {
+    let mut options = Options {
+        file: String::new(),
+        backend: Backend::StateVector,
+        shots: 1024,
+        seed: None,
+        noise: None,
+        show: Show::Counts,
+        observables: vec![],
+        endianness: Endianness::Little,
+        output: Output::Human,
+    };
+    let mut file = None;
+    while let Some(argument) = arguments.next() {
+        if argument == "-h" || argument == "--help" {
+            return Ok(None);
+        }
+        if !argument.starts_with("--") {
+            match file {
+                None => file = Some(argument),
+                Some(_) => return Err(format!("unexpected argument {}", argument)),
+            }
+            continue;
+        }
+        let value = arguments.next().ok_or_else(|| format!("{} needs a value", argument))?;
+        let invalid = || format!("invalid value {} for {}", value, argument);
+        match argument.as_str() {
+            "--backend" => {
+                options.backend = match value.as_str() {
+                    "statevector" => Backend::StateVector,
+                    "density" => Backend::DensityMatrix,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--shots" => options.shots = value.parse().map_err(|_| invalid())?,
+            "--seed" => options.seed = Some(value.parse().map_err(|_| invalid())?),
+            "--noise" => {
+                let probabilities: Vec<Real> = value.split(',').map(|p| p.trim().parse()).collect::<Result<_, _>>().map_err(|_| invalid())?;
+                match probabilities[..] {
+                    [single, two, readout] if probabilities.iter().all(|p| (0. ..=1.).contains(p)) => options.noise = Some([single, two, readout]),
+                    _ => return Err(invalid()),
+                }
+            }
+            "--show" => {
+                options.show = match value.as_str() {
+                    "counts" => Show::Counts,
+                    "state" => Show::State,
+                    "expectation" => Show::Expectation,
+                    "circuit" => Show::Circuit,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--observable" => options.observables.push(value),
+            "--endianness" => {
+                options.endianness = match value.as_str() {
+                    "little" => Endianness::Little,
+                    "big" => Endianness::Big,
+                    _ => return Err(invalid()),
+                }
+            }
+            "--output" => {
+                options.output = match value.as_str() {
+                    "human" => Output::Human,
+                    "json" => Output::Json,
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(format!("unknown option {}", argument)),
+        }
+    }
+    options.file = file.ok_or("no circuit given")?;
+    if options.show == Show::Expectation && options.observables.is_empty() {
+        return Err("--show expectation needs at least one --observable".to_owned());
+    }
+    Ok(Some(options))
+}
+
+/// Reads a program from a file, as JSON if it starts with a brace and as OpenQASM otherwise, with the
+/// registers of OpenQASM circuits in the given order.
+fn read_program(file: &str, endianness: Endianness) -> Result<Program, String> {
+    let source = std::fs::read_to_string(file).map_err(|error| format!("cannot read {}: {}", file, error))?;
+    match source.trim_start().starts_with('{') {
+        true => serde_json::from_str(&source).map_err(|error| format!("invalid JSON program: {}", error)),
+        false => Program::from_qasm_with_endianness(&source, endianness).map_err(|error| error.to_string()),
+    }
+}
+
+/// Reads a Pauli string such as Z0Z1 into its (Pauli, qubit) factors.
+fn pauli_string(label: &str) -> Result<Vec<(char, usize)>, String> {
+    let invalid = || format!("{} is not a Pauli string such as Z0Z1", label);
+    let mut factors: Vec<(char, usize)> = vec![];
+    let mut characters = label.chars().peekable();
+    while let Some(pauli) = characters.next() {
+        let pauli = pauli.to_ascii_uppercase();
+        if !"IXYZ".contains(pauli) {
+            return Err(invalid());
+        }
+        let mut digits = String::new();
+        while let Some(digit) = characters.next_if(|c| c.is_ascii_digit()) {
+            digits.push(digit);
+        }
+        let qubit = digits.parse().map_err(|_| invalid())?;
+        if factors.iter().any(|(_, q)| *q == qubit) {
+            return Err(format!("qubit {} appears twice in {}", qubit, label));
+        }
+        factors.push((pauli, qubit));
+    }
+    Ok(factors)
+}
+
+/// The expectation value of a Pauli string, measuring each factor by rotating its qubit into the z basis.
+fn expectation<T: StateTraits + Clone>(state: &T, factors: &[(char, usize)]) -> Result<Real, BraKetError> {
+    let mut rotation = Program::new();
+    let mut mask = 0;
+    for (pauli, qubit) in factors.iter() {
+        if *qubit >= state.number_of_qubits() {
+            return Err(BraKetError::InvalidQubit { qubit: *qubit, number_of_qubits: state.number_of_qubits() });
+        }
+        match pauli {
+            'X' => rotation.h(*qubit),
+            'Y' => {
+                rotation.sdg(*qubit);
+                rotation.h(*qubit);
+            }
+            _ => {}
+        }
+        if *pauli != 'I' {
+            mask |= 1 << qubit;
+        }
+    }
+    let mut rotated = state.clone();
+    rotation.run(&mut rotated)?;
+    Ok(rotated
+        .probabilities()
+        .iter()
+        .enumerate()
+        .map(|(index, p)| if (index & mask).count_ones() % 2 == 0 { *p } else { -p })
+        .sum())
+}
+
+fn print_json<V: Serialize>(value: &V) {
+    println!("{}", serde_json::to_string_pretty(value).expect("serializable output"));
+}
+
+fn run<T: StateTraits + Clone + Display + Serialize>(options: &Options, program: &Program, mut state: T) -> Result<(), String> {
+    let seed = options.seed.unwrap_or_else(rand::random);
+    set_seed(seed);
+    let noise = options
+        .noise
+        .map(|[single, two, readout]| NoiseModel::uniform(single, two, readout, state.number_of_qubits()))
+        .transpose()
+        .map_err(|error| error.to_string())?;
+    let run = |program: &Program, state: &mut T| match noise.as_ref() {
+        Some(model) => program.run_with_noise(state, model),
+        None => program.run(state),
+    };
+    match options.show {
+        Show::Counts => {
+            let counts = match noise.as_ref() {
+                Some(model) => program.sample_with_noise(&state, options.shots, seed, model),
+                None => program.sample(&state, options.shots, seed),
+            }
+            .map_err(|error| error.to_string())?;
+            match options.output {
+                Output::Human => {
+                    for (outcome, count) in counts.iter() {
+                        println!("{} {}", outcome, count);
+                    }
+                }
+                Output::Json => print_json(&json!({ "shots": options.shots, "counts": counts })),
+            }
+        }
+        Show::State => {
+            run(program, &mut state).map_err(|error| error.to_string())?;
+            match options.output {
+                Output::Human => print!("{}", state),
+                Output::Json => print_json(&state),
+            }
+        }
+        Show::Expectation => {
+            run(&program.without_final_measurements(), &mut state).map_err(|error| error.to_string())?;
+            let mut values = BTreeMap::new();
+            for observable in options.observables.iter() {
+                let factors = pauli_string(observable)?;
+                values.insert(observable.to_owned(), expectation(&state, &factors).map_err(|error| error.to_string())?);
+            }
+            match options.output {
+                Output::Human => {
+                    for (observable, value) in values.iter() {
+                        println!("<{}> = {:.6}", observable, value);
+                    }
+                }
+                Output::Json => print_json(&json!({ "expectation_values": values })),
+            }
+        }
+        Show::Circuit => match options.output {
+            Output::Human => print!("{}", program),
+            Output::Json => print_json(&json!({ "drawing": program.to_string() })),
+        },
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let options = match parse_arguments(std::env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            print!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    let result = read_program(&options.file, options.endianness).and_then(|program| {
+        let number_of_qubits = program.number_of_qubits();
+        program.validate(number_of_qubits).map_err(|issues| BraKetError::InvalidProgram(issues).to_string())?;
+        match options.backend {
+            Backend::StateVector => {
+                let mut state = StateVector::try_new(number_of_qubits).map_err(|error| error.to_string())?;
+                state.set_endianness(options.endianness);
+                run(&options, &program, state)
+            }
+            Backend::DensityMatrix => {
+                let mut state = DensityMatrix::try_new(number_of_qubits).map_err(|error| error.to_string())?;
+                state.set_endianness(options.endianness);
+                run(&options, &program, state)
+            }
+        }
+    });
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::basis::Endianness;
+use crate::error::BraKetError;
+use crate::types::*;
+
+/// The first bytes of a checkpoint file.
+const MAGIC: &[u8; 8] = b"BRAKETQS";
+/// The version of the checkpoint format, increased whenever the layout changes.
+const VERSION: u16 = 1;
+/// The first bytes of a NumPy .npy file.
+const NPY_MAGIC: &[u8; 6] = b"\x93NUMPY";
+
+/// The precision the elements of a state are saved with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// 32 bit floats, halving the size of the file at the cost of rounding the state.
+    Single,
+    /// 64 bit floats, saving the state exactly.
+    #[default]
+    Double,
+}
+
+impl Precision {
+    fn bytes(&self) -> u8 {
+        match self {
+            Precision::Single => 4,
+            Precision::Double => 8,
+        }
+    }
+}
+
+/// What a file holds, a state vector or a density matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    StateVector = 0,
+    DensityMatrix = 1,
+}
+
+impl Kind {
+    /// The number of elements of a state of this kind on number_of_qubits qubits, or an error if there are
+    /// too many qubits for the elements to be counted.
+    fn len(&self, number_of_qubits: usize) -> Result<usize, BraKetError> {
+        let bits = match self {
+            Kind::StateVector => number_of_qubits,
+            Kind::DensityMatrix => 2 * number_of_qubits,
+        };
+        match bits < usize::BITS as usize {
+            true => Ok(1 << bits),
+            false => Err(invalid("too many qubits")),
+        }
+    }
+}
+
+fn io_error(error: std::io::Error) -> BraKetError {
+    BraKetError::Io(error.to_string())
+}
+
+fn invalid(message: &str) -> BraKetError {
+    BraKetError::InvalidFile(message.to_owned())
+}
+
+/// The 64 bit FNV-1a hash, updated as the elements are written or read.
+struct Checksum(u64);
+
+impl Checksum {
+    fn new() -> Checksum {
+        Checksum(0xcbf29ce484222325)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+/// Writes an element into the buffer as a little endian (re, im) pair, returning the bytes written.
+fn element_bytes<'a>(z: &Complex, precision: Precision, bytes: &'a mut [u8; 16]) -> &'a [u8] {
+    match precision {
+        Precision::Single => {
+            bytes[0..4].copy_from_slice(&(z.re as f32).to_le_bytes());
+            bytes[4..8].copy_from_slice(&(z.im as f32).to_le_bytes());
+            &bytes[..8]
+        }
+        Precision::Double => {
+            bytes[0..8].copy_from_slice(&z.re.to_le_bytes());
+            bytes[8..16].copy_from_slice(&z.im.to_le_bytes());
+            &bytes[..]
+        }
+    }
+}
+
+fn read_element<R: Read>(reader: &mut R, precision: Precision, checksum: &mut Checksum) -> Result<Complex, BraKetError> {
+    let mut bytes = [0u8; 16];
+    let bytes = &mut bytes[..2 * precision.bytes() as usize];
+    reader.read_exact(bytes).map_err(io_error)?;
+    checksum.update(bytes);
+    Ok(match precision {
+        Precision::Single => Complex::new(
+            f32::from_le_bytes(bytes[0..4].try_into().unwrap()) as Real,
+            f32::from_le_bytes(bytes[4..8].try_into().unwrap()) as Real,
+        ),
+        Precision::Double => Complex::new(
+            f64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            f64::from_le_bytes(bytes[8..16].try_into().unwrap()),
+        ),
+    })
+}
+
+/// Writes a checkpoint: the magic bytes, the format version, the kind of state, its endianness, the number
+/// of bytes per real number, the number of qubits and the checksum of the elements, followed by the
+/// elements as little endian (re, im) pairs. The checksum is written last, once the elements are hashed.
+pub fn save<I: Iterator<Item = Complex>>(
+    path: &Path,
+    kind: Kind,
+    number_of_qubits: usize,
+    endianness: Endianness,
+    precision: Precision,
+    elements: I,
+) -> Result<(), BraKetError> {
+    let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+    let mut header = MAGIC.to_vec();
+    header.extend(VERSION.to_le_bytes());
+    header.push(kind as u8);
+    header.push(endianness as u8);
+    header.push(precision.bytes());
+    header.extend((number_of_qubits as u32).to_le_bytes());
+    writer.write_all(&header).map_err(io_error)?;
+    // a placeholder for the checksum, which is only known once the elements are written
+    writer.write_all(&0u64.to_le_bytes()).map_err(io_error)?;
+
+    let mut checksum = Checksum::new();
+    let mut buffer = [0u8; 16];
+    for element in elements {
+        let bytes = element_bytes(&element, precision, &mut buffer);
+        checksum.update(bytes);
+        writer.write_all(bytes).map_err(io_error)?;
+    }
+
+    let mut file = writer.into_inner().map_err(|error| io_error(error.into_error()))?;
+    file.seek(SeekFrom::Start(header.len() as u64)).map_err(io_error)?;
+    file.write_all(&checksum.0.to_le_bytes()).map_err(io_error)
+}
+
+/// Reads a checkpoint written by save, checking it holds a state of the expected kind and that the
+/// elements match the checksum. Returns the number of qubits, the endianness and the elements.
+pub fn load(path: &Path, kind: Kind) -> Result<(usize, Endianness, Vec<Complex>), BraKetError> {
+    let mut reader = BufReader::new(File::open(path).map_err(io_error)?);
+    let mut header = [0u8; 25];
+    reader.read_exact(&mut header).map_err(io_error)?;
+    if &header[0..8] != MAGIC {
+        return Err(invalid("not a bra_ket checkpoint"));
+    }
+    if u16::from_le_bytes([header[8], header[9]]) != VERSION {
+        return Err(invalid("unsupported checkpoint version"));
+    }
+    if header[10] != kind as u8 {
+        return Err(invalid("checkpoint holds a different kind of state"));
+    }
+    let endianness = match header[11] {
+        0 => Endianness::Little,
+        1 => Endianness::Big,
+        _ => return Err(invalid("unknown endianness")),
+    };
+    let precision = match header[12] {
+        4 => Precision::Single,
+        8 => Precision::Double,
+        _ => return Err(invalid("unknown precision")),
+    };
+    let number_of_qubits = u32::from_le_bytes(header[13..17].try_into().unwrap()) as usize;
+    let expected_checksum = u64::from_le_bytes(header[17..25].try_into().unwrap());
+
+    let mut checksum = Checksum::new();
+    let elements = (0..kind.len(number_of_qubits)?)
+        .map(|_| read_element(&mut reader, precision, &mut checksum))
+        .collect::<Result<Vec<Complex>, BraKetError>>()?;
+    if checksum.0 != expected_checksum {
+        return Err(invalid("checksum does not match, the checkpoint is corrupted"));
+    }
+    Ok((number_of_qubits, endianness, elements))
+}
+
+/// Writes the elements as a NumPy .npy file (format version 1.0) of complex128 numbers with the given
+/// shape, in C order.
+pub fn save_npy<I: Iterator<Item = Complex>>(path: &Path, shape: &[usize], elements: I) -> Result<(), BraKetError> {
+    let mut writer = BufWriter::new(File::create(path).map_err(io_error)?);
+    let shape: Vec<String> = shape.iter().map(|n| format!("{},", n)).collect();
+    let mut header = format!("{{'descr': '<c16', 'fortran_order': False, 'shape': ({}), }}", shape.join(" "));
+    // the header is padded with spaces and ends in a newline so that the data is 64 byte aligned
+    let unpadded = NPY_MAGIC.len() + 4 + header.len() + 1;
+    header.push_str(&" ".repeat((64 - unpadded % 64) % 64));
+    header.push('\n');
+
+    writer.write_all(NPY_MAGIC).map_err(io_error)?;
+    writer.write_all(&[1, 0]).map_err(io_error)?;
+    writer.write_all(&(header.len() as u16).to_le_bytes()).map_err(io_error)?;
+    writer.write_all(header.as_bytes()).map_err(io_error)?;
+    let mut buffer = [0u8; 16];
+    for element in elements {
+        writer.write_all(element_bytes(&element, Precision::Double, &mut buffer)).map_err(io_error)?;
+    }
+    writer.flush().map_err(io_error)
+}
+
+/// The value of a key in the header of a .npy file, up to the next comma outside parentheses.
+fn header_value<'a>(header: &'a str, key: &str) -> Result<&'a str, BraKetError> {
+    let start = header.find(&format!("'{}':", key)).ok_or_else(|| invalid("incomplete .npy header"))? + key.len() + 3;
+    let rest = header[start..].trim_start();
+    let mut depth = 0;
+    for (i, c) in rest.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' | '}' if depth == 0 => return Ok(rest[..i].trim()),
+            _ => {}
+        }
+    }
+    Err(invalid("incomplete .npy header"))
+}
+
+/// Reads a NumPy .npy file of complex64 or complex128 numbers, returning its shape and its elements in C
+/// order.
+pub fn load_npy(path: &Path) -> Result<(Vec<usize>, Vec<Complex>), BraKetError> {
+    let mut reader = BufReader::new(File::open(path).map_err(io_error)?);
+    let mut preamble = [0u8; 8];
+    reader.read_exact(&mut preamble).map_err(io_error)?;
+    if &preamble[0..6] != NPY_MAGIC {
+        return Err(invalid("not a .npy file"));
+    }
+    let header_length = match preamble[6] {
+        1 => {
+            let mut length = [0u8; 2];
+            reader.read_exact(&mut length).map_err(io_error)?;
+            u16::from_le_bytes(length) as usize
+        }
+        2 | 3 => {
+            let mut length = [0u8; 4];
+            reader.read_exact(&mut length).map_err(io_error)?;
+            u32::from_le_bytes(length) as usize
+        }
+        _ => return Err(invalid("unsupported .npy version")),
+    };
+    let mut header = vec![0u8; header_length];
+    reader.read_exact(&mut header).map_err(io_error)?;
+    let header = String::from_utf8(header).map_err(|_| invalid("unreadable .npy header"))?;
+
+    let precision = match header_value(&header, "descr")?.trim_matches(|c| c == '\'' || c == '"') {
+        "<c16" => Precision::Double,
+        "<c8" => Precision::Single,
+        _ => return Err(invalid("only little endian complex .npy files can be loaded")),
+    };
+    let fortran_order = header_value(&header, "fortran_order")? == "True";
+    let shape = header_value(&header, "shape")?
+        .trim_matches(|c| c == '(' || c == ')')
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .map(|n| n.parse::<usize>().map_err(|_| invalid("unreadable .npy shape")))
+        .collect::<Result<Vec<usize>, BraKetError>>()?;
+
+    let mut checksum = Checksum::new();
+    let mut elements = (0..shape.iter().product())
+        .map(|_| read_element(&mut reader, precision, &mut checksum))
+        .collect::<Result<Vec<Complex>, BraKetError>>()?;
+    if fortran_order && shape.len() == 2 {
+        let (rows, columns) = (shape[0], shape[1]);
+        elements = (0..rows * columns).map(|n| elements[(n % columns) * rows + n / columns]).collect();
+    }
+    Ok((shape, elements))
+}
@@ -0,0 +1,84 @@
+use crate::error::BraKetError;
+use crate::macros::*;
+use crate::operations::*;
+use crate::program::Program;
+use crate::transpile::*;
+use crate::types::*;
+
+/// Applies the phase e^(i angle) to the state of the qubit being set.
+fn phase(qubit: usize, angle: Angle) -> Operations {
+    let u = Matrix2x2::from_diagonal(&nalgebra::vector![c!(1., 0.), c!(angle.cos(), angle.sin())]);
+    Operations::ArbitrarySingle(qubit, u)
+}
+
+/// The doubly controlled Z gate, from controlled S gates and CNOTs.
+fn controlled_cz(control: usize, qubit_0: usize, qubit_1: usize) -> Vec<Operations> {
+    let cs = controlled_matrix(&S);
+    vec![
+        Operations::ArbitaryTwo(qubit_1, qubit_0, cs),
+        Operations::CNOT(control, qubit_0),
+        Operations::ArbitaryTwo(qubit_1, qubit_0, cs.adjoint()),
+        Operations::CNOT(control, qubit_0),
+        Operations::ArbitaryTwo(qubit_1, control, cs),
+    ]
+}
+
+/// The Toffoli gate, flipping the target when both controls are set.
+fn controlled_cnot(control: usize, control_1: usize, target: usize) -> Vec<Operations> {
+    let mut gates = vec![Operations::H(target)];
+    gates.append(&mut controlled_cz(control, control_1, target));
+    gates.push(Operations::H(target));
+    gates
+}
+
+/// Decomposes the controlled version of a gate into single and two qubit gates. The decomposition is
+/// exact, including the phase the gate applies.
+///
+/// Returns an error if the operation is not unitary or acts on the control qubit.
+pub fn controlled_gates(gate: &Operations, control: usize) -> Result<Vec<Operations>, BraKetError> {
+    if which_qubits(gate).contains(&&control) {
+        return Err(BraKetError::RepeatedQubit(control));
+    }
+    let gates = match gate {
+        Operations::Barrier => vec![Operations::Barrier],
+        Operations::Subcircuit(subcircuit, qubits) => {
+            let mut gates = vec![];
+            for gate in subcircuit.expand(qubits).iter() {
+                gates.append(&mut controlled_gates(gate, control)?);
+            }
+            gates
+        }
+        Operations::X(qubit) => vec![Operations::CNOT(control, *qubit)],
+        Operations::Z(qubit) => vec![Operations::CZ(control, *qubit)],
+        Operations::CNOT(control_1, target) => controlled_cnot(control, *control_1, *target),
+        Operations::CZ(qubit_0, qubit_1) => controlled_cz(control, *qubit_0, *qubit_1),
+        Operations::SWAP(qubit_0, qubit_1) => {
+            let mut gates = vec![Operations::CNOT(*qubit_1, *qubit_0)];
+            gates.append(&mut controlled_cnot(control, *qubit_0, *qubit_1));
+            gates.push(Operations::CNOT(*qubit_1, *qubit_0));
+            gates
+        }
+        _ => {
+            if let Some((qubit, u)) = single_qubit_unitary(gate) {
+                return Ok(vec![Operations::ArbitaryTwo(qubit, control, controlled_matrix(&u))]);
+            }
+            let (qubit_0, qubit_1, u) = two_qubit_unitary(gate)
+                .ok_or(BraKetError::Unsupported("controlling measurements, resets and other non-unitary operations"))?;
+            // decompose into CZ and single qubit gates, which are correct up to a global phase that becomes
+            // a phase on the control
+            let native = two_qubit_to_native(0, 1, &u, &NativeGateSet::CZ);
+            let mut program = Program::new();
+            program.add_gates(native.clone());
+            let overlap: Complex = program.unitary(2)?.iter().zip(u.iter()).map(|(m, u)| m.conj() * u).sum();
+
+            let qubits = [qubit_0, qubit_1];
+            let mut gates = vec![];
+            for gate in native.iter() {
+                gates.append(&mut controlled_gates(&map_qubits(gate, |qubit| qubits[qubit]), control)?);
+            }
+            gates.push(phase(control, overlap.im.atan2(overlap.re)));
+            gates
+        }
+    };
+    Ok(gates)
+}
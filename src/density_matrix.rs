@@ -3,39 +3,62 @@ use std::mem::size_of_val;
 use itertools::iproduct;
 use log::debug;
 use nalgebra::ComplexField;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
 use rayon::prelude::*;
 
+use crate::information::Entropy;
 use crate::state_traits::StateTraits;
 
 use crate::helper_functions::*;
 use crate::types::*;
+use crate::basis::{BasisLabel, Endianness};
+use crate::checkpoint::{self, Kind, Precision};
+use crate::error::BraKetError;
 use crate::StateVector;
 use std::fmt;
+use std::path::Path;
 
 /// A density matrix describing an in general mixed quantum state.
-#[derive(Clone, Debug)]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "crate::serialization::DensityMatrixData", try_from = "crate::serialization::DensityMatrixData"))]
 pub struct DensityMatrix {
     pub number_of_qubits: usize,
     pub density_matrix: CMatrix,
+    /// The order of the qubits in the labels of basis states.
+    pub endianness: Endianness,
     density_matrix_pointer: DensityMatrixPointer<Complex>,
 }
 
 impl fmt::Display for DensityMatrix {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "density matrix:{}", self.density_matrix)
+        let labels: Vec<String> = (0..self.density_matrix.nrows())
+            .map(|index| self.endianness.bitstring(index, self.number_of_qubits))
+            .collect();
+        write!(f, "density matrix:{}basis: {}", self.density_matrix, labels.join(" "))
+    }
+}
+
+impl Clone for DensityMatrix {
+    fn clone(&self) -> Self {
+        let mut density_matrix = DensityMatrix::from_unchecked(self.density_matrix.clone());
+        density_matrix.endianness = self.endianness;
+        density_matrix
     }
 }
 
 impl StateTraits for DensityMatrix {
-    fn check_qubit_number(&self, qubits: Vec<&usize>) {
-        let required_number_of_qubits = qubits.last().unwrap();
-        let number_of_qubits = &self.number_of_qubits;
-        assert!(
-            required_number_of_qubits < &number_of_qubits,
-            "fewer qubits in the density matrix than required by program {} < {}",
-            required_number_of_qubits,
-            number_of_qubits
-        )
+    fn number_of_qubits(&self) -> usize {
+        self.number_of_qubits
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
+
+    fn check_qubit_number(&self, qubits: Vec<&usize>) -> Result<(), BraKetError> {
+        check_qubit_number(qubits, self.number_of_qubits)
     }
 
     fn reinitialise_all(&mut self) {
@@ -70,6 +93,34 @@ impl StateTraits for DensityMatrix {
         debug!("density matrix after:\n{}", self.density_matrix);
     }
 
+    /// Resets the target qubit with the channel rho -> |0><0| rho |0><0| + |0><1| rho |1><0|.
+    fn reset(&mut self, target: &usize) {
+        let swap = |x| swap_pair(x, target);
+        (0..1 << self.number_of_qubits)
+            .into_par_iter()
+            .step_by(2)
+            .for_each(|n: usize| unsafe {
+                (0..1 << self.number_of_qubits)
+                    .step_by(2)
+                    .for_each(|m: usize| {
+                        let rho_00 = self.read((swap(n), swap(m)));
+                        let rho_11 = self.read((swap(n + 1), swap(m + 1)));
+                        self.write((swap(n), swap(m)), rho_00 + rho_11);
+                        for (i, j) in [(0, 1), (1, 0), (1, 1)] {
+                            self.write((swap(i + n), swap(j + m)), Complex::new(0., 0.))
+                        }
+                    });
+            });
+    }
+
+    /// The density matrix describes the ensemble of outcomes and has no classical bits to write one into,
+    /// so the qubit is dephased as measure does and the bit is left alone. Sampling a program gives the
+    /// outcomes of its classical bits instead.
+    fn measure_into(&mut self, target: &usize, _bit: &usize) -> Result<(), BraKetError> {
+        self.measure(target);
+        Ok(())
+    }
+
     fn measure_all(&mut self) {
         (0..1 << self.number_of_qubits)
             .into_par_iter()
@@ -110,8 +161,29 @@ impl StateTraits for DensityMatrix {
         }
     }
 
-    fn single_qubit_kraus(&mut self, _target: &usize, _u: &Matrix2x2) {
-        todo!("not implemented yet");
+    fn single_qubit_kraus(&mut self, target: &usize, kraus: &[Matrix2x2]) -> Result<(), BraKetError> {
+        check_kraus(kraus)?;
+        let swap = |x| swap_pair(x, target);
+
+        (0..1 << &self.number_of_qubits)
+            .into_par_iter()
+            .step_by(2)
+            .for_each(|n: usize| unsafe {
+                let mut rho = Matrix2x2::zeros();
+                (0..1 << &self.number_of_qubits)
+                    .step_by(2)
+                    .for_each(|m: usize| {
+                        iproduct!(0..2, 0..2).for_each(|(i, j)| {
+                            rho[(i, j)] = self.read((swap(i + n), swap(j + m)))
+                        });
+
+                        rho = kraus.iter().map(|k| k * rho * k.adjoint()).sum();
+                        iproduct!(0..2, 0..2).for_each(|(i, j)| {
+                            self.write((swap(i + n), swap(j + m)), rho[(i, j)])
+                        });
+                    })
+            });
+        Ok(())
     }
 
     fn two_qubit_gate(&mut self, target: &usize, control: &usize, u: &Matrix4x4) {
@@ -141,56 +213,101 @@ impl StateTraits for DensityMatrix {
         debug!("density matrix after:\n{}", self.density_matrix);
     }
 
-    fn get_probability(&self, _target: &usize) -> Real {
-        todo!()
+    fn two_qubit_kraus(&mut self, target: &usize, control: &usize, kraus: &[Matrix4x4]) -> Result<(), BraKetError> {
+        check_kraus(kraus)?;
+        let swap = |x| swap_two_pairs(x, target, control);
+
+        (0..1 << &self.number_of_qubits)
+            .into_par_iter()
+            .step_by(4)
+            .for_each(|n: usize| unsafe {
+                let mut rho = Matrix4x4::zeros();
+                (0..1 << &self.number_of_qubits)
+                    .step_by(4)
+                    .for_each(|m: usize| {
+                        iproduct!(0..4, 0..4).for_each(|(i, j)| {
+                            rho[(i, j)] = self.read((swap(i + n), swap(j + m)))
+                        });
+
+                        rho = kraus.iter().map(|k| k * rho * k.adjoint()).sum();
+                        iproduct!(0..4, 0..4).for_each(|(i, j)| {
+                            self.write((swap(i + n), swap(j + m)), rho[(i, j)])
+                        });
+                    })
+            });
+        Ok(())
+    }
+
+    fn exact_channels(&self) -> bool {
+        true
     }
 
-    fn get_expectation(&self, _target: &usize) -> Real {
-        todo!()
+    /// The density matrix describes the ensemble of outcomes, so there is no recorded outcome to flip.
+    fn readout_error(&mut self, _target: &usize, _bit: Option<&usize>, _flip: &[Real; 2]) {}
+
+    fn get_probability(&self, target: &usize) -> Real {
+        let swap = |x| swap_pair(x, target);
+        (0..1 << self.number_of_qubits)
+            .into_par_iter()
+            .step_by(2)
+            .map(|n| unsafe { self.read((swap(n), swap(n))).re })
+            .sum()
+    }
+
+    fn get_expectation(&self, target: &usize) -> Real {
+        let p = self.get_probability(target);
+        p - (1. - p)
+    }
+
+    fn probabilities(&self) -> RVector {
+        let probabilities: Vec<Real> = (0..1 << self.number_of_qubits)
+            .into_par_iter()
+            .map(|n: usize| unsafe { self.read((n, n)).re })
+            .collect();
+        RVector::from_vec(probabilities)
     }
 }
 
 impl PartialEq for DensityMatrix {
     fn eq(&self, other: &Self) -> bool {
         let mut result = false;
-        if self.number_of_qubits == other.number_of_qubits {
-            if self.density_matrix.shape() == other.density_matrix.shape() {
-                let difference = &self.density_matrix - &other.density_matrix;
-                if difference.iter().all(|d| d.abs() < COMPARISON_PRECISION) {
-                    result = true;
-                }
+        if self.number_of_qubits == other.number_of_qubits
+            && self.density_matrix.shape() == other.density_matrix.shape()
+        {
+            let difference = &self.density_matrix - &other.density_matrix;
+            if difference.iter().all(|d| d.abs() < COMPARISON_PRECISION) {
+                result = true;
             }
         }
         result
     }
 }
 
-impl From<CMatrix> for DensityMatrix {
-    /// Create a density matrix from a complex matrix
-    fn from(mut density_matrix: CMatrix) -> Self {
-        let shape = density_matrix.shape();
-        assert_eq!(
-            shape.0, shape.1,
-            "density matrix not square {} =/= {}",
-            shape.0, shape.1
-        );
-        let number_of_qubits = log2(shape.0 as usize);
+impl TryFrom<CMatrix> for DensityMatrix {
+    type Error = BraKetError;
 
-        let density_matrix_pointer = DensityMatrixPointer::new(
-            &mut density_matrix[(0, 0)], shape);
-
-        DensityMatrix {
-            number_of_qubits,
-            density_matrix,
-            density_matrix_pointer,
+    /// Create a density matrix from a complex matrix, which must be square with a power of two dimension and
+    /// unit trace.
+    fn try_from(density_matrix: CMatrix) -> Result<Self, BraKetError> {
+        let (rows, columns) = density_matrix.shape();
+        if rows != columns {
+            return Err(BraKetError::NotSquare { rows, columns });
         }
-
+        if !rows.is_power_of_two() {
+            return Err(BraKetError::NotPowerOfTwo(rows));
+        }
+        let trace = density_matrix.trace();
+        if (trace - Complex::new(1., 0.)).abs() > COMPARISON_PRECISION {
+            return Err(BraKetError::NotNormalised(trace.re));
+        }
+        Ok(DensityMatrix::from_unchecked(density_matrix))
     }
 }
 
 impl From<StateVector> for DensityMatrix {
     fn from(state_vector: StateVector) -> Self {
-        let density_matrix = DensityMatrix::new(state_vector.number_of_qubits);
+        let mut density_matrix = DensityMatrix::new(state_vector.number_of_qubits);
+        density_matrix.endianness = state_vector.endianness;
         (0..1 << &state_vector.number_of_qubits)
             .into_par_iter()
             .for_each(|n: usize| {
@@ -207,47 +324,175 @@ impl From<StateVector> for DensityMatrix {
 }
 
 impl DensityMatrix {
+    /// The state |00...0><00...0| of number_of_qubits qubits.
+    ///
+    /// # Panics
+    /// If the density matrix is too large to be allocated, see try_new.
     pub fn new(number_of_qubits: usize) -> DensityMatrix {
-        let mut density_matrix = {
-            // calculating the hilbert dim
-            let hilbert_dim = 1 << number_of_qubits;
-            // printing the size of the density matrix to be created
-            {
-                let density_matrix_footprint = (hilbert_dim << 2) * size_of_val(&Complex::new(0., 0.));
-                let bytes_to_gigabyte: usize = 2 << 33;
-                debug!(
-                    "Allocating density matrix of size: {:.4} Gb",
-                    (density_matrix_footprint as f32) / (bytes_to_gigabyte as f32)
-                );
-            }
+        DensityMatrix::try_new(number_of_qubits).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// The state |00...0><00...0| of number_of_qubits qubits, failing if there is not enough memory for
+    /// its 4^number_of_qubits elements.
+    pub fn try_new(number_of_qubits: usize) -> Result<DensityMatrix, BraKetError> {
+        let hilbert_dim = 1usize.checked_shl(number_of_qubits.try_into().unwrap_or(u32::MAX));
+        let length = hilbert_dim.and_then(|dim| dim.checked_mul(dim));
+        // printing the size of the density matrix to be created
+        if let Some(length) = length {
+            let density_matrix_footprint = length as f32 * size_of_val(&Complex::new(0., 0.)) as f32;
+            debug!("Allocating density matrix of size: {:.4} Gb", density_matrix_footprint / (1u64 << 30) as f32);
+        }
+        let elements = try_zeros(length, number_of_qubits)?;
+        let dim = 1 << number_of_qubits;
+        let mut density_matrix = CMatrix::from_vec(dim, dim, elements);
+        // setting the (0, 0) element to 1 to represent initialisation in the |000...> state
+        density_matrix[(0, 0)] = Complex::new(1., 0.);
+        Ok(DensityMatrix::from_unchecked(density_matrix))
+    }
+
+    /// A density matrix wrapping the matrix as it is.
+    pub(crate) fn from_unchecked(mut density_matrix: CMatrix) -> DensityMatrix {
+        let shape = density_matrix.shape();
+        let number_of_qubits = log2(shape.0);
 
-            // creating the density matrix
-            let mut rho = CMatrix::from_element(hilbert_dim, hilbert_dim, Complex::new(0., 0.));
-            // setting the (0, 0) element to 1 to represent initialisation in the |000...> state
-            rho[(0, 0)] = Complex::new(1., 0.);
-            rho
-        };
         let density_matrix_pointer = DensityMatrixPointer::new(
-            &mut density_matrix[(0, 0)],
-            (1 << &number_of_qubits, 1 << &number_of_qubits),
-        );
+            &mut density_matrix[(0, 0)], shape);
 
         DensityMatrix {
             number_of_qubits,
             density_matrix,
+            endianness: Endianness::default(),
             density_matrix_pointer,
         }
     }
 
+    /// The computational basis state with the given index, qubit k being bit k of the index.
+    pub fn basis(number_of_qubits: usize, index: usize) -> Result<DensityMatrix, BraKetError> {
+        Ok(DensityMatrix::from(StateVector::basis(number_of_qubits, index)?))
+    }
+
+    /// The computational basis state written as a little endian bitstring, the rightmost character being
+    /// qubit 0.
+    pub fn from_bitstring(bitstring: &str) -> Result<DensityMatrix, BraKetError> {
+        Ok(DensityMatrix::from(StateVector::from_bitstring(bitstring)?))
+    }
+
+    /// The product state with qubit k at the point (theta, phi) of the Bloch sphere given by angles[k], see
+    /// StateVector::product.
+    pub fn product(angles: &[(Angle, Angle)]) -> DensityMatrix {
+        DensityMatrix::from(StateVector::product(angles))
+    }
+
+    /// A mixed state drawn from the Hilbert-Schmidt measure, reproducibly for a given seed. It is G G^dagger
+    /// normalised, for G a matrix of complex gaussian entries.
+    pub fn random(number_of_qubits: usize, seed: u64) -> DensityMatrix {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let dim = 1 << number_of_qubits;
+        let ginibre = CMatrix::from_fn(dim, dim, |_, _| complex_gaussian(&mut rng));
+        let density_matrix = &ginibre * ginibre.adjoint();
+        let trace = density_matrix.trace();
+        DensityMatrix::from_unchecked(density_matrix / trace)
+    }
+
+    /// The tensor product of the two states, the qubits of other following those of self as in
+    /// Program::tensor.
+    pub fn tensor(&self, other: &DensityMatrix) -> DensityMatrix {
+        let mut density_matrix = DensityMatrix::from_unchecked(other.density_matrix.kronecker(&self.density_matrix));
+        density_matrix.endianness = self.endianness;
+        density_matrix
+    }
+
+    /// The elements of the density matrix row by row.
+    fn rows(&self) -> impl Iterator<Item = Complex> + '_ {
+        let dim = self.density_matrix.nrows();
+        (0..dim).flat_map(move |row| (0..dim).map(move |column| self.density_matrix[(row, column)]))
+    }
+
+    /// Saves the state to a binary checkpoint file, exactly, see save_with_precision.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        self.save_with_precision(path, Precision::Double)
+    }
+
+    /// Saves the state to a binary checkpoint file with a header holding the number of qubits, the
+    /// endianness, the precision and a checksum of the elements, which are written row by row.
+    pub fn save_with_precision<P: AsRef<Path>>(&self, path: P, precision: Precision) -> Result<(), BraKetError> {
+        checkpoint::save(path.as_ref(), Kind::DensityMatrix, self.number_of_qubits, self.endianness, precision, self.rows())
+    }
+
+    /// Loads a state saved with save, checking the checksum and that the trace is one.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<DensityMatrix, BraKetError> {
+        let (number_of_qubits, endianness, elements) = checkpoint::load(path.as_ref(), Kind::DensityMatrix)?;
+        let dim = 1 << number_of_qubits;
+        let mut density_matrix = DensityMatrix::try_from(CMatrix::from_row_slice(dim, dim, &elements))?;
+        density_matrix.endianness = endianness;
+        Ok(density_matrix)
+    }
+
+    /// Saves the density matrix as a two dimensional complex128 NumPy array.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        let dim = self.density_matrix.nrows();
+        checkpoint::save_npy(path.as_ref(), &[dim, dim], self.rows())
+    }
+
+    /// Loads the density matrix from a two dimensional complex NumPy array.
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<DensityMatrix, BraKetError> {
+        let (shape, elements) = checkpoint::load_npy(path.as_ref())?;
+        if shape.len() != 2 {
+            return Err(BraKetError::InvalidFile(format!("expected a matrix but found an array of shape {:?}", shape)));
+        }
+        DensityMatrix::try_from(CMatrix::from_row_slice(shape[0], shape[1], &elements))
+    }
+
+    /// Sets the order of the qubits in the labels of basis states.
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// The element of the density matrix between two basis states, labelled by bitstrings or integers in the
+    /// endianness of the state.
+    pub fn element<L: BasisLabel>(&self, row: L, column: L) -> Result<Complex, BraKetError> {
+        let (row, column) = (row.index(self.number_of_qubits, self.endianness)?, column.index(self.number_of_qubits, self.endianness)?);
+        Ok(self.density_matrix[(row, column)])
+    }
+
+    /// Traces out the given qubits, the remaining qubits keeping their order.
+    pub fn partial_trace(&self, qubits: &[usize]) -> Result<DensityMatrix, BraKetError> {
+        check_qubits(qubits, self.number_of_qubits)?;
+        let kept: Vec<usize> = (0..self.number_of_qubits).filter(|qubit| !qubits.contains(qubit)).collect();
+        let kept_indices = scattered_indices(&kept);
+        let environment_indices = scattered_indices(qubits);
+
+        let rows: Vec<Vec<Complex>> = kept_indices
+            .par_iter()
+            .map(|j| {
+                kept_indices
+                    .iter()
+                    .map(|k| environment_indices.iter().map(|e| unsafe { self.read((j | e, k | e)) }).sum())
+                    .collect()
+            })
+            .collect();
+        let dim = kept_indices.len();
+        let mut density_matrix = DensityMatrix::from_unchecked(CMatrix::from_fn(dim, dim, |j, k| rows[j][k]));
+        density_matrix.endianness = self.endianness;
+        Ok(density_matrix)
+    }
+
     pub fn is_pure(&self) -> bool {
-        let trace = (&self.density_matrix * &self.density_matrix).trace();
-        trace.re > (1. - COMPARISON_PRECISION)
+        self.purity() > (1. - COMPARISON_PRECISION)
     }
 
+    /// Writes an element of the density matrix through the shared pointer.
+    ///
+    /// # Safety
+    /// No other thread may access the same element concurrently.
     pub unsafe fn write(&self, indices: (usize, usize), value: Complex) {
         self.density_matrix_pointer.write(indices, value)
     }
 
+    /// Reads an element of the density matrix through the shared pointer.
+    ///
+    /// # Safety
+    /// No other thread may write to the same element concurrently.
     pub unsafe fn read(&self, indices: (usize, usize)) -> Complex {
         self.density_matrix_pointer.read(indices)
     }
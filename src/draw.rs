@@ -1,127 +1,369 @@
-use crate::operations::Operations;
-use crate::{PI, Program};
-use itertools::zip;
+use std::fmt;
 
-// Generate qasm file and input into python circuit generator https://www.media.mit.edu/quanta/qasm2circ/
+use crate::metrics::drawing_layers;
+use crate::operations::{gate_name, Operations};
+use crate::program::Program;
+use crate::qasm::unused_name;
+use crate::types::{Angle, Real, PI};
 
-pub fn draw_circuit(program: &Program) {
-    let qubits = program.which_qubits();
-    let mut circuit: Vec<String> = vec![String::from("qubit "); qubits.len()];
+/// What is drawn on a wire where an operation acts on it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Symbol {
+    /// A control qubit.
+    Control,
+    /// The target of a CNOT.
+    Target,
+    /// One end of a SWAP.
+    Swap,
+    /// A box with the name of the gate and its angles. Gates whose qubits play different roles number the
+    /// box on each qubit with the position of the qubit in the gate.
+    Gate { name: String, angles: Vec<Angle>, index: Option<usize> },
+    /// A measurement in the given basis, storing the outcome in a (classical wire, position) if it is
+    /// measured into a classical bit.
+    Measure(&'static str, Option<(usize, usize)>),
+    Reset,
+    Barrier,
+}
+
+/// An operation as it is drawn, the symbols on the rows of the wires they are on. The symbols of a
+/// connected element are joined by a vertical line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub symbols: Vec<(usize, Symbol)>,
+    pub connected: bool,
+}
 
-    for (n, string) in zip(qubits.to_owned(), circuit.iter_mut()) {
-        string.push_str(format!("{} : ---", n).as_str())
+impl Element {
+    fn single(qubit: usize, symbol: Symbol) -> Element {
+        Element { symbols: vec![(qubit, symbol)], connected: false }
     }
 
-    for gate in program.gates.iter() {
-        for (n, string) in zip(qubits.to_owned(), circuit.iter_mut()) {
-            let m: String = plot_gate(gate, n) + "---";
-            string.push_str(&m);
-        }
+    fn gate(qubit: usize, name: &str, angles: Vec<Angle>) -> Element {
+        Element::single(qubit, Symbol::Gate { name: name.to_owned(), angles, index: None })
+    }
+
+    fn controlled(control: usize, target: usize, symbol: Symbol) -> Element {
+        Element { symbols: vec![(control, Symbol::Control), (target, symbol)], connected: true }
+    }
+
+    /// A gate on several qubits drawn as a box on each of them, numbered if the qubits are not interchangeable.
+    fn boxes(qubits: &[usize], name: &str, angles: Vec<Angle>, numbered: bool) -> Element {
+        let symbols = qubits
+            .iter()
+            .enumerate()
+            .map(|(n, qubit)| (*qubit, Symbol::Gate { name: name.to_owned(), angles: angles.clone(), index: numbered.then_some(n) }))
+            .collect();
+        Element { symbols, connected: true }
+    }
+
+    fn every(number_of_qubits: usize, symbol: Symbol) -> Element {
+        Element { symbols: (0..number_of_qubits).map(|qubit| (qubit, symbol.clone())).collect(), connected: false }
     }
 
-    for string in circuit.iter_mut() {
-        string.push_str("||")
+    /// The classical row the element stores a measurement outcome in, if any, counting the rows of the
+    /// quantum wires first.
+    pub fn classical_row(&self, number_of_qubits: usize) -> Option<usize> {
+        self.symbols.iter().find_map(|(_, symbol)| match symbol {
+            Symbol::Measure(_, Some((wire, _))) => Some(number_of_qubits + wire),
+            _ => None,
+        })
     }
-    println!("_____________________________");
-    println!("****** Quantum Circuit ******");
-    println!("");
-    for line in circuit.iter_mut() {
-        println!("{:#?}", line);
+
+    /// The first and last rows the element is drawn over, including the classical wire of a measurement.
+    ///
+    /// # Panics
+    /// If the element has no symbols, which Layout leaves out.
+    pub fn span(&self, number_of_qubits: usize) -> (usize, usize) {
+        let rows: Vec<usize> = self.symbols.iter().map(|(row, _)| *row).chain(self.classical_row(number_of_qubits)).collect();
+        (*rows.iter().min().unwrap(), *rows.iter().max().unwrap())
     }
-    println!("");
-    println!("*****************************");
-    println!("_____________________________");
 }
-//
-fn plot_gate(gate: &Operations, qubit_index: &usize) -> String {
+
+/// The symbols a gate is drawn with. bit_position gives the classical wire and position of a classical bit.
+fn element<F: Fn(usize) -> (usize, usize)>(gate: &Operations, number_of_qubits: usize, bit_position: F) -> Element {
+    use Operations::*;
+    let box_named = |name: &str| Symbol::Gate { name: name.to_owned(), angles: vec![], index: None };
+    let box_with_angle = |name: &str, angle: Angle| Symbol::Gate { name: name.to_owned(), angles: vec![angle], index: None };
     match gate {
-        Operations::Measure(qubit) => {
-            let m: String = format!("{:-<3}", "M");
-            return_string(qubit_index, *qubit, m)
-        }
+        Barrier => Element::every(number_of_qubits, Symbol::Barrier),
+        Measure(qubit) => Element::single(*qubit, Symbol::Measure("M", None)),
+        MeasureInto(qubit, bit) => Element::single(*qubit, Symbol::Measure("M", Some(bit_position(*bit)))),
+        MeasureX(qubit) => Element::single(*qubit, Symbol::Measure("MX", None)),
+        MeasureY(qubit) => Element::single(*qubit, Symbol::Measure("MY", None)),
+        Reset(qubit) => Element::single(*qubit, Symbol::Reset),
+        MeasureAll => Element::every(number_of_qubits, Symbol::Measure("M", None)),
+        ResetAll => Element::every(number_of_qubits, Symbol::Reset),
 
-        Operations::X(qubit) => {
-            let m: String = format!("{:-<1}", "X");
-            return_string(qubit_index, *qubit, m)
-        }
-        Operations::Y(qubit) => {
-            let m: String = format!("{:-<1}", "Y");
-            return_string(qubit_index, *qubit, m)
-        }
-        Operations::Z(qubit) => {
-            let m: String = format!("{:-<1}", "Z");
-            return_string(qubit_index, *qubit, m)
+        X(qubit) | Y(qubit) | Z(qubit) | H(qubit) | S(qubit) | Sdg(qubit) | T(qubit) | Tdg(qubit) | SX(qubit) | SXdg(qubit) => {
+            Element::gate(*qubit, gate_name(gate), vec![])
         }
+        ArbitrarySingle(qubit, _) => Element::gate(*qubit, "U", vec![]),
+        RX(qubit, angle) | RY(qubit, angle) | RZ(qubit, angle) | U1(qubit, angle) => Element::gate(*qubit, gate_name(gate), vec![*angle]),
+        Phase(qubit, lambda) => Element::gate(*qubit, "P", vec![*lambda]),
+        R(qubit, phi, theta, omega) => Element::gate(*qubit, "R", vec![*phi, *theta, *omega]),
+        U2(qubit, phi, lambda) => Element::gate(*qubit, "U2", vec![*phi, *lambda]),
+        U3(qubit, theta, phi, lambda) => Element::gate(*qubit, "U3", vec![*theta, *phi, *lambda]),
 
-        Operations::RX(qubit, angle) => {
-            let m: String = format!("RX({:.*} pi)", 1, angle / PI);
-            return_string(qubit_index, *qubit, m)
-        }
-        Operations::RY(qubit, angle) => {
-            let m: String = format!("RY({:.*} pi)", 1, angle / PI);
-            return_string(qubit_index, *qubit, m)
+        CNOT(control, target) => Element::controlled(*control, *target, Symbol::Target),
+        CZ(control, target) => Element::controlled(*control, *target, Symbol::Control),
+        CY(control, target) => Element::controlled(*control, *target, box_named("Y")),
+        CH(control, target) => Element::controlled(*control, *target, box_named("H")),
+        CRX(control, target, angle) => Element::controlled(*control, *target, box_with_angle("RX", *angle)),
+        CRY(control, target, angle) => Element::controlled(*control, *target, box_with_angle("RY", *angle)),
+        CRZ(control, target, angle) => Element::controlled(*control, *target, box_with_angle("RZ", *angle)),
+        CPhase(control, target, angle) => Element::controlled(*control, *target, box_with_angle("P", *angle)),
+        SWAP(qubit_0, qubit_1) => Element { symbols: vec![(*qubit_0, Symbol::Swap), (*qubit_1, Symbol::Swap)], connected: true },
+        ISWAP(qubit_0, qubit_1) | SISWAP(qubit_0, qubit_1) => Element::boxes(&[*qubit_0, *qubit_1], gate_name(gate), vec![], false),
+        RXX(qubit_0, qubit_1, theta) | RYY(qubit_0, qubit_1, theta) | RZZ(qubit_0, qubit_1, theta) => {
+            Element::boxes(&[*qubit_0, *qubit_1], gate_name(gate), vec![*theta], false)
         }
-        Operations::RZ(qubit, angle) => {
-            let m: String = format!("RZ({:.*})", 1, angle / PI);
-            return_string(qubit_index, *qubit, m)
-        }
-        Operations::H(qubit) => {
-            let m: String = format!("{:-<1}", "H");
-            return_string(qubit_index, *qubit, m)
+        FSim(qubit_0, qubit_1, theta, phi) => Element::boxes(&[*qubit_0, *qubit_1], "FSim", vec![*theta, *phi], false),
+        Givens(qubit_0, qubit_1, theta) => Element::boxes(&[*qubit_0, *qubit_1], "Givens", vec![*theta], true),
+        ECR(qubit_0, qubit_1) => Element::boxes(&[*qubit_0, *qubit_1], "ECR", vec![], true),
+        ArbitaryTwo(qubit_0, qubit_1, _) => Element::boxes(&[*qubit_0, *qubit_1], "U", vec![], true),
+        Subcircuit(subcircuit, qubits) => Element::boxes(qubits, &subcircuit.name, vec![], qubits.len() > 1),
+    }
+}
+
+/// A circuit arranged for drawing: a wire for each qubit and classical register, and the operations in
+/// columns. Each layer of the program is split into as few columns as possible such that the elements in
+/// a column, together with their vertical connections, do not overlap.
+pub struct Layout {
+    /// The labels of the quantum wires, one per qubit.
+    pub qubits: Vec<String>,
+    /// The labels and sizes of the classical wires, one per classical register, followed by a wire for the
+    /// classical bits outside the registers if any is measured into.
+    pub classical: Vec<(String, usize)>,
+    pub columns: Vec<Vec<Element>>,
+}
+
+impl Layout {
+    pub fn new(program: &Program) -> Layout {
+        let number_of_qubits = program.number_of_qubits();
+        let qubits = (0..number_of_qubits)
+            .map(|qubit| match program.qubit_name(qubit) {
+                Some((name, position)) => format!("{}[{}]", name, position),
+                None => format!("q{}", qubit),
+            })
+            .collect();
+
+        let mut classical: Vec<(String, usize)> = program.classical_registers.iter().map(|r| (r.name.to_owned(), r.len())).collect();
+        let loose_bits = program.gates.iter().filter_map(|gate| match gate {
+            Operations::MeasureInto(_, bit) if program.classical_bit_name(*bit).is_none() => Some(*bit),
+            _ => None,
+        });
+        if let Some(last) = loose_bits.max() {
+            classical.push((unused_name(program, "c"), last + 1));
         }
-        Operations::R(qubit, omega, theta, phi) => {
-            let m: String = format!("R.{:.*},{:.*},{:.*}", 0, omega, 0, theta, 0, phi);
-            return_string(qubit_index, *qubit, m)
+        let bit_position = |bit: usize| match program.classical_bit_name(bit) {
+            Some((name, position)) => (program.classical_registers.iter().position(|r| r.name == name).unwrap(), position),
+            None => (program.classical_registers.len(), bit),
+        };
+
+        let mut columns: Vec<Vec<Element>> = vec![];
+        // barriers are drawn in columns of their own and subcircuits as single boxes
+        for layer in drawing_layers(&program.gates) {
+            let mut layer_columns: Vec<Vec<Element>> = vec![];
+            for gate in layer.into_iter().map(|n| &program.gates[n]) {
+                let element = element(gate, number_of_qubits, bit_position);
+                // barriers and measurements of every qubit of a program without qubits have nothing to draw
+                if element.symbols.is_empty() {
+                    continue;
+                }
+                let (first, last) = element.span(number_of_qubits);
+                let free = |column: &&mut Vec<Element>| {
+                    column.iter().all(|other| {
+                        let (other_first, other_last) = other.span(number_of_qubits);
+                        last < other_first || other_last < first
+                    })
+                };
+                match layer_columns.iter_mut().find(free) {
+                    Some(column) => column.push(element),
+                    None => layer_columns.push(vec![element]),
+                }
+            }
+            columns.append(&mut layer_columns);
         }
-        Operations::ArbitrarySingle(qubit, _u) => {
-            let m: String = format!("{:-<1}", "ArbU");
-            return_string(qubit_index, *qubit, m)
+        Layout { qubits, classical, columns }
+    }
+
+    /// The number of rows, the quantum wires followed by the classical wires.
+    pub fn rows(&self) -> usize {
+        self.qubits.len() + self.classical.len()
+    }
+}
+
+/// Writes an angle as a multiple of pi if it is one with a denominator of at most 16, and as a decimal
+/// otherwise. pi is the way pi is written.
+pub fn format_angle(angle: Angle, pi: &str) -> String {
+    for denominator in 1..=16 {
+        let multiple = angle / PI * denominator as Real;
+        if (multiple - multiple.round()).abs() > 1e-9 {
+            continue;
         }
-        Operations::CZ(control, target) => {
-            let m: String = "CZ".to_owned();
-            return_two_gate_string(qubit_index, *control, *target, m)
+        let numerator = multiple.round() as i64;
+        let numerator = match numerator {
+            0 => return "0".to_owned(),
+            1 => pi.to_owned(),
+            -1 => format!("-{}", pi),
+            _ => format!("{}{}", numerator, pi),
+        };
+        return match denominator {
+            1 => numerator,
+            _ => format!("{}/{}", numerator, denominator),
+        };
+    }
+    let decimal = format!("{:.3}", angle);
+    decimal.trim_end_matches('0').trim_end_matches('.').to_owned()
+}
+
+/// The text of a symbol, drawn centred on its wire.
+fn symbol_text(symbol: &Symbol) -> String {
+    match symbol {
+        Symbol::Control => "●".to_owned(),
+        Symbol::Target => "⊕".to_owned(),
+        Symbol::Swap => "×".to_owned(),
+        Symbol::Reset => "|0>".to_owned(),
+        Symbol::Barrier => "░".to_owned(),
+        Symbol::Measure(basis, _) => format!("┤{}├", basis),
+        Symbol::Gate { name, angles, index } => format!("┤{}├", gate_label(name, angles, *index, "π")),
+    }
+}
+
+/// The label of a gate box: the name, the angles in brackets if there are any and the index of a numbered box.
+pub fn gate_label(name: &str, angles: &[Angle], index: Option<usize>, pi: &str) -> String {
+    let mut label = name.to_owned();
+    if !angles.is_empty() {
+        let angles: Vec<String> = angles.iter().map(|angle| format_angle(*angle, pi)).collect();
+        label.push_str(&format!("({})", angles.join(",")));
+    }
+    if let Some(index) = index {
+        label.push_str(&format!(":{}", index));
+    }
+    label
+}
+
+/// Centres text in a cell of the given width, filling the rest with fill.
+fn centre(text: &str, width: usize, fill: char) -> String {
+    let padding = width - text.chars().count();
+    let left = padding / 2;
+    format!("{}{}{}", fill.to_string().repeat(left), text, fill.to_string().repeat(padding - left))
+}
+
+/// Draws a column as two lines per row, the wire and the gap below it.
+fn draw_column(column: &[Element], number_of_qubits: usize, rows: usize) -> Vec<String> {
+    let mut texts: Vec<Option<String>> = vec![None; rows];
+    let mut labels: Vec<Option<String>> = vec![None; rows];
+    for element in column.iter() {
+        for (row, symbol) in element.symbols.iter() {
+            texts[*row] = Some(symbol_text(symbol));
+            if let Symbol::Measure(_, Some((wire, position))) = symbol {
+                labels[number_of_qubits + wire] = Some(position.to_string());
+            }
         }
-        Operations::CNOT(control, target) => {
-            let m: String = "CNOT".to_owned();
-            return_two_gate_string(qubit_index, *control, *target, m)
+    }
+    let width = texts.iter().chain(labels.iter()).flatten().map(|text| text.chars().count()).max().unwrap_or(1) + 2;
+
+    let mut lines = vec![];
+    for row in 0..rows {
+        let quantum = row < number_of_qubits;
+        let fill = if quantum { '─' } else { '═' };
+        // the element drawn over this row, and whether its vertical line reaches the gap below the row
+        let element = column.iter().find(|element| {
+            let (first, last) = element.span(number_of_qubits);
+            first <= row && row <= last
+        });
+        let (crossing, below) = match element {
+            Some(element) => {
+                let (_, last) = element.span(number_of_qubits);
+                let measurement = element.classical_row(number_of_qubits).is_some();
+                let crossing = match (measurement, quantum, row == last) {
+                    (true, true, _) => '╫',
+                    (true, false, true) => '╩',
+                    (true, false, false) => '╬',
+                    (false, _, _) => '┼',
+                };
+                let below = match (measurement, row < last) {
+                    (_, false) => ' ',
+                    (true, true) => '║',
+                    (false, true) if element.connected => '│',
+                    (false, true) => ' ',
+                };
+                (crossing, below)
+            }
+            None => (fill, ' '),
+        };
+        lines.push(match &texts[row] {
+            Some(text) => centre(text, width, fill),
+            None => centre(&crossing.to_string(), width, fill),
+        });
+        lines.push(match &labels[row] {
+            Some(label) => centre(label, width, ' '),
+            None => centre(&below.to_string(), width, ' '),
+        });
+    }
+    lines
+}
+
+/// Draws the layout as text, wrapping the circuit onto several blocks if it is wider than width characters.
+pub fn draw_text(layout: &Layout, width: usize) -> String {
+    let number_of_qubits = layout.qubits.len();
+    let rows = layout.rows();
+    if rows == 0 {
+        return String::new();
+    }
+    let labels: Vec<&str> = layout.qubits.iter().map(|label| label.as_str()).chain(layout.classical.iter().map(|(label, _)| label.as_str())).collect();
+    let label_width = labels.iter().map(|label| label.chars().count()).max().unwrap_or(0);
+    let prefix_width = label_width + 3;
+
+    let columns: Vec<Vec<String>> = layout.columns.iter().map(|column| draw_column(column, number_of_qubits, rows)).collect();
+    let mut blocks: Vec<Vec<&Vec<String>>> = vec![vec![]];
+    let mut block_width = 0;
+    for column in columns.iter() {
+        let column_width = column[0].chars().count();
+        let block = blocks.last_mut().unwrap();
+        if !block.is_empty() && prefix_width + block_width + column_width + 2 > width {
+            blocks.push(vec![column]);
+            block_width = column_width;
+        } else {
+            block.push(column);
+            block_width += column_width;
         }
-        Operations::SISWAP(control, target) => {
-            let m: String = "SSwap".to_owned();
-            return_two_gate_string(qubit_index, *control, *target, m)
+    }
+
+    let mut lines: Vec<String> = vec![];
+    for (n, block) in blocks.iter().enumerate() {
+        if n > 0 {
+            lines.push(String::new());
         }
-        Operations::ArbitaryTwo(control, target, _u) => {
-            let m: String = "ArbU".to_owned();
-            return_two_gate_string(qubit_index, *control, *target, m)
+        for line in 0..2 * rows {
+            let row = line / 2;
+            let wire = line % 2 == 0;
+            let fill = if row < number_of_qubits { '─' } else { '═' };
+            let mut text = match wire {
+                true => format!("{:>width$}: {}", labels[row], if n > 0 { '«' } else { fill }, width = label_width),
+                false => " ".repeat(prefix_width),
+            };
+            for column in block.iter() {
+                text.push_str(&column[line]);
+            }
+            if wire {
+                text.push(if n + 1 < blocks.len() { '»' } else { fill });
+            }
+            lines.push(text.trim_end().to_owned());
         }
-        _ => {
-            let default: String = "Other__".to_owned();
-            default
+        while lines.last().is_some_and(|line| line.is_empty()) {
+            lines.pop();
         }
     }
+    lines.join("\n") + "\n"
 }
 
-fn return_string(indexed_qubit: &usize, gate_qubit: usize, message: String) -> String {
-    if *indexed_qubit == gate_qubit {
-        return message;
+/// Draws the program as text, with a wire for each qubit and classical register and the gates packed into
+/// as few columns as possible. The circuit is wrapped at the width given to the formatter, for instance
+/// format!("{:120}", program), or at 80 characters by default.
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", draw_text(&Layout::new(self), f.width().unwrap_or(80)))
     }
-    let default: String = format!("{:-<3}", "-");
-    return default;
 }
-
-fn return_two_gate_string(
-    indexed_qubit: &usize,
-    control: usize,
-    target: usize,
-    message: String,
-) -> String {
-    if *indexed_qubit == control {
-        let m: String = format!("{:->1}_C", message);
-        return m;
-    } else if *indexed_qubit == target {
-        let m: String = format!("{:->1}_T", message);
-        return m;
-    }
-    let default: String = format!("{:-<1}", "-");
-    return default;
-}
-
@@ -0,0 +1,102 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::registers::{MAX_CLASSICAL_BITS, MAX_REGISTER_SIZE};
+use crate::types::Real;
+use crate::validation::ValidationIssue;
+
+/// The errors returned when a program cannot be run on a state, a state cannot be constructed or a
+/// result is not available.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BraKetError {
+    /// The program acts on a qubit beyond those of the state.
+    NotEnoughQubits { required: usize, available: usize },
+    /// A state of so many qubits that there is not enough memory for it.
+    TooManyQubits(usize),
+    /// A qubit which is not in the state.
+    InvalidQubit { qubit: usize, number_of_qubits: usize },
+    /// A qubit given more than once where distinct qubits are required.
+    RepeatedQubit(usize),
+    /// A qubit whose measurement outcome is read before it has been measured.
+    QubitNotMeasured(usize),
+    /// A classical bit which is read before a measurement has been stored in it.
+    BitNotMeasured(usize),
+    /// A classical bit beyond the most a program may use.
+    InvalidBit(usize),
+    /// A register read from a file which holds more qubits or bits than MAX_REGISTER_SIZE.
+    RegisterTooLarge(usize),
+    /// A label of a basis state which is not a bitstring or integer of the right size.
+    InvalidBasisLabel { label: String, number_of_qubits: usize },
+    /// A vector or matrix whose dimension is not a power of two.
+    NotPowerOfTwo(usize),
+    /// A vector or matrix whose dimension does not match the number of qubits it is given for.
+    WrongDimension { expected: usize, found: usize },
+    /// A density matrix which is not square.
+    NotSquare { rows: usize, columns: usize },
+    /// A state vector whose norm squared, or a density matrix whose trace, is not one.
+    NotNormalised(Real),
+    /// Kraus operators which do not form a channel, as the sum of K^dagger K over them is not the identity.
+    NotTracePreserving,
+    /// A probability which is not between 0 and 1.
+    InvalidProbability(Real),
+    /// A confusion matrix whose columns are not probability distributions.
+    NotStochastic,
+    /// A matrix which has no inverse.
+    NotInvertible,
+    /// An operation the state cannot perform.
+    Unsupported(&'static str),
+    /// A file could not be read or written.
+    Io(String),
+    /// A file is not a valid checkpoint or .npy file of the expected state.
+    InvalidFile(String),
+    /// An OpenQASM program which cannot be read, with the line the problem is on.
+    InvalidQasm { line: usize, message: String },
+    /// The problems Program::validate found with a program.
+    InvalidProgram(Vec<ValidationIssue>),
+}
+
+impl fmt::Display for BraKetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BraKetError::NotEnoughQubits { required, available } => {
+                write!(f, "the program requires {} qubits but the state has {}", required, available)
+            }
+            BraKetError::TooManyQubits(number_of_qubits) => {
+                write!(f, "there is not enough memory for a state of {} qubits", number_of_qubits)
+            }
+            BraKetError::InvalidQubit { qubit, number_of_qubits } => {
+                write!(f, "qubit {} is not in the state of {} qubits", qubit, number_of_qubits)
+            }
+            BraKetError::RepeatedQubit(qubit) => write!(f, "qubit {} is repeated", qubit),
+            BraKetError::QubitNotMeasured(qubit) => write!(f, "qubit {} not measured yet", qubit),
+            BraKetError::BitNotMeasured(bit) => write!(f, "classical bit {} not measured into yet", bit),
+            BraKetError::RegisterTooLarge(size) => {
+                write!(f, "a register of {} is larger than the {} allowed", size, MAX_REGISTER_SIZE)
+            }
+            BraKetError::InvalidBit(bit) => write!(f, "classical bit {} is beyond the {} a program may use", bit, MAX_CLASSICAL_BITS),
+            BraKetError::InvalidBasisLabel { label, number_of_qubits } => {
+                write!(f, "{} is not a basis state of {} qubits", label, number_of_qubits)
+            }
+            BraKetError::NotPowerOfTwo(dim) => write!(f, "dimension {} is not a power of two", dim),
+            BraKetError::WrongDimension { expected, found } => {
+                write!(f, "expected dimension {} but found {}", expected, found)
+            }
+            BraKetError::NotSquare { rows, columns } => write!(f, "density matrix not square {} =/= {}", rows, columns),
+            BraKetError::NotNormalised(norm_squared) => write!(f, "state not normalised, its norm squared is {}", norm_squared),
+            BraKetError::NotTracePreserving => write!(f, "kraus operators do not preserve the trace"),
+            BraKetError::InvalidProbability(probability) => write!(f, "{} is not a probability", probability),
+            BraKetError::NotStochastic => write!(f, "the columns of the confusion matrix are not probability distributions"),
+            BraKetError::NotInvertible => write!(f, "the matrix is not invertible"),
+            BraKetError::Unsupported(operation) => write!(f, "{} not supported", operation),
+            BraKetError::Io(message) => write!(f, "{}", message),
+            BraKetError::InvalidFile(message) => write!(f, "invalid file: {}", message),
+            BraKetError::InvalidQasm { line, message } => write!(f, "invalid OpenQASM on line {}: {}", line, message),
+            BraKetError::InvalidProgram(issues) => {
+                let issues: Vec<String> = issues.iter().map(|issue| issue.to_string()).collect();
+                write!(f, "invalid program: {}", issues.join(", "))
+            }
+        }
+    }
+}
+
+impl Error for BraKetError {}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use crate::operations::*;
+use crate::types::*;
+
+/// How aggressively the gates of a program are fused together before they are run.
+///
+/// Every gate costs a full parallel sweep over the state, so merging runs of gates which act on
+/// the same qubits into a single matrix reduces the number of sweeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Fusion {
+    /// Run every gate as written, the default.
+    #[default]
+    None,
+    /// Merge consecutive single qubit gates acting on the same qubit into one 2x2 unitary.
+    SingleQubit,
+    /// Additionally merge two qubit gates acting on the same pair of qubits, together with the
+    /// single qubit gates on either qubit, into one 4x4 unitary.
+    TwoQubit,
+}
+
+/// Embeds a single qubit unitary acting on qubit_0 into the two qubit space.
+fn embed_qubit_0(u: &Matrix2x2) -> Matrix4x4 {
+    Matrix4x4::from_fn(|i, j| match (i >> 1) == (j >> 1) {
+        true => u[(i & 1, j & 1)],
+        false => Complex::new(0., 0.),
+    })
+}
+
+/// Embeds a single qubit unitary acting on qubit_1 into the two qubit space.
+fn embed_qubit_1(u: &Matrix2x2) -> Matrix4x4 {
+    Matrix4x4::from_fn(|i, j| match (i & 1) == (j & 1) {
+        true => u[(i >> 1, j >> 1)],
+        false => Complex::new(0., 0.),
+    })
+}
+
+/// Swaps the roles of qubit_0 and qubit_1 in a two qubit unitary.
+fn swap_qubits(u: &Matrix4x4) -> Matrix4x4 {
+    let permute = |i: usize| ((i & 1) << 1) | (i >> 1);
+    Matrix4x4::from_fn(|i, j| u[(permute(i), permute(j))])
+}
+
+/// Rewrites a list of gates so that runs of gates acting on the same one or two qubits are
+/// merged into single ArbitrarySingle or ArbitaryTwo operations.
+///
+/// Measurements, resets and barriers are never fused and act as boundaries for the qubits they touch.
+pub fn fuse(gates: &[Operations], fusion: &Fusion) -> Vec<Operations> {
+    if *fusion == Fusion::None {
+        return gates.to_vec();
+    }
+
+    let mut fused: Vec<Operations> = Vec::with_capacity(gates.len());
+    // single qubit unitaries waiting to be emitted or absorbed into a two qubit block
+    let mut pending: HashMap<usize, Matrix2x2> = HashMap::new();
+    // the index in fused of the two qubit block each qubit was last part of, if it is still open
+    let mut open_blocks: HashMap<usize, usize> = HashMap::new();
+
+    let flush = |qubit: &usize,
+                 fused: &mut Vec<Operations>,
+                 pending: &mut HashMap<usize, Matrix2x2>,
+                 open_blocks: &mut HashMap<usize, usize>| {
+        if let Some(u) = pending.remove(qubit) {
+            fused.push(Operations::ArbitrarySingle(*qubit, u));
+        }
+        open_blocks.remove(qubit);
+    };
+
+    for gate in gates {
+        if let Some((qubit, u)) = single_qubit_unitary(gate) {
+            match open_blocks.get(&qubit) {
+                // nothing has touched the qubit since the block, so the gate can be absorbed into it
+                Some(&index) => {
+                    if let Operations::ArbitaryTwo(qubit_0, _, block) = &mut fused[index] {
+                        *block = match *qubit_0 == qubit {
+                            true => embed_qubit_0(&u) * *block,
+                            false => embed_qubit_1(&u) * *block,
+                        };
+                    }
+                }
+                None => {
+                    let previous = pending.get(&qubit).copied().unwrap_or(IDENTITY);
+                    pending.insert(qubit, u * previous);
+                }
+            }
+            continue;
+        }
+
+        if *fusion == Fusion::TwoQubit {
+            if let Some((qubit_0, qubit_1, u)) = two_qubit_unitary(gate) {
+                match (open_blocks.get(&qubit_0), open_blocks.get(&qubit_1)) {
+                    // the previous block acted on the same pair of qubits so the gate can be merged into it
+                    (Some(&index_0), Some(&index_1)) if index_0 == index_1 => {
+                        if let Operations::ArbitaryTwo(block_qubit_0, _, block) = &mut fused[index_0] {
+                            *block = match *block_qubit_0 == qubit_0 {
+                                true => u * *block,
+                                false => swap_qubits(&u) * *block,
+                            };
+                        }
+                    }
+                    _ => {
+                        let u_0 = pending.remove(&qubit_0).unwrap_or(IDENTITY);
+                        let u_1 = pending.remove(&qubit_1).unwrap_or(IDENTITY);
+                        let block = u * embed_qubit_0(&u_0) * embed_qubit_1(&u_1);
+                        fused.push(Operations::ArbitaryTwo(qubit_0, qubit_1, block));
+                        open_blocks.insert(qubit_0, fused.len() - 1);
+                        open_blocks.insert(qubit_1, fused.len() - 1);
+                    }
+                }
+                continue;
+            }
+        }
+
+        // any other operation ends the runs on the qubits it touches
+        let qubits: Vec<usize> = match gate {
+            Operations::Barrier | Operations::MeasureAll | Operations::ResetAll => {
+                let mut qubits: Vec<usize> = pending.keys().chain(open_blocks.keys()).copied().collect();
+                qubits.sort();
+                qubits.dedup();
+                qubits
+            }
+            _ => which_qubits(gate).into_iter().copied().collect(),
+        };
+        for qubit in qubits.iter() {
+            flush(qubit, &mut fused, &mut pending, &mut open_blocks);
+        }
+        fused.push(gate.to_owned());
+    }
+
+    let mut qubits: Vec<usize> = pending.keys().copied().collect();
+    qubits.sort();
+    for qubit in qubits.iter() {
+        flush(qubit, &mut fused, &mut pending, &mut open_blocks);
+    }
+    fused
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::fusion::*;
+
+    #[test]
+    fn test_single_qubit_runs_are_merged() {
+        let gates = vec![Operations::H(0), Operations::X(0), Operations::Z(1), Operations::H(0)];
+        let fused = fuse(&gates, &Fusion::SingleQubit);
+        assert_eq!(fused.len(), 2);
+    }
+
+    #[test]
+    fn test_measurement_is_a_boundary() {
+        let gates = vec![Operations::H(0), Operations::Measure(0), Operations::H(0)];
+        let fused = fuse(&gates, &Fusion::TwoQubit);
+        assert_eq!(fused.len(), 3);
+        assert_eq!(fused[1], Operations::Measure(0));
+    }
+
+    #[test]
+    fn test_two_qubit_blocks_are_merged() {
+        let gates = vec![
+            Operations::H(0),
+            Operations::CNOT(0, 1),
+            Operations::RZ(1, 0.3),
+            Operations::CNOT(1, 0),
+            Operations::H(2),
+        ];
+        let fused = fuse(&gates, &Fusion::TwoQubit);
+        assert_eq!(fused.len(), 2);
+    }
+
+    #[test]
+    fn test_no_fusion() {
+        let gates = vec![Operations::H(0), Operations::H(0)];
+        assert_eq!(fuse(&gates, &Fusion::None), gates);
+    }
+}
@@ -1,15 +1,26 @@
+use std::cell::RefCell;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+
+use crate::error::BraKetError;
+use nalgebra::SMatrix;
+
+use crate::types::{Complex, Real, RVector, COMPARISON_PRECISION, PI};
+
 pub fn log2(x: usize) -> usize {
     (x as f64).log2() as usize
 }
 
 fn swap(x: usize, bits: (&usize, &usize)) -> usize {
-    let bit_value_0 = (&x & (1 << bits.0)) >> bits.0;
-    let bit_value_1 = (&x & (1 << bits.1)) >> bits.1;
+    let bit_value_0 = (x & (1 << bits.0)) >> bits.0;
+    let bit_value_1 = (x & (1 << bits.1)) >> bits.1;
     let difference = {
         let difference = bit_value_0 ^ bit_value_1;
         (difference << bits.0) | (difference << bits.1)
     };
-    return x ^ difference;
+    x ^ difference
 }
 
 pub fn swap_pair(x: usize, target: &usize) -> usize {
@@ -23,14 +34,122 @@ pub fn swap_two_pairs(x: usize, target: &usize, control: &usize) -> usize {
         // swap the two bit values
         (1, 0) => swap(x, (&0, &1)),
         // it is only necessary to swap bit_1
-        (0, _) => swap(x, (&1, &control)),
+        (0, _) => swap(x, (&1, control)),
         // it is only necessary to swap bit_0
-        (_, 1) => swap(x, (&0, &target)),
+        (_, 1) => swap(x, (&0, target)),
         // swap bits 0 and 1 then swap bit 0 with bit_1
-        (1, _) => swap(swap(x, (&0, &1)), (&0, &control)),
+        (1, _) => swap(swap(x, (&0, &1)), (&0, control)),
         // swap bits 0 and 1 then swap bit 1 with qubit_0
-        (_, 0) => swap(swap(x, (&0, &1)), (&1, &target)),
+        (_, 0) => swap(swap(x, (&0, &1)), (&1, target)),
         // swap both bits
-        (_, _) => swap(swap(x, (&0, &target)), (&1, &control)),
+        (_, _) => swap(swap(x, (&0, target)), (&1, control)),
+    }
+}
+
+/// Checks the kraus operators form a channel, the sum of K^dagger K over them being the identity.
+pub fn check_kraus<const D: usize>(kraus: &[SMatrix<Complex, D, D>]) -> Result<(), BraKetError> {
+    let sum: SMatrix<Complex, D, D> = kraus.iter().map(|k| k.adjoint() * k).sum();
+    match (sum - SMatrix::<Complex, D, D>::identity()).norm() < COMPARISON_PRECISION {
+        true => Ok(()),
+        false => Err(BraKetError::NotTracePreserving),
+    }
+}
+
+/// Sums a probability distribution over the basis states down to the distribution of the given qubits,
+/// qubits[i] being bit i of the index of the marginal distribution.
+pub fn marginalise(probabilities: &RVector, qubits: &[usize]) -> Result<RVector, BraKetError> {
+    check_qubits(qubits, log2(probabilities.len()))?;
+
+    let marginal_dim = 1 << qubits.len();
+    let marginal_index = |n: usize| -> usize {
+        qubits.iter().enumerate().map(|(i, qubit)| ((n >> qubit) & 1) << i).sum()
+    };
+    let marginal = (0..probabilities.len())
+        .into_par_iter()
+        .fold(
+            || vec![0. as Real; marginal_dim],
+            |mut marginal, n| {
+                marginal[marginal_index(n)] += probabilities[n];
+                marginal
+            },
+        )
+        .reduce(
+            || vec![0. as Real; marginal_dim],
+            |a, b| a.iter().zip(b.iter()).map(|(a, b)| a + b).collect(),
+        );
+    Ok(RVector::from_vec(marginal))
+}
+
+/// Zeros for the elements of a state of number_of_qubits qubits, or None if there are more of them than
+/// fit in a usize. Fails instead of aborting if the memory for them cannot be reserved.
+pub(crate) fn try_zeros(length: Option<usize>, number_of_qubits: usize) -> Result<Vec<Complex>, BraKetError> {
+    let length = length.ok_or(BraKetError::TooManyQubits(number_of_qubits))?;
+    let mut elements = vec![];
+    elements.try_reserve_exact(length).map_err(|_| BraKetError::TooManyQubits(number_of_qubits))?;
+    elements.resize(length, Complex::new(0., 0.));
+    Ok(elements)
+}
+
+/// Checks that a state of number_of_qubits qubits has all the qubits a program acts on.
+pub fn check_qubit_number(qubits: Vec<&usize>, number_of_qubits: usize) -> Result<(), BraKetError> {
+    match qubits.into_iter().max() {
+        Some(qubit) if *qubit >= number_of_qubits => {
+            Err(BraKetError::NotEnoughQubits { required: qubit + 1, available: number_of_qubits })
+        }
+        _ => Ok(()),
     }
 }
+
+/// Checks that the qubits are distinct and within a state of number_of_qubits qubits.
+pub fn check_qubits(qubits: &[usize], number_of_qubits: usize) -> Result<(), BraKetError> {
+    for (n, qubit) in qubits.iter().enumerate() {
+        if *qubit >= number_of_qubits {
+            return Err(BraKetError::InvalidQubit { qubit: *qubit, number_of_qubits });
+        }
+        if qubits[..n].contains(qubit) {
+            return Err(BraKetError::RepeatedQubit(*qubit));
+        }
+    }
+    Ok(())
+}
+
+/// The basis state indices with every combination of bits written into the given qubits, bit i of the
+/// combination going to qubits[i] and all other bits zero.
+pub fn scattered_indices(qubits: &[usize]) -> Vec<usize> {
+    (0..1 << qubits.len())
+        .map(|value: usize| qubits.iter().enumerate().map(|(i, qubit)| ((value >> i) & 1) << qubit).sum())
+        .collect()
+}
+
+/// A complex number with independent standard normal real and imaginary parts, using the Box-Muller
+/// transform.
+pub fn complex_gaussian<R: Rng>(rng: &mut R) -> Complex {
+    let radius = (-2. * (1. - rng.gen::<Real>()).ln()).sqrt();
+    let angle = 2. * PI * rng.gen::<Real>();
+    Complex::new(radius * angle.cos(), radius * angle.sin())
+}
+
+thread_local! {
+    static MEASUREMENT_RNG: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
+
+/// Calls f with the random number generator the outcomes of measurements on this thread are sampled with.
+pub fn with_measurement_rng<T, F: FnOnce(&mut StdRng) -> T>(f: F) -> T {
+    MEASUREMENT_RNG.with(|rng| f(&mut rng.borrow_mut()))
+}
+
+/// Seeds the random number generator the outcomes of measurements on this thread are sampled with, so that
+/// runs of programs which measure qubits can be reproduced. Without a seed the outcomes are drawn from a
+/// generator seeded from the operating system.
+pub fn set_seed(seed: u64) {
+    MEASUREMENT_RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed))
+}
+
+/// Calls f with the measurement generator of this thread seeded with seed, and then puts back the generator
+/// the thread had before, so that the outcomes the caller draws afterwards are not changed.
+pub(crate) fn with_seeded_measurement_rng<T, F: FnOnce() -> T>(seed: u64, f: F) -> T {
+    let previous = MEASUREMENT_RNG.with(|rng| rng.replace(StdRng::seed_from_u64(seed)));
+    let result = f();
+    MEASUREMENT_RNG.with(|rng| rng.replace(previous));
+    result
+}
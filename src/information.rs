@@ -0,0 +1,145 @@
+use nalgebra::ComplexField;
+
+use crate::error::BraKetError;
+use crate::helper_functions::check_qubits;
+use crate::types::*;
+use crate::{DensityMatrix, StateVector};
+
+/// Comparisons between two quantum states, implemented between any pair of state vectors and density
+/// matrices.
+pub trait Distance<Rhs> {
+    /// The fidelity (tr sqrt(sqrt(rho) sigma sqrt(rho)))^2 of the two states, which is |<psi|phi>|^2 for two
+    /// pure states and <psi|rho|psi> when one of them is pure. It is 1 for equal states and 0 for
+    /// orthogonal ones.
+    fn fidelity(&self, other: &Rhs) -> Real;
+    /// The trace distance tr|rho - sigma| / 2 of the two states, between 0 for equal states and 1 for
+    /// orthogonal ones.
+    fn trace_distance(&self, other: &Rhs) -> Real;
+}
+
+/// The purity and entropies of a quantum state. Entropies are in bits, that is with base 2 logarithms.
+pub trait Entropy {
+    /// The purity tr(rho^2), 1 for a pure state and down to 1 / 2^n for a maximally mixed one.
+    fn purity(&self) -> Real;
+    /// The von Neumann entropy -tr(rho log rho).
+    fn von_neumann_entropy(&self) -> Real;
+    /// The Rényi entropy log(tr(rho^alpha)) / (1 - alpha), which is the von Neumann entropy for alpha = 1.
+    ///
+    /// # Panics
+    /// If alpha is negative.
+    fn renyi_entropy(&self, alpha: Real) -> Real;
+    /// The von Neumann entropy of the given qubits once the rest are traced out. For a pure state this is the
+    /// entanglement entropy of the bipartition into the given qubits and the rest.
+    fn entanglement_entropy(&self, qubits: &[usize]) -> Result<Real, BraKetError>;
+}
+
+/// The eigenvalues of a hermitian matrix, clipped to be non negative to remove rounding errors.
+fn eigenvalues(matrix: &CMatrix) -> Vec<Real> {
+    matrix.clone().symmetric_eigenvalues().iter().map(|lambda| lambda.max(0.)).collect()
+}
+
+/// The square root of a positive semidefinite matrix.
+fn matrix_sqrt(matrix: &CMatrix) -> CMatrix {
+    let eigen = matrix.clone().symmetric_eigen();
+    let roots = eigen.eigenvalues.map(|lambda| Complex::from_real(lambda.max(0.).sqrt()));
+    &eigen.eigenvectors * CMatrix::from_diagonal(&roots) * eigen.eigenvectors.adjoint()
+}
+
+fn von_neumann_entropy(eigenvalues: &[Real]) -> Real {
+    eigenvalues.iter().filter(|lambda| **lambda > 0.).map(|lambda| -lambda * lambda.log2()).sum()
+}
+
+fn renyi_entropy(eigenvalues: &[Real], alpha: Real) -> Real {
+    assert!(alpha >= 0., "the order of the Rényi entropy must not be negative, got {}", alpha);
+    if (alpha - 1.).abs() < COMPARISON_PRECISION {
+        return von_neumann_entropy(eigenvalues);
+    }
+    let sum: Real = eigenvalues.iter().filter(|lambda| **lambda > 0.).map(|lambda| lambda.powf(alpha)).sum();
+    sum.log2() / (1. - alpha)
+}
+
+impl Distance<StateVector> for StateVector {
+    fn fidelity(&self, other: &StateVector) -> Real {
+        assert_eq!(self.number_of_qubits, other.number_of_qubits, "states have different numbers of qubits");
+        self.state_vector.dotc(&other.state_vector).norm_sqr()
+    }
+
+    fn trace_distance(&self, other: &StateVector) -> Real {
+        (1. - self.fidelity(other)).max(0.).sqrt()
+    }
+}
+
+impl Distance<DensityMatrix> for StateVector {
+    fn fidelity(&self, other: &DensityMatrix) -> Real {
+        assert_eq!(self.number_of_qubits, other.number_of_qubits, "states have different numbers of qubits");
+        self.state_vector.dotc(&(&other.density_matrix * &self.state_vector)).re
+    }
+
+    fn trace_distance(&self, other: &DensityMatrix) -> Real {
+        DensityMatrix::from(self.clone()).trace_distance(other)
+    }
+}
+
+impl Distance<StateVector> for DensityMatrix {
+    fn fidelity(&self, other: &StateVector) -> Real {
+        other.fidelity(self)
+    }
+
+    fn trace_distance(&self, other: &StateVector) -> Real {
+        other.trace_distance(self)
+    }
+}
+
+impl Distance<DensityMatrix> for DensityMatrix {
+    fn fidelity(&self, other: &DensityMatrix) -> Real {
+        assert_eq!(self.number_of_qubits, other.number_of_qubits, "states have different numbers of qubits");
+        let root = matrix_sqrt(&self.density_matrix);
+        let product = &root * &other.density_matrix * &root;
+        let trace: Real = eigenvalues(&product).iter().map(|lambda| lambda.sqrt()).sum();
+        trace * trace
+    }
+
+    fn trace_distance(&self, other: &DensityMatrix) -> Real {
+        assert_eq!(self.number_of_qubits, other.number_of_qubits, "states have different numbers of qubits");
+        let difference = &self.density_matrix - &other.density_matrix;
+        difference.symmetric_eigenvalues().iter().map(|lambda| lambda.abs()).sum::<Real>() / 2.
+    }
+}
+
+impl Entropy for StateVector {
+    fn purity(&self) -> Real {
+        self.state_vector.norm_squared().powi(2)
+    }
+
+    fn von_neumann_entropy(&self) -> Real {
+        0.
+    }
+
+    fn renyi_entropy(&self, alpha: Real) -> Real {
+        renyi_entropy(&[1.], alpha)
+    }
+
+    fn entanglement_entropy(&self, qubits: &[usize]) -> Result<Real, BraKetError> {
+        Ok(self.reduced_density_matrix(qubits)?.von_neumann_entropy())
+    }
+}
+
+impl Entropy for DensityMatrix {
+    fn purity(&self) -> Real {
+        self.density_matrix.norm_squared()
+    }
+
+    fn von_neumann_entropy(&self) -> Real {
+        von_neumann_entropy(&eigenvalues(&self.density_matrix))
+    }
+
+    fn renyi_entropy(&self, alpha: Real) -> Real {
+        renyi_entropy(&eigenvalues(&self.density_matrix), alpha)
+    }
+
+    fn entanglement_entropy(&self, qubits: &[usize]) -> Result<Real, BraKetError> {
+        check_qubits(qubits, self.number_of_qubits)?;
+        let environment: Vec<usize> = (0..self.number_of_qubits).filter(|qubit| !qubits.contains(qubit)).collect();
+        Ok(self.partial_trace(&environment)?.von_neumann_entropy())
+    }
+}
@@ -1,20 +1,58 @@
 extern crate core;
 
+mod basis;
+mod checkpoint;
+mod controlled;
 mod density_matrix;
+mod error;
 mod draw;
+mod fusion;
 mod operations;
+#[cfg(feature = "plot")]
+mod plot;
 mod helper_functions;
+mod information;
+mod noise;
 mod program;
+mod qasm;
+mod qasm_parser;
+mod quantikz;
+mod readout;
+mod registers;
+mod routing;
+mod sampling;
+#[cfg(feature = "serde")]
+mod serialization;
 mod state_vector;
+mod subcircuit;
+mod svg;
 mod state_traits;
+mod transpile;
 mod types;
+mod validation;
 mod macros;
+mod metrics;
 mod quantum_functions;
 
+pub use basis::{BasisLabel, Endianness};
+pub use checkpoint::Precision;
 pub use density_matrix::DensityMatrix;
+pub use error::BraKetError;
+pub use helper_functions::set_seed;
+#[cfg(feature = "plot")]
+pub use plot::Plot;
 pub use program::Program;
+pub use registers::{QuantumRegister, ClassicalRegister, MAX_CLASSICAL_BITS, MAX_REGISTER_SIZE};
+pub use fusion::Fusion;
+pub use information::{Distance, Entropy};
+pub use noise::{Channel, Gates, NoiseModel};
+pub use readout::{ReadoutModel, calibration_circuits, tensored_calibration_circuits};
+pub use routing::{CouplingMap, Routing};
+pub use transpile::{NativeGateSet, zyz_decomposition};
 pub use state_vector::StateVector;
-pub use types::{Complex, Real, Angle, Int, RVector, CVector, SQRT_2,  PI};
+pub use subcircuit::Subcircuit;
+pub use validation::ValidationIssue;
+pub use types::{Complex, Real, Angle, Int, RVector, RMatrix, CVector, CMatrix, Matrix2x2, Matrix4x4, SQRT_2,  PI};
 pub use operations::Operations;
 pub use state_traits::*;
 pub use quantum_functions::*;
@@ -23,3 +23,4 @@ macro_rules! expi {
         Complex::new($angle.cos(), $angle.sin())
     };
 }
+pub(crate) use {expi};
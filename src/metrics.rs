@@ -0,0 +1,145 @@
+use std::collections::BTreeMap;
+
+use crate::operations::*;
+use crate::subcircuit::*;
+
+/// The qubits an operation occupies in the circuit, where operations acting on the whole register
+/// occupy every qubit used by the program.
+fn occupied_qubits(gate: &Operations, qubits: &[usize]) -> Vec<usize> {
+    match gate {
+        Operations::Barrier | Operations::MeasureAll | Operations::ResetAll => qubits.to_vec(),
+        _ => which_qubits(gate).into_iter().copied().collect(),
+    }
+}
+
+/// The length of the longest path through the circuit, where each gate contributes its weight. Barriers
+/// synchronise every qubit without contributing to the depth.
+fn weighted_depth<F: Fn(&Operations) -> usize>(gates: &[Operations], weight: F) -> usize {
+    let qubits = qubits(gates);
+    let mut depths = vec![0; qubits.last().map_or(0, |q| q + 1)];
+    let mut barrier_depth = 0;
+    for gate in gates.iter() {
+        if *gate == Operations::Barrier {
+            barrier_depth = depths.iter().copied().max().unwrap_or(0);
+            continue;
+        }
+        let occupied = occupied_qubits(gate, &qubits);
+        let depth = occupied.iter().map(|q| depths[*q]).max().unwrap_or(0).max(barrier_depth) + weight(gate);
+        for qubit in occupied {
+            depths[qubit] = depth;
+        }
+    }
+    depths.into_iter().max().unwrap_or(0).max(barrier_depth)
+}
+
+/// The sorted qubits used by the gates.
+fn qubits(gates: &[Operations]) -> Vec<usize> {
+    let mut qubits: Vec<usize> = gates.iter().flat_map(|gate| which_qubits(gate).into_iter().copied()).collect();
+    qubits.sort();
+    qubits.dedup();
+    qubits
+}
+
+/// The depth of the circuit, the number of layers of gates which have to be performed one after another.
+/// Subcircuits are expanded into their gates.
+pub fn depth(gates: &[Operations]) -> usize {
+    weighted_depth(&flatten(gates), |_| 1)
+}
+
+/// The depth of the circuit counting only the two qubit gates. Subcircuits are expanded into their gates.
+pub fn two_qubit_depth(gates: &[Operations]) -> usize {
+    weighted_depth(&flatten(gates), |gate| match which_qubits(gate).len() {
+        2 => 1,
+        _ => 0,
+    })
+}
+
+/// The number of times each type of gate appears in the circuit, once subcircuits are expanded into their gates.
+pub fn gate_counts(gates: &[Operations]) -> BTreeMap<String, usize> {
+    let mut counts = BTreeMap::new();
+    for gate in flatten(gates).iter() {
+        *counts.entry(gate_name(gate).to_owned()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Partitions the gates into layers (moments) of operations acting on disjoint qubits, placing each gate
+/// in the earliest layer after every previous gate acting on its qubits. The layers contain the indices
+/// of the gates. Every gate after a barrier comes after every gate before it, and subcircuits are treated
+/// as single operations, so the gates should be flattened first. There are as many layers as the depth.
+pub fn layers(gates: &[Operations]) -> Vec<Vec<usize>> {
+    partition(gates, false)
+}
+
+/// Partitions the gates into layers as layers does, but with each barrier placed in a layer of its own, the
+/// layers the circuit is drawn with.
+pub fn drawing_layers(gates: &[Operations]) -> Vec<Vec<usize>> {
+    partition(gates, true)
+}
+
+fn partition(gates: &[Operations], barrier_layers: bool) -> Vec<Vec<usize>> {
+    let qubits = qubits(gates);
+    let mut next_layer = vec![0; qubits.last().map_or(0, |q| q + 1)];
+    let mut barrier_layer = 0;
+    let mut layers: Vec<Vec<usize>> = vec![];
+    for (n, gate) in gates.iter().enumerate() {
+        let layer = match gate {
+            Operations::Barrier if barrier_layers => {
+                let layer = next_layer.iter().copied().max().unwrap_or(0).max(barrier_layer);
+                barrier_layer = layer + 1;
+                layer
+            }
+            Operations::Barrier => {
+                barrier_layer = next_layer.iter().copied().max().unwrap_or(0).max(barrier_layer);
+                continue;
+            }
+            _ => {
+                let occupied = occupied_qubits(gate, &qubits);
+                let layer = occupied.iter().map(|q| next_layer[*q]).max().unwrap_or(0).max(barrier_layer);
+                for qubit in occupied {
+                    next_layer[qubit] = layer + 1;
+                }
+                layer
+            }
+        };
+        if layers.len() <= layer {
+            layers.resize(layer + 1, vec![]);
+        }
+        layers[layer].push(n);
+    }
+    layers
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::metrics::*;
+
+    #[test]
+    fn test_depth() {
+        let gates = vec![Operations::H(0), Operations::H(1), Operations::CNOT(0, 1), Operations::X(2)];
+        assert_eq!(depth(&gates), 2);
+        assert_eq!(two_qubit_depth(&gates), 1);
+    }
+
+    #[test]
+    fn test_barrier() {
+        let gates = vec![Operations::H(0), Operations::H(0), Operations::Barrier, Operations::X(1)];
+        assert_eq!(depth(&gates), 3);
+        assert_eq!(layers(&gates), vec![vec![0], vec![1], vec![3]]);
+        assert_eq!(drawing_layers(&gates), vec![vec![0], vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn test_layers() {
+        let gates = vec![Operations::H(0), Operations::H(1), Operations::CNOT(0, 1), Operations::X(2), Operations::Z(2)];
+        assert_eq!(layers(&gates), vec![vec![0, 1, 3], vec![2, 4]]);
+    }
+
+    #[test]
+    fn test_gate_counts() {
+        let gates = vec![Operations::H(0), Operations::H(1), Operations::CNOT(0, 1)];
+        let counts = gate_counts(&gates);
+        assert_eq!(counts["H"], 2);
+        assert_eq!(counts["CNOT"], 1);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::error::BraKetError;
+use crate::operations::{gate_name, implement_gate, which_qubits, Operations};
+use crate::program::Program;
+use crate::readout::ReadoutModel;
+use crate::state_traits::StateTraits;
+use crate::subcircuit::flatten;
+use crate::types::*;
+
+/// An error which follows a gate, with the probability it occurs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Channel {
+    /// Replaces the state of the qubits by the maximally mixed state with the given probability. After a two
+    /// qubit gate both qubits are depolarized together.
+    Depolarizing(Real),
+    /// Decay of |1> to |0> with the given probability, the energy relaxation described by T1.
+    AmplitudeDamping(Real),
+    /// Loss of the coherence between |0> and |1> without a loss of energy, the dephasing described by T2.
+    PhaseDamping(Real),
+}
+
+impl Channel {
+    fn probability(&self) -> Real {
+        match self {
+            Channel::Depolarizing(p) | Channel::AmplitudeDamping(p) | Channel::PhaseDamping(p) => *p,
+        }
+    }
+}
+
+/// The gates an error follows.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Gates {
+    All,
+    SingleQubit,
+    TwoQubit,
+    /// The gates with the given name, the name of their Operations variant such as "CNOT".
+    Named(String),
+}
+
+impl Gates {
+    fn matches(&self, gate: &Operations) -> bool {
+        match self {
+            Gates::All => true,
+            Gates::SingleQubit => which_qubits(gate).len() == 1,
+            Gates::TwoQubit => which_qubits(gate).len() == 2,
+            Gates::Named(name) => gate_name(gate) == name,
+        }
+    }
+}
+
+/// An error after the gates, on the qubits they act on or only on the given qubit.
+#[derive(Debug, Clone, PartialEq)]
+struct GateError {
+    gates: Gates,
+    qubit: Option<usize>,
+    channel: Channel,
+}
+
+/// The errors of a noisy device, applied after each gate by Program::run_with_noise: errors after given gates,
+/// on every qubit or on particular ones, and readout errors flipping the recorded outcomes of measurements.
+/// Measurements, resets and barriers are not followed by gate errors.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NoiseModel {
+    errors: Vec<GateError>,
+    /// The readout errors, each qubit's not correlated with those of other qubits.
+    readout: ReadoutModel,
+}
+
+fn check_probability(probability: Real) -> Result<(), BraKetError> {
+    match (0. ..=1.).contains(&probability) {
+        true => Ok(()),
+        false => Err(BraKetError::InvalidProbability(probability)),
+    }
+}
+
+impl NoiseModel {
+    pub fn new() -> NoiseModel {
+        NoiseModel::default()
+    }
+
+    /// Adds an error after every one of the gates, acting on the qubits the gate acts on. Depolarizing
+    /// errors act on them together, and damping on each of them alone. Fails if the probability of the
+    /// error is not between 0 and 1.
+    pub fn add_gate_error(&mut self, gates: Gates, channel: Channel) -> Result<&mut NoiseModel, BraKetError> {
+        check_probability(channel.probability())?;
+        self.errors.push(GateError { gates, qubit: None, channel });
+        Ok(self)
+    }
+
+    /// Adds an error after every one of the gates which acts on qubit, acting on that qubit alone. Fails if
+    /// the probability of the error is not between 0 and 1.
+    pub fn add_qubit_error(&mut self, gates: Gates, qubit: usize, channel: Channel) -> Result<&mut NoiseModel, BraKetError> {
+        check_probability(channel.probability())?;
+        self.errors.push(GateError { gates, qubit: Some(qubit), channel });
+        Ok(self)
+    }
+
+    /// Sets the probabilities with which a measurement of qubit records 1 when the qubit is in |0>, and 0
+    /// when it is in |1>. Fails if either is not between 0 and 1.
+    pub fn set_readout_error(&mut self, qubit: usize, zero_to_one: Real, one_to_zero: Real) -> Result<&mut NoiseModel, BraKetError> {
+        check_probability(zero_to_one)?;
+        check_probability(one_to_zero)?;
+        self.readout.extend(qubit + 1);
+        self.readout.set_qubit_error(qubit, zero_to_one, one_to_zero)?;
+        Ok(self)
+    }
+
+    /// Replaces the readout errors by those of a readout model, such as one estimated from calibration
+    /// circuits. The errors of the qubits must not be correlated, as each measurement is flipped on its own.
+    pub fn set_readout_model(&mut self, readout: ReadoutModel) -> Result<&mut NoiseModel, BraKetError> {
+        if readout.is_correlated() {
+            return Err(BraKetError::Unsupported("correlated readout errors in a noise model"));
+        }
+        self.readout = readout;
+        Ok(self)
+    }
+
+    /// A model with the same depolarizing error after every single qubit gate, another after every two qubit
+    /// gate and the same readout error on each of number_of_qubits qubits. Fails if a probability is not
+    /// between 0 and 1.
+    pub fn uniform(single_qubit: Real, two_qubit: Real, readout: Real, number_of_qubits: usize) -> Result<NoiseModel, BraKetError> {
+        let mut model = NoiseModel::new();
+        model.add_gate_error(Gates::SingleQubit, Channel::Depolarizing(single_qubit))?;
+        model.add_gate_error(Gates::TwoQubit, Channel::Depolarizing(two_qubit))?;
+        check_probability(readout)?;
+        model.set_readout_model(ReadoutModel::uniform(number_of_qubits, readout, readout)?)?;
+        Ok(model)
+    }
+
+    /// The readout errors of the model.
+    pub fn readout_model(&self) -> &ReadoutModel {
+        &self.readout
+    }
+
+    /// The probabilities of misreading 0 and 1 on qubit, if it has a readout error.
+    pub fn readout_probabilities(&self, qubit: &usize) -> Option<[Real; 2]> {
+        self.readout.qubit_error(*qubit)
+    }
+
+    /// Applies the errors which follow the gate.
+    fn apply<T: StateTraits>(&self, state: &mut T, gate: &Operations) -> Result<(), BraKetError> {
+        match gate {
+            Operations::Barrier | Operations::Reset(_) | Operations::ResetAll => {}
+            Operations::Measure(qubit) | Operations::MeasureX(qubit) | Operations::MeasureY(qubit) => self.readout(state, qubit, None),
+            Operations::MeasureInto(qubit, bit) => self.readout(state, qubit, Some(bit)),
+            Operations::MeasureAll => (0..state.number_of_qubits()).for_each(|qubit| self.readout(state, &qubit, None)),
+            _ => {
+                let qubits: Vec<usize> = which_qubits(gate).into_iter().copied().collect();
+                for error in self.errors.iter().filter(|error| error.gates.matches(gate)) {
+                    match error.qubit {
+                        Some(qubit) if qubits.contains(&qubit) => apply_channel(state, &[qubit], &error.channel)?,
+                        Some(_) => {}
+                        None => apply_channel(state, &qubits, &error.channel)?,
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn readout<T: StateTraits>(&self, state: &mut T, qubit: &usize, bit: Option<&usize>) {
+        if let Some(flip) = self.readout.qubit_error(*qubit) {
+            state.readout_error(qubit, bit, &flip)
+        }
+    }
+}
+
+/// The kraus operators of the Pauli channel applying X, Y or Z each with probability p / 4, or nothing.
+fn depolarizing_kraus(p: Real) -> Vec<Matrix2x2> {
+    let paulis = [IDENTITY, SIGMA_X, SIGMA_Y, SIGMA_Z];
+    let weights = [1. - 3. * p / 4., p / 4., p / 4., p / 4.];
+    paulis.iter().zip(weights).map(|(pauli, weight)| pauli.scale(weight.sqrt())).collect()
+}
+
+fn amplitude_damping_kraus(gamma: Real) -> Vec<Matrix2x2> {
+    let mut k_0 = IDENTITY;
+    k_0[(1, 1)] = Complex::new((1. - gamma).sqrt(), 0.);
+    let mut k_1 = Matrix2x2::zeros();
+    k_1[(0, 1)] = Complex::new(gamma.sqrt(), 0.);
+    vec![k_0, k_1]
+}
+
+fn phase_damping_kraus(lambda: Real) -> Vec<Matrix2x2> {
+    let mut k_0 = IDENTITY;
+    k_0[(1, 1)] = Complex::new((1. - lambda).sqrt(), 0.);
+    let mut k_1 = Matrix2x2::zeros();
+    k_1[(1, 1)] = Complex::new(lambda.sqrt(), 0.);
+    vec![k_0, k_1]
+}
+
+/// Applies a channel to the qubits, depolarizing them together and damping each of them alone.
+fn apply_channel<T: StateTraits>(state: &mut T, qubits: &[usize], channel: &Channel) -> Result<(), BraKetError> {
+    match (channel, qubits) {
+        (_, []) => Ok(()),
+        (Channel::Depolarizing(p), [qubit_0, qubit_1]) => {
+            // the sixteen two qubit Paulis, each but the identity applied with probability p / 16
+            let paulis = [IDENTITY, SIGMA_X, SIGMA_Y, SIGMA_Z];
+            let kraus: Vec<Matrix4x4> = paulis
+                .iter()
+                .enumerate()
+                .flat_map(|(n_1, pauli_1)| paulis.iter().enumerate().map(move |(n_0, pauli_0)| (n_0 + n_1 == 0, pauli_1.kronecker(pauli_0))))
+                .map(|(identity, pauli)| pauli.scale(if identity { 1. - 15. * p / 16. } else { p / 16. }.sqrt()))
+                .collect();
+            state.two_qubit_kraus(qubit_0, qubit_1, &kraus)
+        }
+        (Channel::Depolarizing(p), _) => qubits.iter().try_for_each(|qubit| state.single_qubit_kraus(qubit, &depolarizing_kraus(*p))),
+        (Channel::AmplitudeDamping(gamma), _) => qubits.iter().try_for_each(|qubit| state.single_qubit_kraus(qubit, &amplitude_damping_kraus(*gamma))),
+        (Channel::PhaseDamping(lambda), _) => qubits.iter().try_for_each(|qubit| state.single_qubit_kraus(qubit, &phase_damping_kraus(*lambda))),
+    }
+}
+
+/// Runs the program with the errors of the model after each gate, with subcircuits expanded into their gates
+/// and without fusing gates. On a density matrix the errors are applied exactly, and on a state vector
+/// they are sampled, so that each run follows one of the possible trajectories.
+pub fn run_with_noise<T: StateTraits>(program: &Program, state: &mut T, model: &NoiseModel) -> Result<(), BraKetError> {
+    state.check_qubit_number(program.which_qubits())?;
+    program.check_classical_bits()?;
+    if cfg!(debug_assertions) {
+        program.validate(state.number_of_qubits()).map_err(BraKetError::InvalidProgram)?;
+    }
+    for gate in flatten(&program.gates).iter() {
+        implement_gate(state, gate)?;
+        model.apply(state, gate)?;
+    }
+    Ok(())
+}
@@ -1,18 +1,32 @@
+use std::sync::Arc;
+
 use log::debug;
 use nalgebra::matrix;
 
+use crate::error::BraKetError;
 use crate::state_traits::StateTraits;
 use crate::types::*;
 use crate::macros::*;
+use crate::subcircuit::*;
 
 /// The operations which can be performed on either the state vector or density matrix describing the quantum state
 /// as part of a quantum program.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "gate", content = "args"))]
 pub enum Operations {
     Barrier,
 
     /// Measure a qubit.
     Measure(usize),
+    /// Measure a qubit, storing the outcome in a classical bit.
+    MeasureInto(usize, usize),
+    /// Measure a qubit in the x basis, the outcome being false for |+> and true for |->.
+    MeasureX(usize),
+    /// Measure a qubit in the y basis, the outcome being false for |+i> and true for |-i>.
+    MeasureY(usize),
+    /// Reinitialise a qubit to its ground state.
+    Reset(usize),
     /// Measure all qubits.
     MeasureAll,
     /// Reinitialise all qubits to their ground state.
@@ -23,20 +37,58 @@ pub enum Operations {
     Z(usize),
     H(usize),
     S(usize),
-    ArbitrarySingle(usize, Matrix2x2),
+    Sdg(usize),
+    T(usize),
+    Tdg(usize),
+    SX(usize),
+    SXdg(usize),
+    ArbitrarySingle(usize, #[cfg_attr(feature = "serde", serde(with = "crate::serialization::matrix"))] Matrix2x2),
 
     RX(usize, Angle),
     RY(usize, Angle),
     RZ(usize, Angle),
     R(usize, Angle, Angle, Angle),
+    /// The phase gate diag(1, e^(i lambda)), which is also U1(lambda).
+    Phase(usize, Angle),
+    U1(usize, Angle),
+    /// U2(phi, lambda) = U3(pi / 2, phi, lambda).
+    U2(usize, Angle, Angle),
+    /// U3(theta, phi, lambda) = RZ(phi) RY(theta) RZ(lambda) up to a global phase.
+    U3(usize, Angle, Angle, Angle),
 
     CNOT(usize, usize),
     CZ(usize, usize),
+    CY(usize, usize),
+    CH(usize, usize),
+    CRX(usize, usize, Angle),
+    CRY(usize, usize, Angle),
+    /// The controlled RZ(angle), diag(1, 1, e^(-i angle / 2), e^(i angle / 2)) with the control the high bit.
+    /// Earlier versions applied the controlled phase diag(1, 1, 1, e^(i angle)) here, which is now CPhase.
     CRZ(usize, usize, Angle),
+    /// The controlled phase, diag(1, 1, 1, e^(i angle)) with the control the high bit.
+    CPhase(usize, usize, Angle),
     SISWAP(usize, usize),
-    ArbitaryTwo(usize, usize, Matrix4x4),
+    /// A two qubit unitary. The variant keeps its historical spelling, and is written as ArbitraryTwo in
+    /// JSON, where ArbitaryTwo is still accepted.
+    #[cfg_attr(feature = "serde", serde(rename = "ArbitraryTwo", alias = "ArbitaryTwo"))]
+    ArbitaryTwo(usize, usize, #[cfg_attr(feature = "serde", serde(with = "crate::serialization::matrix"))] Matrix4x4),
     ISWAP(usize, usize),
-    SWAP(usize, usize)
+    SWAP(usize, usize),
+    /// The Ising gate exp(-i theta / 2 X X).
+    RXX(usize, usize, Angle),
+    /// The Ising gate exp(-i theta / 2 Y Y).
+    RYY(usize, usize, Angle),
+    /// The Ising gate exp(-i theta / 2 Z Z).
+    RZZ(usize, usize, Angle),
+    /// The fermionic simulation gate fSim(theta, phi), a swap angle theta and a conditional phase phi.
+    FSim(usize, usize, Angle, Angle),
+    /// A Givens rotation by theta in the space of |01> and |10>.
+    Givens(usize, usize, Angle),
+    /// The echoed cross resonance gate.
+    ECR(usize, usize),
+
+    /// A named subcircuit placed on the given qubits, its qubit n being mapped to qubits[n].
+    Subcircuit(Arc<Subcircuit>, Vec<usize>),
 }
 
 pub fn implement_gate<
@@ -44,61 +96,174 @@ pub fn implement_gate<
 >(
     state: &mut T,
     gate: &Operations,
-) {
+) -> Result<(), BraKetError> {
     debug!("{:?}", gate);
     match gate {
         Operations::Barrier => {},
         Operations::Measure(qubit) => state.measure(qubit),
+        Operations::MeasureInto(qubit, bit) => state.measure_into(qubit, bit)?,
+        Operations::MeasureX(qubit) => {
+            // rotate the x basis onto the z basis and back again, leaving the qubit in the measured eigenstate
+            state.single_qubit_gate(qubit, &H);
+            state.measure(qubit);
+            state.single_qubit_gate(qubit, &H);
+        }
+        Operations::MeasureY(qubit) => {
+            state.single_qubit_gate(qubit, &(H * SDG));
+            state.measure(qubit);
+            state.single_qubit_gate(qubit, &(S * H));
+        }
+        Operations::Reset(qubit) => state.reset(qubit),
         Operations::MeasureAll => state.measure_all(),
         Operations::ResetAll => state.reinitialise_all(),
+        Operations::Subcircuit(subcircuit, qubits) => {
+            for gate in subcircuit.expand(qubits).iter() {
+                implement_gate(state, gate)?
+            }
+        }
+        _ => {
+            if let Some((qubit, u)) = single_qubit_unitary(gate) {
+                state.single_qubit_gate(&qubit, &u)
+            } else if let Some((qubit_0, qubit_1, u)) = two_qubit_unitary(gate) {
+                state.two_qubit_gate(&qubit_0, &qubit_1, &u)
+            }
+        }
+    }
+    Ok(())
+}
 
-        Operations::X(qubit) => state.single_qubit_gate(qubit, &SIGMA_X),
-        Operations::Y(qubit) => state.single_qubit_gate(qubit, &SIGMA_Y),
-        Operations::Z(qubit) => state.single_qubit_gate(qubit, &SIGMA_Z),
-        Operations::S(qubit) => state.single_qubit_gate(qubit, &S),
-        Operations::H(qubit) => state.single_qubit_gate(qubit, &H),
+/// The matrix of a rotation by angle about the x axis.
+pub fn rx_matrix(angle: &Angle) -> Matrix2x2 {
+    IDENTITY * c!((angle / 2.).cos(), 0.) - SIGMA_X * c!(0., (angle / 2.).sin())
+}
 
-        Operations::RX(qubit, angle) => {
-            let u = &IDENTITY * c!((angle / 2.).cos(), 0.) - &SIGMA_X * c!(0., (angle / 2.).sin());
-            state.single_qubit_gate(qubit, &u)
-        }
+/// The matrix of a rotation by angle about the y axis.
+pub fn ry_matrix(angle: &Angle) -> Matrix2x2 {
+    IDENTITY * c!((angle / 2.).cos(), 0.) - SIGMA_Y * c!(0., (angle / 2.).sin())
+}
 
-        Operations::RY(qubit, angle) => {
-            let u = &IDENTITY * c!((angle / 2.).cos(), 0.) - &SIGMA_Y * c!(0., (angle / 2.).sin());
-            state.single_qubit_gate(qubit, &u)
-        }
+/// The matrix of a rotation by angle about the z axis.
+pub fn rz_matrix(angle: &Angle) -> Matrix2x2 {
+    IDENTITY * c!((angle / 2.).cos(), 0.) - SIGMA_Z * c!(0., (angle / 2.).sin())
+}
 
-        Operations::RZ(qubit, angle) => {
-            let u = &IDENTITY * c!((angle / 2.).cos(), 0.) - &SIGMA_Z * c!(0., (angle / 2.).sin());
-            state.single_qubit_gate(qubit, &u)
-        }
-        Operations::R(qubit, phi, theta, omega) => {
-            let (c_theta, s_theta) = ((theta / 2.).cos(), (theta / 2.).sin());
-            let (c_plus, s_plus) = (((phi + omega) / 2.).cos(), ((phi + omega) / 2.).sin());
-            let (c_minus, s_minus) = (((phi - omega) / 2.).cos(), ((phi - omega) / 2.).sin());
-
-            let u: Matrix2x2 = matrix![
-                c!(c_plus, -s_plus) * c_theta, -c!(c_minus, s_minus) * s_theta;
-                c!(c_minus, -s_minus) * s_theta,  c!(c_plus, s_plus) * c_theta
-            ];
-            state.single_qubit_gate(qubit, &u)
-        }
-        Operations::ArbitrarySingle(qubit, u) => state.single_qubit_gate(qubit, u),
-        Operations::CNOT(control, target) => state.two_qubit_gate(target, control, &CNOT),
-        Operations::CZ(control, target) => state.two_qubit_gate(target, control, &CZ),
-        Operations::CRZ(control, target, angle) => {
-            let u: Matrix4x4 = matrix![
-                c!(1., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-                c!(0., 0.), c!(1., 0.), c!(0., 0.), c!(0., 0.);
-                c!(0., 0.), c!(0., 0.), c!(1., 0.), c!(0., 0.);
-                c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(angle.cos(), angle.sin());
-            ];
-            state.two_qubit_gate(target, control, &u)
-        },
-        Operations::ISWAP(control, target) => state.two_qubit_gate(target, control, &ISWAP),
-        Operations::SISWAP(control, target) => state.two_qubit_gate(target, control, &SISWAP),
-        Operations::SWAP(control, target) => state.two_qubit_gate(target, control, &SWAP),
-        Operations::ArbitaryTwo(control, target, u) => state.two_qubit_gate(control, target, u),
+/// The matrix of the general rotation R(phi, theta, omega) = RZ(omega) RY(theta) RZ(phi).
+pub fn r_matrix(phi: &Angle, theta: &Angle, omega: &Angle) -> Matrix2x2 {
+    let (c_theta, s_theta) = ((theta / 2.).cos(), (theta / 2.).sin());
+    let (c_plus, s_plus) = (((phi + omega) / 2.).cos(), ((phi + omega) / 2.).sin());
+    let (c_minus, s_minus) = (((phi - omega) / 2.).cos(), ((phi - omega) / 2.).sin());
+
+    matrix![
+        c!(c_plus, -s_plus) * c_theta, -c!(c_minus, s_minus) * s_theta;
+        c!(c_minus, -s_minus) * s_theta,  c!(c_plus, s_plus) * c_theta
+    ]
+}
+
+/// The matrix of the phase gate diag(1, e^(i lambda)).
+pub fn phase_matrix(lambda: &Angle) -> Matrix2x2 {
+    matrix![
+        c!(1., 0.), c!(0., 0.);
+        c!(0., 0.), expi!(lambda);
+    ]
+}
+
+/// The matrix of U3(theta, phi, lambda).
+pub fn u3_matrix(theta: &Angle, phi: &Angle, lambda: &Angle) -> Matrix2x2 {
+    let (c_theta, s_theta) = ((theta / 2.).cos(), (theta / 2.).sin());
+    matrix![
+        c!(c_theta, 0.), -expi!(lambda) * s_theta;
+        expi!(phi) * s_theta, expi!(phi + lambda) * c_theta;
+    ]
+}
+
+/// The matrix applying u to the second qubit when the first is set, with the first qubit on the left.
+pub fn controlled_matrix(u: &Matrix2x2) -> Matrix4x4 {
+    Matrix4x4::from_fn(|i, j| match (i >> 1, j >> 1) {
+        (0, 0) => IDENTITY[(i & 1, j & 1)],
+        (1, 1) => u[(i & 1, j & 1)],
+        _ => c!(0., 0.),
+    })
+}
+
+/// The matrix of exp(-i theta / 2 P P) for a pauli matrix P.
+fn ising_matrix(theta: &Angle, pauli: &Matrix2x2) -> Matrix4x4 {
+    let pp: Matrix4x4 = pauli.kronecker(pauli);
+    Matrix4x4::identity() * c!((theta / 2.).cos(), 0.) - pp * c!(0., (theta / 2.).sin())
+}
+
+/// The matrix of the fermionic simulation gate fSim(theta, phi).
+pub fn fsim_matrix(theta: &Angle, phi: &Angle) -> Matrix4x4 {
+    let (c, s) = (theta.cos(), theta.sin());
+    matrix![
+        c!(1., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
+        c!(0., 0.), c!(c, 0.), c!(0., -s), c!(0., 0.);
+        c!(0., 0.), c!(0., -s), c!(c, 0.), c!(0., 0.);
+        c!(0., 0.), c!(0., 0.), c!(0., 0.), expi!(-phi);
+    ]
+}
+
+/// The matrix of a Givens rotation by theta, with the first qubit on the left.
+pub fn givens_matrix(theta: &Angle) -> Matrix4x4 {
+    let (c, s) = (theta.cos(), theta.sin());
+    matrix![
+        c!(1., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
+        c!(0., 0.), c!(c, 0.), c!(-s, 0.), c!(0., 0.);
+        c!(0., 0.), c!(s, 0.), c!(c, 0.), c!(0., 0.);
+        c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(1., 0.);
+    ]
+}
+
+/// Returns the qubit and unitary matrix of a single qubit gate, or None if the operation
+/// is not a single qubit gate.
+pub fn single_qubit_unitary(gate: &Operations) -> Option<(usize, Matrix2x2)> {
+    match gate {
+        Operations::X(qubit) => Some((*qubit, SIGMA_X)),
+        Operations::Y(qubit) => Some((*qubit, SIGMA_Y)),
+        Operations::Z(qubit) => Some((*qubit, SIGMA_Z)),
+        Operations::S(qubit) => Some((*qubit, S)),
+        Operations::Sdg(qubit) => Some((*qubit, SDG)),
+        Operations::T(qubit) => Some((*qubit, T)),
+        Operations::Tdg(qubit) => Some((*qubit, TDG)),
+        Operations::SX(qubit) => Some((*qubit, SX)),
+        Operations::SXdg(qubit) => Some((*qubit, SXDG)),
+        Operations::H(qubit) => Some((*qubit, H)),
+        Operations::RX(qubit, angle) => Some((*qubit, rx_matrix(angle))),
+        Operations::RY(qubit, angle) => Some((*qubit, ry_matrix(angle))),
+        Operations::RZ(qubit, angle) => Some((*qubit, rz_matrix(angle))),
+        Operations::R(qubit, phi, theta, omega) => Some((*qubit, r_matrix(phi, theta, omega))),
+        Operations::Phase(qubit, lambda) | Operations::U1(qubit, lambda) => Some((*qubit, phase_matrix(lambda))),
+        Operations::U2(qubit, phi, lambda) => Some((*qubit, u3_matrix(&(PI / 2.), phi, lambda))),
+        Operations::U3(qubit, theta, phi, lambda) => Some((*qubit, u3_matrix(theta, phi, lambda))),
+        Operations::ArbitrarySingle(qubit, u) => Some((*qubit, u.to_owned())),
+        _ => None,
+    }
+}
+
+/// Returns the two qubits and unitary matrix of a two qubit gate, or None if the operation
+/// is not a two qubit gate. The rows and columns of the matrix are indexed by
+/// bit(qubit_0) + 2 * bit(qubit_1), so the matrices of gates written with their first qubit on the
+/// left are returned with the qubits in reverse order.
+pub fn two_qubit_unitary(gate: &Operations) -> Option<(usize, usize, Matrix4x4)> {
+    match gate {
+        Operations::CNOT(control, target) => Some((*target, *control, CNOT)),
+        Operations::CZ(control, target) => Some((*target, *control, CZ)),
+        Operations::CY(control, target) => Some((*target, *control, CY)),
+        Operations::CH(control, target) => Some((*target, *control, CH)),
+        Operations::CRX(control, target, angle) => Some((*target, *control, controlled_matrix(&rx_matrix(angle)))),
+        Operations::CRY(control, target, angle) => Some((*target, *control, controlled_matrix(&ry_matrix(angle)))),
+        Operations::CRZ(control, target, angle) => Some((*target, *control, controlled_matrix(&rz_matrix(angle)))),
+        Operations::CPhase(control, target, angle) => Some((*target, *control, controlled_matrix(&phase_matrix(angle)))),
+        Operations::ISWAP(control, target) => Some((*target, *control, ISWAP)),
+        Operations::SISWAP(control, target) => Some((*target, *control, SISWAP)),
+        Operations::SWAP(control, target) => Some((*target, *control, SWAP)),
+        Operations::RXX(qubit_0, qubit_1, theta) => Some((*qubit_1, *qubit_0, ising_matrix(theta, &SIGMA_X))),
+        Operations::RYY(qubit_0, qubit_1, theta) => Some((*qubit_1, *qubit_0, ising_matrix(theta, &SIGMA_Y))),
+        Operations::RZZ(qubit_0, qubit_1, theta) => Some((*qubit_1, *qubit_0, ising_matrix(theta, &SIGMA_Z))),
+        Operations::FSim(qubit_0, qubit_1, theta, phi) => Some((*qubit_1, *qubit_0, fsim_matrix(theta, phi))),
+        Operations::Givens(qubit_0, qubit_1, theta) => Some((*qubit_1, *qubit_0, givens_matrix(theta))),
+        Operations::ECR(qubit_0, qubit_1) => Some((*qubit_1, *qubit_0, ECR)),
+        Operations::ArbitaryTwo(control, target, u) => Some((*control, *target, u.to_owned())),
+        _ => None,
     }
 }
 
@@ -106,6 +271,10 @@ pub fn which_qubits(gate: &Operations) -> Vec<&usize> {
     match gate {
         Operations::Barrier => vec![],
         Operations::Measure(qubit) => vec![qubit],
+        Operations::MeasureInto(qubit, _) => vec![qubit],
+        Operations::MeasureX(qubit) => vec![qubit],
+        Operations::MeasureY(qubit) => vec![qubit],
+        Operations::Reset(qubit) => vec![qubit],
         Operations::MeasureAll => vec![],
         Operations::ResetAll => vec![],
 
@@ -113,20 +282,254 @@ pub fn which_qubits(gate: &Operations) -> Vec<&usize> {
         Operations::Y(qubit) => vec![qubit],
         Operations::Z(qubit) => vec![qubit],
         Operations::S(qubit) => vec![qubit],
+        Operations::Sdg(qubit) => vec![qubit],
+        Operations::T(qubit) => vec![qubit],
+        Operations::Tdg(qubit) => vec![qubit],
+        Operations::SX(qubit) => vec![qubit],
+        Operations::SXdg(qubit) => vec![qubit],
         Operations::H(qubit) => vec![qubit],
 
         Operations::RX(qubit, _) => vec![qubit],
         Operations::RY(qubit, _) => vec![qubit],
         Operations::RZ(qubit, _) => vec![qubit],
         Operations::R(qubit, _, _, _) => vec![qubit],
+        Operations::Phase(qubit, _) => vec![qubit],
+        Operations::U1(qubit, _) => vec![qubit],
+        Operations::U2(qubit, _, _) => vec![qubit],
+        Operations::U3(qubit, _, _, _) => vec![qubit],
         Operations::ArbitrarySingle(qubit, _) => vec![qubit],
 
         Operations::CZ(control, target) => vec![control, target],
         Operations::CNOT(control, target) => vec![control, target],
+        Operations::CY(control, target) => vec![control, target],
+        Operations::CH(control, target) => vec![control, target],
+        Operations::CRX(control, target, _) => vec![control, target],
+        Operations::CRY(control, target, _) => vec![control, target],
         Operations::CRZ(control, target, _) => vec![control, target],
+        Operations::CPhase(control, target, _) => vec![control, target],
         Operations::ISWAP(control, target) => vec![control, target],
         Operations::SISWAP(control, target) => vec![control, target],
         Operations::SWAP(control, target) => vec![control, target],
         Operations::ArbitaryTwo(control, target, _) => vec![control, target],
+        Operations::RXX(qubit_0, qubit_1, _) => vec![qubit_0, qubit_1],
+        Operations::RYY(qubit_0, qubit_1, _) => vec![qubit_0, qubit_1],
+        Operations::RZZ(qubit_0, qubit_1, _) => vec![qubit_0, qubit_1],
+        Operations::FSim(qubit_0, qubit_1, _, _) => vec![qubit_0, qubit_1],
+        Operations::Givens(qubit_0, qubit_1, _) => vec![qubit_0, qubit_1],
+        Operations::ECR(qubit_0, qubit_1) => vec![qubit_0, qubit_1],
+
+        Operations::Subcircuit(_, qubits) => qubits.iter().collect(),
+    }
+}
+
+/// The angles parametrising the gate, empty for fixed gates.
+pub fn gate_angles(gate: &Operations) -> Vec<Angle> {
+    match gate {
+        Operations::RX(_, angle)
+        | Operations::RY(_, angle)
+        | Operations::RZ(_, angle)
+        | Operations::Phase(_, angle)
+        | Operations::U1(_, angle)
+        | Operations::CRX(_, _, angle)
+        | Operations::CRY(_, _, angle)
+        | Operations::CRZ(_, _, angle)
+        | Operations::CPhase(_, _, angle)
+        | Operations::RXX(_, _, angle)
+        | Operations::RYY(_, _, angle)
+        | Operations::RZZ(_, _, angle)
+        | Operations::Givens(_, _, angle) => vec![*angle],
+        Operations::U2(_, phi, lambda) => vec![*phi, *lambda],
+        Operations::FSim(_, _, theta, phi) => vec![*theta, *phi],
+        Operations::R(_, phi, theta, omega) => vec![*phi, *theta, *omega],
+        Operations::U3(_, theta, phi, lambda) => vec![*theta, *phi, *lambda],
+
+        Operations::Barrier
+        | Operations::Measure(_)
+        | Operations::MeasureInto(_, _)
+        | Operations::MeasureX(_)
+        | Operations::MeasureY(_)
+        | Operations::Reset(_)
+        | Operations::MeasureAll
+        | Operations::ResetAll
+        | Operations::X(_)
+        | Operations::Y(_)
+        | Operations::Z(_)
+        | Operations::S(_)
+        | Operations::Sdg(_)
+        | Operations::T(_)
+        | Operations::Tdg(_)
+        | Operations::SX(_)
+        | Operations::SXdg(_)
+        | Operations::H(_)
+        | Operations::ArbitrarySingle(_, _)
+        | Operations::CZ(_, _)
+        | Operations::CNOT(_, _)
+        | Operations::CY(_, _)
+        | Operations::CH(_, _)
+        | Operations::ISWAP(_, _)
+        | Operations::SISWAP(_, _)
+        | Operations::SWAP(_, _)
+        | Operations::ArbitaryTwo(_, _, _)
+        | Operations::ECR(_, _)
+        | Operations::Subcircuit(_, _) => vec![],
+    }
+}
+
+/// Returns a copy of the gate with every qubit index passed through map.
+pub fn map_qubits<F: Fn(usize) -> usize>(gate: &Operations, map: F) -> Operations {
+    match gate {
+        Operations::Barrier => Operations::Barrier,
+        Operations::Measure(qubit) => Operations::Measure(map(*qubit)),
+        Operations::MeasureInto(qubit, bit) => Operations::MeasureInto(map(*qubit), *bit),
+        Operations::MeasureX(qubit) => Operations::MeasureX(map(*qubit)),
+        Operations::MeasureY(qubit) => Operations::MeasureY(map(*qubit)),
+        Operations::Reset(qubit) => Operations::Reset(map(*qubit)),
+        Operations::MeasureAll => Operations::MeasureAll,
+        Operations::ResetAll => Operations::ResetAll,
+
+        Operations::X(qubit) => Operations::X(map(*qubit)),
+        Operations::Y(qubit) => Operations::Y(map(*qubit)),
+        Operations::Z(qubit) => Operations::Z(map(*qubit)),
+        Operations::S(qubit) => Operations::S(map(*qubit)),
+        Operations::Sdg(qubit) => Operations::Sdg(map(*qubit)),
+        Operations::T(qubit) => Operations::T(map(*qubit)),
+        Operations::Tdg(qubit) => Operations::Tdg(map(*qubit)),
+        Operations::SX(qubit) => Operations::SX(map(*qubit)),
+        Operations::SXdg(qubit) => Operations::SXdg(map(*qubit)),
+        Operations::H(qubit) => Operations::H(map(*qubit)),
+
+        Operations::RX(qubit, angle) => Operations::RX(map(*qubit), *angle),
+        Operations::RY(qubit, angle) => Operations::RY(map(*qubit), *angle),
+        Operations::RZ(qubit, angle) => Operations::RZ(map(*qubit), *angle),
+        Operations::R(qubit, phi, theta, omega) => Operations::R(map(*qubit), *phi, *theta, *omega),
+        Operations::Phase(qubit, lambda) => Operations::Phase(map(*qubit), *lambda),
+        Operations::U1(qubit, lambda) => Operations::U1(map(*qubit), *lambda),
+        Operations::U2(qubit, phi, lambda) => Operations::U2(map(*qubit), *phi, *lambda),
+        Operations::U3(qubit, theta, phi, lambda) => Operations::U3(map(*qubit), *theta, *phi, *lambda),
+        Operations::ArbitrarySingle(qubit, u) => Operations::ArbitrarySingle(map(*qubit), *u),
+
+        Operations::CZ(control, target) => Operations::CZ(map(*control), map(*target)),
+        Operations::CNOT(control, target) => Operations::CNOT(map(*control), map(*target)),
+        Operations::CY(control, target) => Operations::CY(map(*control), map(*target)),
+        Operations::CH(control, target) => Operations::CH(map(*control), map(*target)),
+        Operations::CRX(control, target, angle) => Operations::CRX(map(*control), map(*target), *angle),
+        Operations::CRY(control, target, angle) => Operations::CRY(map(*control), map(*target), *angle),
+        Operations::CRZ(control, target, angle) => Operations::CRZ(map(*control), map(*target), *angle),
+        Operations::CPhase(control, target, angle) => Operations::CPhase(map(*control), map(*target), *angle),
+        Operations::ISWAP(control, target) => Operations::ISWAP(map(*control), map(*target)),
+        Operations::SISWAP(control, target) => Operations::SISWAP(map(*control), map(*target)),
+        Operations::SWAP(control, target) => Operations::SWAP(map(*control), map(*target)),
+        Operations::ArbitaryTwo(control, target, u) => Operations::ArbitaryTwo(map(*control), map(*target), *u),
+        Operations::RXX(qubit_0, qubit_1, theta) => Operations::RXX(map(*qubit_0), map(*qubit_1), *theta),
+        Operations::RYY(qubit_0, qubit_1, theta) => Operations::RYY(map(*qubit_0), map(*qubit_1), *theta),
+        Operations::RZZ(qubit_0, qubit_1, theta) => Operations::RZZ(map(*qubit_0), map(*qubit_1), *theta),
+        Operations::FSim(qubit_0, qubit_1, theta, phi) => Operations::FSim(map(*qubit_0), map(*qubit_1), *theta, *phi),
+        Operations::Givens(qubit_0, qubit_1, theta) => Operations::Givens(map(*qubit_0), map(*qubit_1), *theta),
+        Operations::ECR(qubit_0, qubit_1) => Operations::ECR(map(*qubit_0), map(*qubit_1)),
+
+        Operations::Subcircuit(subcircuit, qubits) => {
+            Operations::Subcircuit(subcircuit.clone(), qubits.iter().map(|qubit| map(*qubit)).collect())
+        }
+    }
+}
+
+/// Returns the inverse (hermitian conjugate) of a gate, or None if the operation is not unitary.
+pub fn dagger(gate: &Operations) -> Option<Operations> {
+    match gate {
+        Operations::Barrier => Some(Operations::Barrier),
+        Operations::Measure(_) | Operations::MeasureInto(_, _) | Operations::MeasureAll | Operations::ResetAll => None,
+        Operations::MeasureX(_) | Operations::MeasureY(_) | Operations::Reset(_) => None,
+
+        Operations::X(_) | Operations::Y(_) | Operations::Z(_) | Operations::H(_) => Some(gate.to_owned()),
+        Operations::S(qubit) => Some(Operations::Sdg(*qubit)),
+        Operations::Sdg(qubit) => Some(Operations::S(*qubit)),
+        Operations::T(qubit) => Some(Operations::Tdg(*qubit)),
+        Operations::Tdg(qubit) => Some(Operations::T(*qubit)),
+        Operations::SX(qubit) => Some(Operations::SXdg(*qubit)),
+        Operations::SXdg(qubit) => Some(Operations::SX(*qubit)),
+        Operations::RX(qubit, angle) => Some(Operations::RX(*qubit, -angle)),
+        Operations::RY(qubit, angle) => Some(Operations::RY(*qubit, -angle)),
+        Operations::RZ(qubit, angle) => Some(Operations::RZ(*qubit, -angle)),
+        Operations::R(qubit, phi, theta, omega) => Some(Operations::R(*qubit, -omega, -theta, -phi)),
+        Operations::Phase(qubit, lambda) => Some(Operations::Phase(*qubit, -lambda)),
+        Operations::U1(qubit, lambda) => Some(Operations::U1(*qubit, -lambda)),
+        Operations::U2(qubit, phi, lambda) => Some(Operations::U3(*qubit, -PI / 2., -lambda, -phi)),
+        Operations::U3(qubit, theta, phi, lambda) => Some(Operations::U3(*qubit, -theta, -lambda, -phi)),
+        Operations::ArbitrarySingle(qubit, u) => Some(Operations::ArbitrarySingle(*qubit, u.adjoint())),
+
+        Operations::CNOT(_, _) | Operations::CZ(_, _) | Operations::CY(_, _) | Operations::CH(_, _) => Some(gate.to_owned()),
+        Operations::SWAP(_, _) | Operations::ECR(_, _) => Some(gate.to_owned()),
+        Operations::CRX(control, target, angle) => Some(Operations::CRX(*control, *target, -angle)),
+        Operations::CRY(control, target, angle) => Some(Operations::CRY(*control, *target, -angle)),
+        Operations::CRZ(control, target, angle) => Some(Operations::CRZ(*control, *target, -angle)),
+        Operations::CPhase(control, target, angle) => Some(Operations::CPhase(*control, *target, -angle)),
+        Operations::RXX(qubit_0, qubit_1, theta) => Some(Operations::RXX(*qubit_0, *qubit_1, -theta)),
+        Operations::RYY(qubit_0, qubit_1, theta) => Some(Operations::RYY(*qubit_0, *qubit_1, -theta)),
+        Operations::RZZ(qubit_0, qubit_1, theta) => Some(Operations::RZZ(*qubit_0, *qubit_1, -theta)),
+        Operations::FSim(qubit_0, qubit_1, theta, phi) => Some(Operations::FSim(*qubit_0, *qubit_1, -theta, -phi)),
+        Operations::Givens(qubit_0, qubit_1, theta) => Some(Operations::Givens(*qubit_0, *qubit_1, -theta)),
+        Operations::ISWAP(_, _) | Operations::SISWAP(_, _) | Operations::ArbitaryTwo(_, _, _) => {
+            two_qubit_unitary(gate).map(|(qubit_0, qubit_1, u)| Operations::ArbitaryTwo(qubit_0, qubit_1, u.adjoint()))
+        }
+
+        Operations::Subcircuit(subcircuit, qubits) => {
+            subcircuit.inverse().map(|inverse| Operations::Subcircuit(Arc::new(inverse), qubits.clone()))
+        }
+    }
+}
+
+/// The name of the gate, without its qubits or parameters.
+pub fn gate_name(gate: &Operations) -> &'static str {
+    match gate {
+        Operations::Barrier => "Barrier",
+        Operations::Measure(_) => "Measure",
+        Operations::MeasureInto(_, _) => "MeasureInto",
+        Operations::MeasureX(_) => "MeasureX",
+        Operations::MeasureY(_) => "MeasureY",
+        Operations::Reset(_) => "Reset",
+        Operations::MeasureAll => "MeasureAll",
+        Operations::ResetAll => "ResetAll",
+
+        Operations::X(_) => "X",
+        Operations::Y(_) => "Y",
+        Operations::Z(_) => "Z",
+        Operations::S(_) => "S",
+        Operations::Sdg(_) => "Sdg",
+        Operations::T(_) => "T",
+        Operations::Tdg(_) => "Tdg",
+        Operations::SX(_) => "SX",
+        Operations::SXdg(_) => "SXdg",
+        Operations::H(_) => "H",
+
+        Operations::RX(_, _) => "RX",
+        Operations::RY(_, _) => "RY",
+        Operations::RZ(_, _) => "RZ",
+        Operations::R(_, _, _, _) => "R",
+        Operations::Phase(_, _) => "Phase",
+        Operations::U1(_, _) => "U1",
+        Operations::U2(_, _, _) => "U2",
+        Operations::U3(_, _, _, _) => "U3",
+        Operations::ArbitrarySingle(_, _) => "ArbitrarySingle",
+
+        Operations::CZ(_, _) => "CZ",
+        Operations::CNOT(_, _) => "CNOT",
+        Operations::CY(_, _) => "CY",
+        Operations::CH(_, _) => "CH",
+        Operations::CRX(_, _, _) => "CRX",
+        Operations::CRY(_, _, _) => "CRY",
+        Operations::CRZ(_, _, _) => "CRZ",
+        Operations::CPhase(_, _, _) => "CPhase",
+        Operations::ISWAP(_, _) => "ISWAP",
+        Operations::SISWAP(_, _) => "SISWAP",
+        Operations::SWAP(_, _) => "SWAP",
+        Operations::ArbitaryTwo(_, _, _) => "ArbitaryTwo",
+        Operations::RXX(_, _, _) => "RXX",
+        Operations::RYY(_, _, _) => "RYY",
+        Operations::RZZ(_, _, _) => "RZZ",
+        Operations::FSim(_, _, _, _) => "FSim",
+        Operations::Givens(_, _, _) => "Givens",
+        Operations::ECR(_, _) => "ECR",
+
+        Operations::Subcircuit(_, _) => "Subcircuit",
     }
 }
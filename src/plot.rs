@@ -0,0 +1,264 @@
+use std::path::Path;
+
+use gnuplot::{AutoOption, Axes2D, Axes3D, AxesCommon, Caption, Color, Figure, FillAlpha, LabelOption, PaletteType, PlotOption, Tick};
+
+use crate::basis::Endianness;
+use crate::error::BraKetError;
+use crate::state_traits::StateTraits;
+use crate::types::*;
+use crate::{DensityMatrix, StateVector};
+
+/// Plots of a quantum state drawn with gnuplot, which must be installed. The image is written as a PNG or
+/// an SVG file depending on the extension of the path. Basis states are labelled by bitstrings in the
+/// endianness of the state.
+pub trait Plot {
+    /// A histogram of the probabilities of measuring each basis state.
+    fn plot_probabilities<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError>;
+    /// The real and imaginary parts of the density matrix as two 3D bar charts.
+    fn plot_cityscape<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError>;
+    /// The real and imaginary parts of the density matrix as two heatmaps.
+    fn plot_heatmap<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError>;
+    /// The Bloch vector of the reduced state of each qubit, drawn in a Bloch sphere per qubit. The vector is
+    /// shorter than the radius of the sphere when the qubit is entangled with the others or mixed.
+    fn plot_bloch_spheres<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError>;
+}
+
+/// The labels of the basis states of number_of_qubits qubits, or none if there are too many to read.
+fn bitstring_ticks(number_of_qubits: usize, endianness: Endianness, most: usize) -> Vec<Tick<Real, String>> {
+    let dimension = 1 << number_of_qubits;
+    match dimension <= most {
+        true => (0..dimension).map(|index| Tick::Major(index as Real, AutoOption::Fix(endianness.bitstring(index, number_of_qubits)))).collect(),
+        false => vec![],
+    }
+}
+
+/// Writes the figure to a PNG or SVG file of the given size in pixels.
+fn save(figure: &mut Figure, path: &Path, width: u32, height: u32) -> Result<(), BraKetError> {
+    let extension = path.extension().and_then(|extension| extension.to_str()).map(|extension| extension.to_ascii_lowercase());
+    let result = match extension.as_deref() {
+        Some("png") => figure.save_to_png(path, width, height),
+        Some("svg") => figure.save_to_svg(path, width, height),
+        _ => return Err(BraKetError::Unsupported("plot files other than .png and .svg")),
+    };
+    result.map_err(|error| BraKetError::Io(error.to_string()))?;
+    match path.exists() {
+        true => Ok(()),
+        false => Err(BraKetError::Io(format!("gnuplot did not write {}", path.display()))),
+    }
+}
+
+fn probability_figure(probabilities: &[Real], number_of_qubits: usize, endianness: Endianness) -> Figure {
+    let mut figure = Figure::new();
+    let axes = figure.axes2d();
+    axes.set_title("Probabilities", &[])
+        .set_x_label("basis state", &[])
+        .set_y_label("probability", &[])
+        .set_x_range(AutoOption::Fix(-0.5), AutoOption::Fix(probabilities.len() as Real - 0.5))
+        .set_y_range(AutoOption::Fix(0.), AutoOption::Fix(1.))
+        .boxes_set_width(0..probabilities.len(), probabilities, probabilities.iter().map(|_| 0.8), &[Color("#3465a4"), FillAlpha(0.8)]);
+    let ticks = bitstring_ticks(number_of_qubits, endianness, 64);
+    if !ticks.is_empty() {
+        let rotation = if number_of_qubits > 3 { 90. } else { 0. };
+        axes.set_x_ticks_custom(ticks, &[], &[LabelOption::Rotate(rotation)]);
+    }
+    figure
+}
+
+/// A diverging palette, blue for negative values and red for positive ones.
+const DIVERGING: PaletteType<&[(f32, f32, f32, f32)]> = PaletteType::Custom(&[(0., 0.13, 0.4, 0.67), (0.5, 1., 1., 1.), (1., 0.7, 0.09, 0.17)]);
+
+/// The real and imaginary parts of the elements of a density matrix, row by row, with the largest magnitude
+/// of any of them.
+fn parts(density_matrix: &CMatrix) -> (Vec<Real>, Vec<Real>, Real) {
+    let elements: Vec<Complex> = density_matrix.transpose().iter().cloned().collect();
+    let real: Vec<Real> = elements.iter().map(|element| element.re).collect();
+    let imaginary: Vec<Real> = elements.iter().map(|element| element.im).collect();
+    let largest = real.iter().chain(imaginary.iter()).fold(0., |largest: Real, value| largest.max(value.abs()));
+    (real, imaginary, if largest > 0. { largest } else { 1. })
+}
+
+fn heatmap_figure(density_matrix: &CMatrix, number_of_qubits: usize, endianness: Endianness) -> Figure {
+    let dimension = density_matrix.nrows();
+    let (real, imaginary, largest) = parts(density_matrix);
+    let mut figure = Figure::new();
+    figure.set_multiplot_layout(1, 2);
+    for (part, title) in [(real, "Re ρ"), (imaginary, "Im ρ")] {
+        let axes: &mut Axes2D = figure.axes2d();
+        axes.set_title(title, &[])
+            .set_palette(DIVERGING)
+            .set_cb_range(AutoOption::Fix(-largest), AutoOption::Fix(largest))
+            .set_aspect_ratio(AutoOption::Fix(1.))
+            .set_y_reverse(true)
+            .image(part, dimension, dimension, Some((0., 0., dimension as Real - 1., dimension as Real - 1.)), &[]);
+        let ticks = bitstring_ticks(number_of_qubits, endianness, 16);
+        if !ticks.is_empty() {
+            axes.set_x_ticks_custom(ticks.iter(), &[], &[LabelOption::Rotate(90.)]).set_y_ticks_custom(ticks.iter(), &[], &[]);
+        }
+    }
+    figure
+}
+
+fn cityscape_figure(density_matrix: &CMatrix, number_of_qubits: usize, endianness: Endianness) -> Figure {
+    let dimension = density_matrix.nrows();
+    let (real, imaginary, largest) = parts(density_matrix);
+    // each element is sampled on a square of points, so that the surface has flat tops and steep walls
+    let samples = (256 / dimension).clamp(2, 8);
+    let size = dimension * samples;
+    let edge = 0.5 - 0.5 / samples as Real;
+    let mut figure = Figure::new();
+    figure.set_multiplot_layout(1, 2);
+    for (part, title) in [(real, "Re ρ"), (imaginary, "Im ρ")] {
+        let heights = (0..size * size).map(|n| part[(n / size / samples) * dimension + (n % size) / samples]);
+        let axes: &mut Axes3D = figure.axes3d();
+        axes.set_title(title, &[])
+            .set_palette(DIVERGING)
+            .set_cb_range(AutoOption::Fix(-largest), AutoOption::Fix(largest))
+            .set_z_range(AutoOption::Fix(-largest), AutoOption::Fix(largest))
+            .set_view(60., 30.)
+            .surface(heights, size, size, Some((-edge, -edge, dimension as Real - 1. + edge, dimension as Real - 1. + edge)), &[]);
+        let ticks = bitstring_ticks(number_of_qubits, endianness, 16);
+        if !ticks.is_empty() {
+            axes.set_x_ticks_custom(ticks.iter(), &[], &[]).set_y_ticks_custom(ticks.iter(), &[], &[]);
+        }
+    }
+    figure
+}
+
+/// The Bloch vector (x, y, z) of a single qubit density matrix.
+fn bloch_vector(density_matrix: &CMatrix) -> [Real; 3] {
+    let coherence = density_matrix[(0, 1)];
+    [2. * coherence.re, -2. * coherence.im, (density_matrix[(0, 0)] - density_matrix[(1, 1)]).re]
+}
+
+fn bloch_figure(vectors: &[[Real; 3]]) -> Figure {
+    let columns = vectors.len().clamp(1, 4);
+    let rows = vectors.len().div_ceil(columns).max(1);
+    let circle: Vec<Real> = (0..=64).map(|n| 2. * PI * n as Real / 64.).collect();
+    let (cos, sin): (Vec<Real>, Vec<Real>) = circle.iter().map(|angle| (angle.cos(), angle.sin())).unzip();
+    let zeros = vec![0.; circle.len()];
+    let grey: [PlotOption<&str>; 1] = [Color("grey")];
+
+    let mut figure = Figure::new();
+    figure.set_multiplot_layout(rows, columns);
+    for (qubit, [x, y, z]) in vectors.iter().enumerate() {
+        let axes = figure.axes3d();
+        axes.set_title(&format!("qubit {}", qubit), &[])
+            .set_x_range(AutoOption::Fix(-1.), AutoOption::Fix(1.))
+            .set_y_range(AutoOption::Fix(-1.), AutoOption::Fix(1.))
+            .set_z_range(AutoOption::Fix(-1.), AutoOption::Fix(1.))
+            .set_x_ticks(None, &[], &[])
+            .set_y_ticks(None, &[], &[])
+            .set_z_ticks(None, &[], &[])
+            .set_x_label("x", &[])
+            .set_y_label("y", &[])
+            .set_z_label("z", &[])
+            .set_view(70., 30.)
+            .lines(&cos, &sin, &zeros, &grey)
+            .lines(&cos, &zeros, &sin, &grey)
+            .lines(&zeros, &cos, &sin, &grey)
+            .lines([-1., 1.], [0., 0.], [0., 0.], &grey)
+            .lines([0., 0.], [-1., 1.], [0., 0.], &grey)
+            .lines([0., 0.], [0., 0.], [-1., 1.], &grey)
+            .lines([0., *x], [0., *y], [0., *z], &[Color("#cc0000"), PlotOption::LineWidth(2.)])
+            .points([*x], [*y], [*z], &[Color("#cc0000"), PlotOption::PointSymbol('O'), Caption("")]);
+    }
+    figure
+}
+
+impl Plot for StateVector {
+    fn plot_probabilities<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        save(&mut probability_figure(self.probabilities().as_slice(), self.number_of_qubits, self.endianness), path.as_ref(), 800, 500)
+    }
+
+    fn plot_cityscape<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        DensityMatrix::from(self.clone()).plot_cityscape(path)
+    }
+
+    fn plot_heatmap<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        DensityMatrix::from(self.clone()).plot_heatmap(path)
+    }
+
+    fn plot_bloch_spheres<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        let vectors: Result<Vec<[Real; 3]>, BraKetError> = (0..self.number_of_qubits)
+            .map(|qubit| Ok(bloch_vector(&self.reduced_density_matrix(&[qubit])?.density_matrix)))
+            .collect();
+        save_bloch_spheres(&vectors?, path.as_ref())
+    }
+}
+
+impl Plot for DensityMatrix {
+    fn plot_probabilities<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        save(&mut probability_figure(self.probabilities().as_slice(), self.number_of_qubits, self.endianness), path.as_ref(), 800, 500)
+    }
+
+    fn plot_cityscape<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        save(&mut cityscape_figure(&self.density_matrix, self.number_of_qubits, self.endianness), path.as_ref(), 1200, 550)
+    }
+
+    fn plot_heatmap<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        save(&mut heatmap_figure(&self.density_matrix, self.number_of_qubits, self.endianness), path.as_ref(), 1100, 500)
+    }
+
+    fn plot_bloch_spheres<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        let vectors: Result<Vec<[Real; 3]>, BraKetError> = (0..self.number_of_qubits)
+            .map(|qubit| {
+                let others: Vec<usize> = (0..self.number_of_qubits).filter(|other| *other != qubit).collect();
+                Ok(bloch_vector(&self.partial_trace(&others)?.density_matrix))
+            })
+            .collect();
+        save_bloch_spheres(&vectors?, path.as_ref())
+    }
+}
+
+fn save_bloch_spheres(vectors: &[[Real; 3]], path: &Path) -> Result<(), BraKetError> {
+    let columns = vectors.len().clamp(1, 4) as u32;
+    let rows = vectors.len().div_ceil(4).max(1) as u32;
+    save(&mut bloch_figure(vectors), path, 320 * columns, 340 * rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::plot::*;
+
+    fn commands(figure: &Figure) -> String {
+        let mut output: Vec<u8> = vec![];
+        figure.echo(&mut output);
+        String::from_utf8_lossy(&output).into_owned()
+    }
+
+    #[test]
+    fn test_bloch_vectors() {
+        let plus = CMatrix::from_row_slice(2, 2, &[Complex::new(0.5, 0.), Complex::new(0.5, 0.), Complex::new(0.5, 0.), Complex::new(0.5, 0.)]);
+        let plus_i = CMatrix::from_row_slice(2, 2, &[Complex::new(0.5, 0.), Complex::new(0., -0.5), Complex::new(0., 0.5), Complex::new(0.5, 0.)]);
+        let one = CMatrix::from_row_slice(2, 2, &[Complex::new(0., 0.), Complex::new(0., 0.), Complex::new(0., 0.), Complex::new(1., 0.)]);
+        assert_eq!(bloch_vector(&plus), [1., 0., 0.]);
+        assert_eq!(bloch_vector(&plus_i), [0., 1., 0.]);
+        assert_eq!(bloch_vector(&one), [0., 0., -1.]);
+    }
+
+    #[test]
+    fn test_parts_are_row_major() {
+        let matrix = CMatrix::from_row_slice(2, 2, &[Complex::new(1., 0.), Complex::new(2., 0.5), Complex::new(3., 0.), Complex::new(-4., 0.)]);
+        let (real, imaginary, largest) = parts(&matrix);
+        assert_eq!(real, vec![1., 2., 3., -4.]);
+        assert_eq!(imaginary, vec![0., 0.5, 0., 0.]);
+        assert_eq!(largest, 4.);
+    }
+
+    #[test]
+    fn test_ticks_follow_endianness() {
+        let figure = probability_figure(&[0.5, 0.5, 0., 0.], 2, Endianness::Big);
+        let output = commands(&figure);
+        assert!(output.contains("\"10\" 1"), "{}", output);
+        assert!(output.contains("\"01\" 2"), "{}", output);
+        assert!(bitstring_ticks(7, Endianness::Little, 64).is_empty());
+    }
+
+    #[test]
+    fn test_figures_have_a_plot_per_part_and_qubit() {
+        let matrix = DensityMatrix::new(2).density_matrix;
+        assert_eq!(commands(&heatmap_figure(&matrix, 2, Endianness::Little)).matches("with image").count(), 2);
+        assert_eq!(commands(&cityscape_figure(&matrix, 2, Endianness::Little)).matches("with pm3d").count(), 2);
+        assert_eq!(commands(&bloch_figure(&[[0., 0., 1.]; 3])).matches("qubit").count(), 3);
+    }
+}
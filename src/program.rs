@@ -1,20 +1,49 @@
-use crate::draw::*;
+use crate::basis::Endianness;
+use crate::controlled::*;
+use crate::error::BraKetError;
+use crate::fusion::*;
+use crate::metrics::*;
+use crate::noise::{run_with_noise, NoiseModel};
 use crate::operations::*;
+use crate::qasm::*;
+use crate::qasm_parser::*;
+use crate::quantikz::to_quantikz;
+use crate::registers::*;
+use crate::routing::*;
+use crate::sampling::*;
 use crate::state_traits::{StateTraits};
+use crate::state_vector::StateVector;
+use crate::subcircuit::*;
+use crate::svg::to_svg;
+use crate::transpile::*;
 use crate::types::*;
-use std::ops::Add;
+use crate::validation::*;
+use std::collections::BTreeMap;
+use std::ops::{Add, BitOr};
+use std::sync::Arc;
 
 /// A quantum program, encoding the sequence of qubit operations to be performed.
 #[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     pub gates: Vec<Operations>,
+    /// How the gates are fused together before being run.
+    pub fusion: Fusion,
+    /// The named groups of qubits allocated from the program.
+    pub quantum_registers: Vec<QuantumRegister>,
+    /// The named groups of classical bits allocated from the program.
+    pub classical_registers: Vec<ClassicalRegister>,
 }
 
-/// Add two programs, so that one runs after the other.
+/// Add two programs, so that one runs after the other. The sum has the registers of both.
+///
+/// # Panics
+/// If a register of one program has the same name as a different register of the other.
 impl Add for Program {
     type Output = Program;
     fn add(self, other: Self) -> Self {
-        let mut program = Program::new();
+        let mut program = self.empty_like();
+        program.add_registers(&other);
         let gates = [&self.gates[..], &other.gates[..]].concat();
         program.add_gates(gates);
         program
@@ -22,21 +51,346 @@ impl Add for Program {
 }
 
 
+/// Place two programs side by side, with the other program acting on the qubits after those of this one.
+impl BitOr for Program {
+    type Output = Program;
+    fn bitor(self, other: Self) -> Self {
+        self.tensor(&other)
+    }
+}
+
+impl Default for Program {
+    fn default() -> Self {
+        Program::new()
+    }
+}
+
 impl Program {
     pub fn new() -> Program {
-        return Program { gates: vec![] };
+        Program { gates: vec![], fusion: Fusion::default(), quantum_registers: vec![], classical_registers: vec![] }
+    }
+
+    /// A program without any gates, but with the same fusion setting and registers as this one.
+    fn empty_like(&self) -> Program {
+        Program {
+            gates: vec![],
+            fusion: self.fusion,
+            quantum_registers: self.quantum_registers.clone(),
+            classical_registers: self.classical_registers.clone(),
+        }
+    }
+
+    /// Adds the registers of the other program which this one does not have yet.
+    ///
+    /// # Panics
+    /// If a register of the other program has the same name as a different register of this one.
+    fn add_registers(&mut self, other: &Program) {
+        for register in other.quantum_registers.iter() {
+            match self.quantum_registers.iter().find(|r| r.name == register.name) {
+                Some(existing) => assert_eq!(existing, register, "two different quantum registers are named {}", register.name),
+                None => self.quantum_registers.push(register.clone()),
+            }
+        }
+        for register in other.classical_registers.iter() {
+            match self.classical_registers.iter().find(|r| r.name == register.name) {
+                Some(existing) => assert_eq!(existing, register, "two different classical registers are named {}", register.name),
+                None => self.classical_registers.push(register.clone()),
+            }
+        }
+    }
+
+    /// Allocates a named register of size qubits, after every qubit already used by the program.
+    ///
+    /// # Panics
+    /// If the program already has a quantum register with the same name, or the register would run past the
+    /// last qubit index.
+    pub fn quantum_register(&mut self, name: &str, size: usize) -> QuantumRegister {
+        assert!(self.quantum_registers.iter().all(|r| r.name != name), "a quantum register named {} already exists", name);
+        let start = self.number_of_qubits();
+        let register = QuantumRegister { name: name.to_owned(), qubits: (start..start.checked_add(size).expect("the register runs past the last qubit index")).collect() };
+        self.quantum_registers.push(register.clone());
+        register
+    }
+
+    /// Allocates a named register of size classical bits, after every bit already used by the program.
+    ///
+    /// # Panics
+    /// If the program already has a classical register with the same name, or the register would run past the
+    /// last bit index.
+    pub fn classical_register(&mut self, name: &str, size: usize) -> ClassicalRegister {
+        assert!(self.classical_registers.iter().all(|r| r.name != name), "a classical register named {} already exists", name);
+        let start = self.number_of_classical_bits();
+        let register = ClassicalRegister { name: name.to_owned(), bits: (start..start.checked_add(size).expect("the register runs past the last bit index")).collect() };
+        self.classical_registers.push(register.clone());
+        register
+    }
+
+    /// The name of the quantum register a qubit belongs to and its position within it.
+    pub fn qubit_name(&self, qubit: usize) -> Option<(&str, usize)> {
+        self.quantum_registers.iter().find_map(|register| {
+            register.iter().position(|q| *q == qubit).map(|position| (register.name.as_str(), position))
+        })
     }
 
+    /// The name of the classical register a bit belongs to and its position within it.
+    pub fn classical_bit_name(&self, bit: usize) -> Option<(&str, usize)> {
+        self.classical_registers.iter().find_map(|register| {
+            register.iter().position(|b| *b == bit).map(|position| (register.name.as_str(), position))
+        })
+    }
+
+    /// Runs the program on the state, failing without touching the state if the program acts on qubits
+    /// the state does not have or measures into a classical bit beyond MAX_CLASSICAL_BITS. In debug builds the program is also validated first.
     pub fn run<T: StateTraits>(
         &self,
         state: &mut T,
-    ) {
-        // logic to panic if the program requires more qubits than present in the state
-        state.check_qubit_number(self.which_qubits());
-        // iterate through the gates and implement them
-        for gate in &self.gates {
-            implement_gate(state, gate)
+    ) -> Result<(), BraKetError> {
+        // logic to fail if the program requires more qubits than present in the state
+        state.check_qubit_number(self.which_qubits())?;
+        self.check_classical_bits()?;
+        if cfg!(debug_assertions) {
+            self.validate(state.number_of_qubits()).map_err(BraKetError::InvalidProgram)?;
+        }
+        // iterate through the (fused) gates and implement them
+        for gate in fuse(&self.gates, &self.fusion).iter() {
+            implement_gate(state, gate)?
         }
+        Ok(())
+    }
+
+    /// Runs the program with the errors of the noise model after each gate. The errors are applied exactly to a
+    /// density matrix, while on a state vector each run samples one of the trajectories the noise can
+    /// take, so averaging over runs gives the noisy state. Subcircuits are expanded and gates are not fused.
+    pub fn run_with_noise<T: StateTraits>(&self, state: &mut T, model: &NoiseModel) -> Result<(), BraKetError> {
+        run_with_noise(self, state, model)
+    }
+
+    /// Samples the measurements at the end of the program shots times, running it once on a copy of the
+    /// initial state and drawing the outcomes with a generator seeded with seed. Returns how often each
+    /// outcome occurs, labelled by the bitstring of the classical bits if the program measures into any, of the
+    /// measured qubits otherwise, or of every qubit if it measures none, written with the endianness of the
+    /// state. Programs which act on qubits after measuring them, reset them or measure them in the x or y
+    /// basis cannot be sampled this way.
+    pub fn sample<T: StateTraits + Clone>(&self, state: &T, shots: usize, seed: u64) -> Result<BTreeMap<String, usize>, BraKetError> {
+        sample(self, state, shots, seed)
+    }
+
+    /// Samples the measurements at the end of the program as sample does, with the errors of the noise model
+    /// after each gate and its readout errors flipping the sampled bits. A density matrix is run once, while
+    /// a state vector is run once for each shot, sampling a trajectory of the noise each time. The noise and the
+    /// outcomes are drawn with generators seeded with seed, leaving the measurement generator of the thread as
+    /// it was.
+    pub fn sample_with_noise<T: StateTraits + Clone>(
+        &self,
+        state: &T,
+        shots: usize,
+        seed: u64,
+        model: &NoiseModel,
+    ) -> Result<BTreeMap<String, usize>, BraKetError> {
+        sample_with_noise(self, state, shots, seed, model)
+    }
+
+    /// Returns a copy of the program without the measurements at its end, those of qubits which no later gate
+    /// acts on, for instance to look at the state they would measure.
+    pub fn without_final_measurements(&self) -> Program {
+        let mut program = self.empty_like();
+        program.add_gates(without_final_measurements(&self.gates));
+        program
+    }
+
+    /// Checks the program can be run on number_of_qubits qubits, reporting every gate which acts on a
+    /// missing qubit or on the same qubit twice, has an arbitrary matrix which is not unitary or an angle
+    /// which is not finite.
+    pub fn validate(&self, number_of_qubits: usize) -> Result<(), Vec<ValidationIssue>> {
+        validate(&self.gates, number_of_qubits)
+    }
+
+    /// Sets how the gates are fused together before being run. Programs run every gate as written unless
+    /// fusion is turned on.
+    pub fn set_fusion(&mut self, fusion: Fusion) {
+        self.fusion = fusion
+    }
+
+    /// Returns a copy of the program with its gates fused according to its fusion setting.
+    pub fn fused(&self) -> Program {
+        let mut program = self.empty_like();
+        program.add_gates(fuse(&self.gates, &self.fusion));
+        program
+    }
+
+    /// Calculates the unitary matrix the program implements on number_of_qubits qubits, by running it
+    /// on each computational basis state.
+    ///
+    /// Returns an error if the program contains measurements or resets, or acts on more than
+    /// number_of_qubits qubits.
+    pub fn unitary(&self, number_of_qubits: usize) -> Result<CMatrix, BraKetError> {
+        if self.gates.iter().any(|gate| dagger(gate).is_none()) {
+            return Err(BraKetError::Unsupported("the unitary of a program with measurements or resets"));
+        }
+        let hilbert_dim = 1 << number_of_qubits;
+        let mut unitary = CMatrix::zeros(hilbert_dim, hilbert_dim);
+        for n in 0..hilbert_dim {
+            let mut state = StateVector::basis(number_of_qubits, n)?;
+            self.run(&mut state)?;
+            unitary.set_column(n, &state.state_vector);
+        }
+        Ok(unitary)
+    }
+
+    /// The inverse of the program, which undoes it: each gate is replaced by its hermitian conjugate and
+    /// the order of the gates is reversed.
+    ///
+    /// Returns an error if the program contains measurements or resets, which cannot be undone.
+    pub fn inverse(&self) -> Result<Program, BraKetError> {
+        let mut program = self.empty_like();
+        for gate in self.gates.iter().rev() {
+            let gate = dagger(gate).ok_or(BraKetError::Unsupported("inverting measurements and resets"))?;
+            program.add_gate(gate);
+        }
+        Ok(program)
+    }
+
+    /// The program applied only when the control qubit is set, with each gate lifted to its controlled form
+    /// and decomposed into single and two qubit gates.
+    ///
+    /// Returns an error if the program contains measurements or resets, or acts on the control qubit.
+    pub fn controlled(&self, control: usize) -> Result<Program, BraKetError> {
+        let mut program = self.empty_like();
+        for gate in self.gates.iter() {
+            program.add_gates(controlled_gates(gate, control)?);
+        }
+        Ok(program)
+    }
+
+    /// The program repeated k times, where a negative k repeats the inverse.
+    ///
+    /// Returns an error if k is negative and the program cannot be inverted.
+    pub fn power(&self, k: Int) -> Result<Program, BraKetError> {
+        let base = match k < 0 {
+            true => self.inverse()?,
+            false => self.clone(),
+        };
+        let mut program = self.empty_like();
+        for _ in 0..k.unsigned_abs() {
+            program.add_gates(base.gates.clone());
+        }
+        Ok(program)
+    }
+
+    /// The number of qubits the program needs, one more than the highest qubit it acts on or has allocated.
+    pub fn number_of_qubits(&self) -> usize {
+        let registers = self.quantum_registers.iter().flat_map(|register| register.iter());
+        self.which_qubits().into_iter().chain(registers).max().map_or(0, |qubit| qubit + 1)
+    }
+
+    /// The number of classical bits the program needs, one more than the highest bit it measures into or
+    /// has allocated.
+    pub fn number_of_classical_bits(&self) -> usize {
+        let measured = self.gates.iter().filter_map(|gate| match gate {
+            Operations::MeasureInto(_, bit) => Some(bit),
+            _ => None,
+        });
+        let registers = self.classical_registers.iter().flat_map(|register| register.iter());
+        measured.chain(registers).max().map_or(0, |bit| bit.saturating_add(1))
+    }
+
+    /// Fails if the program measures into a classical bit beyond the most a program may use, checked
+    /// before running it as storing the outcome allocates every bit up to it.
+    pub(crate) fn check_classical_bits(&self) -> Result<(), BraKetError> {
+        match self.gates.iter().find_map(|gate| match gate {
+            Operations::MeasureInto(_, bit) if *bit >= MAX_CLASSICAL_BITS => Some(*bit),
+            _ => None,
+        }) {
+            Some(bit) => Err(BraKetError::InvalidBit(bit)),
+            None => Ok(()),
+        }
+    }
+
+    /// Relocates the program onto other qubits, so that qubit q is moved to map[q].
+    ///
+    /// # Panics
+    /// If map does not cover every qubit of the program or sends two qubits to the same place.
+    pub fn remap(&self, map: &[usize]) -> Program {
+        assert!(map.len() >= self.number_of_qubits(), "the map {:?} does not cover the {} qubits of the program", map, self.number_of_qubits());
+        let mut targets = map.to_vec();
+        targets.sort();
+        targets.dedup();
+        assert_eq!(targets.len(), map.len(), "the map {:?} sends two qubits to the same place", map);
+
+        let mut program = self.empty_like();
+        program.quantum_registers = self.quantum_registers.iter().map(|r| r.map_qubits(|qubit| map[qubit])).collect();
+        program.add_gates(self.gates.iter().map(|gate| map_qubits(gate, |qubit| map[qubit])).collect());
+        program
+    }
+
+    /// Moves the program up by offset qubits.
+    pub fn shift(&self, offset: usize) -> Program {
+        let mut program = self.empty_like();
+        program.quantum_registers = self.quantum_registers.iter().map(|r| r.map_qubits(|qubit| qubit + offset)).collect();
+        program.add_gates(self.gates.iter().map(|gate| map_qubits(gate, |qubit| qubit + offset)).collect());
+        program
+    }
+
+    /// Places the other program side by side with this one, on the qubits and classical bits after those
+    /// this program needs. Operations on every qubit, such as barriers and measuring all qubits, act on both
+    /// programs.
+    pub fn tensor(&self, other: &Program) -> Program {
+        let bit_offset = self.number_of_classical_bits();
+        let mut shifted = other.shift(self.number_of_qubits());
+        shifted.classical_registers = other.classical_registers.iter().map(|r| r.map_bits(|bit| bit + bit_offset)).collect();
+        for gate in shifted.gates.iter_mut() {
+            if let Operations::MeasureInto(_, bit) = gate {
+                *bit += bit_offset;
+            }
+        }
+
+        let mut program = self.clone();
+        program.add_registers(&shifted);
+        program.add_gates(shifted.gates);
+        program
+    }
+
+    /// Rewrites the program into the native gate set of a device, RZ, SX and either CZ or SISWAP.
+    /// The transpiled program implements the same unitary up to a global phase.
+    pub fn transpile(&self, gate_set: &NativeGateSet) -> Program {
+        let mut program = self.empty_like();
+        program.add_gates(transpile(self, gate_set));
+        program
+    }
+
+    /// Routes the program onto the connectivity of a device with the SABRE algorithm, returning the program
+    /// on physical qubits with SWAP gates inserted, together with the initial and final layouts. Fails if
+    /// the coupling map has too few qubits or does not connect the qubits a gate acts on.
+    pub fn route(&self, coupling_map: &CouplingMap) -> Result<Routing, BraKetError> {
+        route(self, coupling_map)
+    }
+
+    /// The depth of the program, the number of layers of gates which have to be performed one after another.
+    /// Barriers separate the layers on either side of them but do not add to the depth, and subcircuits
+    /// count with the depth of their gates.
+    pub fn depth(&self) -> usize {
+        depth(&self.gates)
+    }
+
+    /// The depth of the program counting only the two qubit gates.
+    pub fn two_qubit_depth(&self) -> usize {
+        two_qubit_depth(&self.gates)
+    }
+
+    /// The number of times each type of gate appears in the program, keyed by the gate name.
+    pub fn gate_counts(&self) -> BTreeMap<String, usize> {
+        gate_counts(&self.gates)
+    }
+
+    /// Partitions the gates into layers (moments) of operations acting on disjoint qubits, each gate being
+    /// placed in the earliest layer it can go in. As for the depth, subcircuits are expanded into their gates
+    /// and barriers separate the layers without being in one, so there are depth() layers.
+    pub fn layers(&self) -> Vec<Vec<Operations>> {
+        let gates = flatten(&self.gates);
+        layers(&gates)
+            .into_iter()
+            .map(|layer| layer.into_iter().map(|n| gates[n].clone()).collect())
+            .collect()
     }
 
     pub fn which_qubits(&self) -> Vec<&usize> {
@@ -49,8 +403,77 @@ impl Program {
         qubits
     }
 
+    /// Prints the circuit as text, see the Display implementation of Program.
     pub fn draw(&self) {
-        draw_circuit(&self)
+        print!("{}", self)
+    }
+
+    /// Draws the circuit as an SVG image, laid out in the same way as the text drawing.
+    pub fn to_svg(&self) -> String {
+        to_svg(self)
+    }
+
+    /// Writes the circuit as a quantikz environment for LaTeX documents, laid out in the same way as the text
+    /// drawing. The document needs \usepackage{quantikz}.
+    pub fn to_quantikz(&self) -> String {
+        to_quantikz(self)
+    }
+
+    /// Exports the program as OpenQASM 2.0, with the registers of the program declared under their names.
+    /// The first element of each register is the least significant, as in OpenQASM.
+    pub fn to_qasm(&self) -> String {
+        to_qasm(self, Endianness::Little)
+    }
+
+    /// Exports the program as OpenQASM 2.0 as to_qasm does, for a program whose registers are in the given
+    /// order. For big endian programs the elements of each register are reversed, so that register values
+    /// read from states of the same endianness are unchanged.
+    pub fn to_qasm_with_endianness(&self, endianness: Endianness) -> String {
+        to_qasm(self, endianness)
+    }
+
+    /// Reads a program from OpenQASM 2.0, for instance one written by to_qasm. Registers keep their names,
+    /// gates defined without parameters become subcircuits and the gates of qelib1.inc are read as the
+    /// matching operations. Classically controlled gates and includes other than qelib1.inc are not supported.
+    /// A gate may only call the gates defined before it, and the registers may hold at most MAX_REGISTER_SIZE
+    /// qubits and as many classical bits.
+    pub fn from_qasm(source: &str) -> Result<Program, BraKetError> {
+        from_qasm(source, Endianness::Little)
+    }
+
+    /// Reads a program from OpenQASM 2.0 as from_qasm does, putting the elements of each register in the
+    /// given order, the inverse of to_qasm_with_endianness.
+    pub fn from_qasm_with_endianness(source: &str, endianness: Endianness) -> Result<Program, BraKetError> {
+        from_qasm(source, endianness)
+    }
+
+    /// Defines a named subcircuit from the gates of the program, which can be inserted into other programs
+    /// with add_subcircuit.
+    pub fn define(&self, name: &str) -> Arc<Subcircuit> {
+        Arc::new(Subcircuit::new(name, self))
+    }
+
+    /// Inserts a subcircuit as a single operation, its qubit n being placed on qubits[n].
+    ///
+    /// # Panics
+    /// If the number of qubits does not match the subcircuit, or a qubit is repeated.
+    pub fn add_subcircuit(&mut self, subcircuit: &Arc<Subcircuit>, qubits: &[usize]) {
+        assert_eq!(
+            qubits.len(), subcircuit.number_of_qubits,
+            "the subcircuit {} acts on {} qubits, not {}", subcircuit.name, subcircuit.number_of_qubits, qubits.len()
+        );
+        let mut distinct = qubits.to_vec();
+        distinct.sort();
+        distinct.dedup();
+        assert_eq!(distinct.len(), qubits.len(), "the subcircuit {} is placed on repeated qubits {:?}", subcircuit.name, qubits);
+        self.add_gate(Operations::Subcircuit(subcircuit.clone(), qubits.to_vec()))
+    }
+
+    /// Returns a copy of the program with every subcircuit expanded into its gates.
+    pub fn flatten(&self) -> Program {
+        let mut program = self.empty_like();
+        program.add_gates(flatten(&self.gates));
+        program
     }
 
     pub fn add_gate(&mut self, gate: Operations) {
@@ -65,6 +488,62 @@ impl Program {
         self.add_gate(Operations::Measure(qubit))
     }
 
+    /// Measures a qubit, storing the outcome in a classical bit.
+    pub fn measure_into(&mut self, qubit: usize, bit: usize) {
+        self.add_gate(Operations::MeasureInto(qubit, bit))
+    }
+
+    /// Measures each qubit into the corresponding classical bit, for instance a whole quantum register into
+    /// a classical register.
+    ///
+    /// # Panics
+    /// If there are not as many classical bits as qubits.
+    pub fn measure_register(&mut self, qubits: &[usize], bits: &[usize]) {
+        assert_eq!(qubits.len(), bits.len(), "cannot measure {} qubits into {} classical bits", qubits.len(), bits.len());
+        for (qubit, bit) in qubits.iter().zip(bits.iter()) {
+            self.measure_into(*qubit, *bit)
+        }
+    }
+
+    /// Adds the gate to each of the qubits, for instance program.apply_each(&register, Operations::H).
+    pub fn apply_each<F: Fn(usize) -> Operations>(&mut self, qubits: &[usize], gate: F) {
+        for qubit in qubits.iter() {
+            self.add_gate(gate(*qubit))
+        }
+    }
+
+    /// Adds the two qubit gate to each pair of corresponding qubits, for instance
+    /// program.apply_pairwise(&a, &b, Operations::CNOT).
+    ///
+    /// # Panics
+    /// If the slices of qubits differ in length.
+    pub fn apply_pairwise<F: Fn(usize, usize) -> Operations>(&mut self, qubits_0: &[usize], qubits_1: &[usize], gate: F) {
+        assert_eq!(qubits_0.len(), qubits_1.len(), "cannot pair {} qubits with {} qubits", qubits_0.len(), qubits_1.len());
+        for (qubit_0, qubit_1) in qubits_0.iter().zip(qubits_1.iter()) {
+            self.add_gate(gate(*qubit_0, *qubit_1))
+        }
+    }
+
+    /// Measures a qubit in the x basis.
+    pub fn measure_x(&mut self, qubit: usize) {
+        self.add_gate(Operations::MeasureX(qubit))
+    }
+
+    /// Measures a qubit in the y basis.
+    pub fn measure_y(&mut self, qubit: usize) {
+        self.add_gate(Operations::MeasureY(qubit))
+    }
+
+    /// Resets a single qubit to |0>, for instance to reuse an ancilla.
+    pub fn reset(&mut self, qubit: usize) {
+        self.add_gate(Operations::Reset(qubit))
+    }
+
+    /// Separates the gates before and after it, which are not fused or moved across it.
+    pub fn barrier(&mut self) {
+        self.add_gate(Operations::Barrier)
+    }
+
     pub fn measure_all(&mut self) {
         self.add_gate(Operations::MeasureAll)
     }
@@ -109,8 +588,10 @@ impl Program {
         self.add_gate(Operations::CZ(control, target))
     }
 
-    pub fn crz(&mut self, control: usize, target: usize, _angle: Angle) {
-        self.add_gate(Operations::CZ(control, target))
+    /// The controlled RZ gate. Earlier versions added a CZ and ignored the angle; for the controlled phase
+    /// diag(1, 1, 1, e^(i angle)) use cphase.
+    pub fn crz(&mut self, control: usize, target: usize, angle: Angle) {
+        self.add_gate(Operations::CRZ(control, target, angle))
     }
 
     pub fn cnot(&mut self, control: usize, target: usize) {
@@ -132,6 +613,86 @@ impl Program {
     pub fn s(&mut self, qubit: usize) {
         self.add_gate(Operations::S(qubit))
     }
+
+    pub fn sdg(&mut self, qubit: usize) {
+        self.add_gate(Operations::Sdg(qubit))
+    }
+
+    pub fn t(&mut self, qubit: usize) {
+        self.add_gate(Operations::T(qubit))
+    }
+
+    pub fn tdg(&mut self, qubit: usize) {
+        self.add_gate(Operations::Tdg(qubit))
+    }
+
+    pub fn sx(&mut self, qubit: usize) {
+        self.add_gate(Operations::SX(qubit))
+    }
+
+    pub fn sxdg(&mut self, qubit: usize) {
+        self.add_gate(Operations::SXdg(qubit))
+    }
+
+    pub fn phase(&mut self, qubit: usize, lambda: Angle) {
+        self.add_gate(Operations::Phase(qubit, lambda))
+    }
+
+    pub fn u1(&mut self, qubit: usize, lambda: Angle) {
+        self.add_gate(Operations::U1(qubit, lambda))
+    }
+
+    pub fn u2(&mut self, qubit: usize, phi: Angle, lambda: Angle) {
+        self.add_gate(Operations::U2(qubit, phi, lambda))
+    }
+
+    pub fn u3(&mut self, qubit: usize, theta: Angle, phi: Angle, lambda: Angle) {
+        self.add_gate(Operations::U3(qubit, theta, phi, lambda))
+    }
+
+    pub fn cy(&mut self, control: usize, target: usize) {
+        self.add_gate(Operations::CY(control, target))
+    }
+
+    pub fn ch(&mut self, control: usize, target: usize) {
+        self.add_gate(Operations::CH(control, target))
+    }
+
+    pub fn crx(&mut self, control: usize, target: usize, angle: Angle) {
+        self.add_gate(Operations::CRX(control, target, angle))
+    }
+
+    pub fn cry(&mut self, control: usize, target: usize, angle: Angle) {
+        self.add_gate(Operations::CRY(control, target, angle))
+    }
+
+    pub fn cphase(&mut self, control: usize, target: usize, lambda: Angle) {
+        self.add_gate(Operations::CPhase(control, target, lambda))
+    }
+
+    pub fn rxx(&mut self, qubit_0: usize, qubit_1: usize, theta: Angle) {
+        self.add_gate(Operations::RXX(qubit_0, qubit_1, theta))
+    }
+
+    pub fn ryy(&mut self, qubit_0: usize, qubit_1: usize, theta: Angle) {
+        self.add_gate(Operations::RYY(qubit_0, qubit_1, theta))
+    }
+
+    pub fn rzz(&mut self, qubit_0: usize, qubit_1: usize, theta: Angle) {
+        self.add_gate(Operations::RZZ(qubit_0, qubit_1, theta))
+    }
+
+    pub fn fsim(&mut self, qubit_0: usize, qubit_1: usize, theta: Angle, phi: Angle) {
+        self.add_gate(Operations::FSim(qubit_0, qubit_1, theta, phi))
+    }
+
+    pub fn givens(&mut self, qubit_0: usize, qubit_1: usize, theta: Angle) {
+        self.add_gate(Operations::Givens(qubit_0, qubit_1, theta))
+    }
+
+    pub fn ecr(&mut self, qubit_0: usize, qubit_1: usize) {
+        self.add_gate(Operations::ECR(qubit_0, qubit_1))
+    }
 }
 
 #[cfg(test)]
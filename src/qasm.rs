@@ -0,0 +1,254 @@
+use std::sync::Arc;
+
+use crate::basis::Endianness;
+use crate::operations::*;
+use crate::program::Program;
+use crate::subcircuit::{flatten, Subcircuit};
+use crate::transpile::*;
+
+/// A register name which is not already used by the program, starting from name.
+pub fn unused_name(program: &Program, name: &str) -> String {
+    let mut name = name.to_owned();
+    while program.quantum_registers.iter().any(|r| r.name == name) || program.classical_registers.iter().any(|r| r.name == name) {
+        name.push('_');
+    }
+    name
+}
+
+/// The index in the little endian OpenQASM register of the element at position of a register of the given
+/// size, which for big endian programs is the most significant element first.
+fn register_index(position: usize, size: usize, endianness: Endianness) -> usize {
+    match endianness {
+        Endianness::Little => position,
+        Endianness::Big => size - 1 - position,
+    }
+}
+
+/// The names of the qubits and classical bits of a program in the exported OpenQASM. Qubits and bits
+/// outside the registers of the program are gathered into registers of their own. Inside a gate definition
+/// there is no program and the qubits are the formal parameters q0, q1, ...
+struct Names<'a> {
+    program: Option<&'a Program>,
+    /// The order of the elements of the registers of the program.
+    endianness: Endianness,
+    /// The register holding the qubits outside the named registers, and those qubits.
+    qubits: (String, Vec<usize>),
+    /// The register holding the bits outside the named registers, and those bits.
+    bits: (String, Vec<usize>),
+    /// The register the plain measurements of each qubit are stored in.
+    measurements: String,
+}
+
+impl<'a> Names<'a> {
+    fn new(program: &'a Program, endianness: Endianness) -> Names<'a> {
+        let qubits = (0..program.number_of_qubits()).filter(|qubit| program.qubit_name(*qubit).is_none()).collect();
+        let bits = (0..program.number_of_classical_bits()).filter(|bit| program.classical_bit_name(*bit).is_none()).collect();
+        Names {
+            program: Some(program),
+            endianness,
+            qubits: (unused_name(program, "q"), qubits),
+            bits: (unused_name(program, "b"), bits),
+            measurements: unused_name(program, "c"),
+        }
+    }
+
+    fn formal() -> Names<'a> {
+        Names {
+            program: None,
+            endianness: Endianness::Little,
+            qubits: (String::new(), vec![]),
+            bits: (String::new(), vec![]),
+            measurements: String::new(),
+        }
+    }
+
+    fn qubit(&self, qubit: usize) -> String {
+        let program = match self.program {
+            Some(program) => program,
+            None => return format!("q{}", qubit),
+        };
+        let (name, position, size) = match program.qubit_name(qubit) {
+            Some((name, position)) => (name, position, program.quantum_registers.iter().find(|r| r.name == name).unwrap().len()),
+            None => (self.qubits.0.as_str(), self.qubits.1.iter().position(|q| *q == qubit).unwrap(), self.qubits.1.len()),
+        };
+        format!("{}[{}]", name, register_index(position, size, self.endianness))
+    }
+
+    fn bit(&self, bit: usize) -> String {
+        let program = self.program.unwrap();
+        let (name, position, size) = match program.classical_bit_name(bit) {
+            Some((name, position)) => (name, position, program.classical_registers.iter().find(|r| r.name == name).unwrap().len()),
+            None => (self.bits.0.as_str(), self.bits.1.iter().position(|b| *b == bit).unwrap(), self.bits.1.len()),
+        };
+        format!("{}[{}]", name, register_index(position, size, self.endianness))
+    }
+
+    /// The bit of the measurement register a plain measurement of the qubit is stored in.
+    fn measurement(&self, qubit: usize, number_of_qubits: usize) -> String {
+        format!("{}[{}]", self.measurements, register_index(qubit, number_of_qubits, self.endianness))
+    }
+}
+
+/// Writes a single gate as OpenQASM statements. Gates outside the standard library are decomposed into
+/// ones inside it, up to a global phase.
+fn gate_to_qasm(gate: &Operations, names: &Names, number_of_qubits: usize) -> Vec<String> {
+    let q = |qubit: &usize| names.qubit(*qubit);
+    match gate {
+        // a barrier on no qubits is not valid OpenQASM
+        Operations::Barrier if number_of_qubits == 0 => vec![],
+        Operations::Barrier => {
+            let qubits: Vec<String> = (0..number_of_qubits).map(|qubit| q(&qubit)).collect();
+            vec![format!("barrier {};", qubits.join(", "))]
+        }
+        Operations::Measure(qubit) => vec![format!("measure {} -> {};", q(qubit), names.measurement(*qubit, number_of_qubits))],
+        Operations::MeasureInto(qubit, bit) => vec![format!("measure {} -> {};", q(qubit), names.bit(*bit))],
+        Operations::MeasureAll => (0..number_of_qubits)
+            .map(|qubit| format!("measure {} -> {};", q(&qubit), names.measurement(qubit, number_of_qubits)))
+            .collect(),
+        Operations::ResetAll => (0..number_of_qubits).map(|qubit| format!("reset {};", q(&qubit))).collect(),
+        Operations::Reset(qubit) => vec![format!("reset {};", q(qubit))],
+        Operations::MeasureX(qubit) => vec![
+            format!("h {};", q(qubit)),
+            format!("measure {} -> {};", q(qubit), names.measurement(*qubit, number_of_qubits)),
+            format!("h {};", q(qubit)),
+        ],
+        Operations::MeasureY(qubit) => vec![
+            format!("sdg {};", q(qubit)),
+            format!("h {};", q(qubit)),
+            format!("measure {} -> {};", q(qubit), names.measurement(*qubit, number_of_qubits)),
+            format!("h {};", q(qubit)),
+            format!("s {};", q(qubit)),
+        ],
+
+        Operations::X(qubit) => vec![format!("x {};", q(qubit))],
+        Operations::Y(qubit) => vec![format!("y {};", q(qubit))],
+        Operations::Z(qubit) => vec![format!("z {};", q(qubit))],
+        Operations::S(qubit) => vec![format!("s {};", q(qubit))],
+        Operations::Sdg(qubit) => vec![format!("sdg {};", q(qubit))],
+        Operations::T(qubit) => vec![format!("t {};", q(qubit))],
+        Operations::Tdg(qubit) => vec![format!("tdg {};", q(qubit))],
+        Operations::SX(qubit) => vec![format!("sx {};", q(qubit))],
+        Operations::SXdg(qubit) => vec![format!("sxdg {};", q(qubit))],
+        Operations::H(qubit) => vec![format!("h {};", q(qubit))],
+        Operations::RX(qubit, angle) => vec![format!("rx({}) {};", angle, q(qubit))],
+        Operations::RY(qubit, angle) => vec![format!("ry({}) {};", angle, q(qubit))],
+        Operations::RZ(qubit, angle) => vec![format!("rz({}) {};", angle, q(qubit))],
+        Operations::R(qubit, phi, theta, omega) => vec![format!("u3({},{},{}) {};", theta, omega, phi, q(qubit))],
+        Operations::Phase(qubit, lambda) => vec![format!("p({}) {};", lambda, q(qubit))],
+        Operations::U1(qubit, lambda) => vec![format!("u1({}) {};", lambda, q(qubit))],
+        Operations::U2(qubit, phi, lambda) => vec![format!("u2({},{}) {};", phi, lambda, q(qubit))],
+        Operations::U3(qubit, theta, phi, lambda) => vec![format!("u3({},{},{}) {};", theta, phi, lambda, q(qubit))],
+        Operations::ArbitrarySingle(qubit, u) => {
+            let (_, phi, theta, lambda) = zyz_decomposition(u);
+            vec![format!("u3({},{},{}) {};", theta, phi, lambda, q(qubit))]
+        }
+
+        Operations::CNOT(control, target) => vec![format!("cx {},{};", q(control), q(target))],
+        Operations::CZ(control, target) => vec![format!("cz {},{};", q(control), q(target))],
+        Operations::CY(control, target) => vec![format!("cy {},{};", q(control), q(target))],
+        Operations::CH(control, target) => vec![format!("ch {},{};", q(control), q(target))],
+        Operations::CRX(control, target, angle) => vec![format!("crx({}) {},{};", angle, q(control), q(target))],
+        Operations::CRY(control, target, angle) => vec![format!("cry({}) {},{};", angle, q(control), q(target))],
+        Operations::CRZ(control, target, angle) => vec![format!("crz({}) {},{};", angle, q(control), q(target))],
+        Operations::CPhase(control, target, angle) => vec![format!("cp({}) {},{};", angle, q(control), q(target))],
+        Operations::SWAP(control, target) => vec![format!("swap {},{};", q(control), q(target))],
+        Operations::RXX(qubit_0, qubit_1, theta) => vec![format!("rxx({}) {},{};", theta, q(qubit_0), q(qubit_1))],
+        Operations::RZZ(qubit_0, qubit_1, theta) => vec![format!("rzz({}) {},{};", theta, q(qubit_0), q(qubit_1))],
+        Operations::ISWAP(_, _)
+        | Operations::SISWAP(_, _)
+        | Operations::ArbitaryTwo(_, _, _)
+        | Operations::RYY(_, _, _)
+        | Operations::FSim(_, _, _, _)
+        | Operations::Givens(_, _, _)
+        | Operations::ECR(_, _) => {
+            let (qubit_0, qubit_1, u) = two_qubit_unitary(gate).unwrap();
+            two_qubit_to_native(qubit_0, qubit_1, &u, &NativeGateSet::CZ)
+                .iter()
+                .flat_map(|gate| gate_to_qasm(gate, names, number_of_qubits))
+                .collect()
+        }
+
+        Operations::Subcircuit(subcircuit, qubits) => match is_definable(subcircuit) {
+            true => {
+                let qubits: Vec<String> = qubits.iter().map(q).collect();
+                vec![format!("{} {};", subcircuit.name, qubits.join(","))]
+            }
+            false => subcircuit
+                .expand(qubits)
+                .iter()
+                .flat_map(|gate| gate_to_qasm(gate, names, number_of_qubits))
+                .collect(),
+        },
+    }
+}
+
+/// Only unitary subcircuits can be written as gate definitions, others are expanded where they are used.
+fn is_definable(subcircuit: &Subcircuit) -> bool {
+    subcircuit.number_of_qubits > 0 && subcircuit.inverse().is_some()
+}
+
+/// Writes the gate definitions of the subcircuits used by the gates, nested subcircuits first as they have
+/// to be defined before they are used.
+///
+/// # Panics
+/// If two different subcircuits have the same name.
+fn define_subcircuits(gates: &[Operations], defined: &mut Vec<Arc<Subcircuit>>, lines: &mut Vec<String>) {
+    for gate in gates.iter() {
+        if let Operations::Subcircuit(subcircuit, _) = gate {
+            define_subcircuits(&subcircuit.gates, defined, lines);
+            if let Some(existing) = defined.iter().find(|s| s.name == subcircuit.name) {
+                assert_eq!(existing, subcircuit, "two different subcircuits are named {}", subcircuit.name);
+                continue;
+            }
+            defined.push(subcircuit.clone());
+            if !is_definable(subcircuit) {
+                continue;
+            }
+
+            let parameters: Vec<String> = (0..subcircuit.number_of_qubits).map(|qubit| format!("q{}", qubit)).collect();
+            lines.push(format!("gate {} {} {{", subcircuit.name, parameters.join(",")));
+            for gate in subcircuit.gates.iter() {
+                for line in gate_to_qasm(gate, &Names::formal(), subcircuit.number_of_qubits) {
+                    lines.push(format!("  {}", line));
+                }
+            }
+            lines.push("}".to_owned());
+        }
+    }
+}
+
+/// Exports a program as OpenQASM 2.0. The registers of the program are declared under their own names,
+/// qubits and bits outside them are gathered into the registers q and b, and plain measurements of a qubit
+/// are stored in the matching bit of the register c. Unitary subcircuits are emitted as gate definitions.
+///
+/// OpenQASM is little endian, element k of a register having the value 2^k. For a big endian program, whose
+/// registers have their most significant element first, the order of the elements of each register is
+/// reversed in the export, so that the registers keep their values.
+pub fn to_qasm(program: &Program, endianness: Endianness) -> String {
+    let names = Names::new(program, endianness);
+    let number_of_qubits = program.number_of_qubits();
+    let mut lines = vec!["OPENQASM 2.0;".to_owned(), "include \"qelib1.inc\";".to_owned()];
+    define_subcircuits(&program.gates, &mut vec![], &mut lines);
+
+    for register in program.quantum_registers.iter() {
+        lines.push(format!("qreg {}[{}];", register.name, register.len()));
+    }
+    if !names.qubits.1.is_empty() {
+        lines.push(format!("qreg {}[{}];", names.qubits.0, names.qubits.1.len()));
+    }
+    for register in program.classical_registers.iter() {
+        lines.push(format!("creg {}[{}];", register.name, register.len()));
+    }
+    if !names.bits.1.is_empty() {
+        lines.push(format!("creg {}[{}];", names.bits.0, names.bits.1.len()));
+    }
+    let measures = |gate: &Operations| matches!(gate, Operations::Measure(_) | Operations::MeasureX(_) | Operations::MeasureY(_) | Operations::MeasureAll);
+    if flatten(&program.gates).iter().any(measures) {
+        lines.push(format!("creg {}[{}];", names.measurements, number_of_qubits));
+    }
+
+    for gate in program.gates.iter() {
+        lines.append(&mut gate_to_qasm(gate, &names, number_of_qubits));
+    }
+    lines.join("\n") + "\n"
+}
@@ -0,0 +1,441 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use pest::error::LineColLocation;
+use pest::iterators::Pair;
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::basis::Endianness;
+use crate::error::BraKetError;
+use crate::operations::Operations;
+use crate::program::Program;
+use crate::registers::MAX_REGISTER_SIZE;
+use crate::subcircuit::Subcircuit;
+use crate::types::*;
+
+#[derive(Parser)]
+#[grammar = "qasm.pest"]
+struct QasmParser;
+
+fn error(pair: &Pair<Rule>, message: String) -> BraKetError {
+    BraKetError::InvalidQasm { line: pair.line_col().0, message }
+}
+
+/// A gate defined by the program being read.
+enum Definition<'i> {
+    /// A gate without parameters, read into a subcircuit.
+    Subcircuit(Arc<Subcircuit>),
+    /// A gate with parameters, expanded wherever it is called with the values of its parameters. Its body
+    /// sees the gates defined before it, the first visible ones.
+    Parameterised { parameters: Vec<String>, qubits: Vec<String>, body: Pair<'i, Rule>, visible: usize },
+}
+
+/// The number of angles and qubits of the gates of the standard library, qelib1.inc, which can be read.
+fn standard_signature(name: &str) -> Option<(usize, usize)> {
+    Some(match name {
+        "id" | "x" | "y" | "z" | "h" | "s" | "sdg" | "t" | "tdg" | "sx" | "sxdg" => (0, 1),
+        "rx" | "ry" | "rz" | "p" | "u1" => (1, 1),
+        "u2" => (2, 1),
+        "u3" | "u" | "U" => (3, 1),
+        "cx" | "CX" | "cz" | "cy" | "ch" | "swap" => (0, 2),
+        "crx" | "cry" | "crz" | "cp" | "cu1" | "rxx" | "rzz" => (1, 2),
+        "cu3" => (3, 2),
+        "ccx" => (0, 3),
+        _ => return None,
+    })
+}
+
+/// A gate of the standard library, with the angles and qubits its signature asks for. The gates without a
+/// counterpart in Operations are decomposed as in qelib1.inc.
+fn standard_gate(name: &str, a: &[Angle], q: &[usize]) -> Vec<Operations> {
+    use Operations::*;
+    match name {
+        "id" => vec![],
+        "x" => vec![X(q[0])],
+        "y" => vec![Y(q[0])],
+        "z" => vec![Z(q[0])],
+        "h" => vec![H(q[0])],
+        "s" => vec![S(q[0])],
+        "sdg" => vec![Sdg(q[0])],
+        "t" => vec![T(q[0])],
+        "tdg" => vec![Tdg(q[0])],
+        "sx" => vec![SX(q[0])],
+        "sxdg" => vec![SXdg(q[0])],
+        "rx" => vec![RX(q[0], a[0])],
+        "ry" => vec![RY(q[0], a[0])],
+        "rz" => vec![RZ(q[0], a[0])],
+        "p" => vec![Phase(q[0], a[0])],
+        "u1" => vec![U1(q[0], a[0])],
+        "u2" => vec![U2(q[0], a[0], a[1])],
+        "u3" | "u" | "U" => vec![U3(q[0], a[0], a[1], a[2])],
+        "cx" | "CX" => vec![CNOT(q[0], q[1])],
+        "cz" => vec![CZ(q[0], q[1])],
+        "cy" => vec![CY(q[0], q[1])],
+        "ch" => vec![CH(q[0], q[1])],
+        "swap" => vec![SWAP(q[0], q[1])],
+        "crx" => vec![CRX(q[0], q[1], a[0])],
+        "cry" => vec![CRY(q[0], q[1], a[0])],
+        "crz" => vec![CRZ(q[0], q[1], a[0])],
+        "cp" | "cu1" => vec![CPhase(q[0], q[1], a[0])],
+        "rxx" => vec![RXX(q[0], q[1], a[0])],
+        "rzz" => vec![RZZ(q[0], q[1], a[0])],
+        "cu3" => {
+            let (theta, phi, lambda) = (a[0], a[1], a[2]);
+            vec![
+                U1(q[0], (lambda + phi) / 2.),
+                U1(q[1], (lambda - phi) / 2.),
+                CNOT(q[0], q[1]),
+                U3(q[1], -theta / 2., 0., -(phi + lambda) / 2.),
+                CNOT(q[0], q[1]),
+                U3(q[1], theta / 2., phi, 0.),
+            ]
+        }
+        "ccx" => {
+            let (a, b, c) = (q[0], q[1], q[2]);
+            vec![
+                H(c), CNOT(b, c), Tdg(c), CNOT(a, c), T(c), CNOT(b, c), Tdg(c), CNOT(a, c),
+                T(b), T(c), H(c), CNOT(a, b), T(a), Tdg(b), CNOT(a, b),
+            ]
+        }
+        _ => unreachable!("{} is not a gate of the standard library", name),
+    }
+}
+
+/// Evaluates an expression for an angle, the identifiers in it being parameters of the gate being defined.
+fn evaluate(pair: Pair<Rule>, parameters: &HashMap<String, Angle>) -> Result<Angle, BraKetError> {
+    let operand = |pair: Pair<Rule>| evaluate(pair.into_inner().next().unwrap(), parameters);
+    match pair.as_rule() {
+        Rule::expression | Rule::term => {
+            let mut inner = pair.into_inner();
+            let mut value = evaluate(inner.next().unwrap(), parameters)?;
+            for operation in inner {
+                value = match operation.as_rule() {
+                    Rule::add => value + operand(operation)?,
+                    Rule::subtract => value - operand(operation)?,
+                    Rule::multiply => value * operand(operation)?,
+                    _ => value / operand(operation)?,
+                };
+            }
+            Ok(value)
+        }
+        Rule::factor => operand(pair),
+        Rule::negate => Ok(-operand(pair)?),
+        Rule::power => {
+            let mut inner = pair.into_inner();
+            let base = evaluate(inner.next().unwrap(), parameters)?;
+            match inner.next() {
+                Some(exponent) => Ok(base.powf(evaluate(exponent, parameters)?)),
+                None => Ok(base),
+            }
+        }
+        Rule::function => {
+            let mut inner = pair.into_inner();
+            let name = inner.next().unwrap().as_str();
+            let x = evaluate(inner.next().unwrap(), parameters)?;
+            Ok(match name {
+                "sin" => x.sin(),
+                "cos" => x.cos(),
+                "tan" => x.tan(),
+                "exp" => x.exp(),
+                "ln" => x.ln(),
+                _ => x.sqrt(),
+            })
+        }
+        Rule::pi => Ok(PI),
+        Rule::number => pair.as_str().parse().map_err(|_| error(&pair, format!("{} is not a number", pair.as_str()))),
+        Rule::identifier => match parameters.get(pair.as_str()) {
+            Some(value) => Ok(*value),
+            None => Err(error(&pair, format!("unknown parameter {}", pair.as_str()))),
+        },
+        rule => unreachable!("{:?} is not part of an expression", rule),
+    }
+}
+
+/// The identifiers of an identifiers pair, failing if one is repeated.
+fn identifiers(pair: Pair<Rule>) -> Result<Vec<String>, BraKetError> {
+    let mut names: Vec<String> = vec![];
+    for identifier in pair.into_inner() {
+        if names.iter().any(|name| name == identifier.as_str()) {
+            return Err(error(&identifier, format!("{} is repeated", identifier.as_str())));
+        }
+        names.push(identifier.as_str().to_owned());
+    }
+    Ok(names)
+}
+
+/// A call of a gate, with its angles evaluated.
+struct Call<'i> {
+    name: String,
+    angles: Vec<Angle>,
+    arguments: Vec<Pair<'i, Rule>>,
+}
+
+/// Reads the statements of an OpenQASM program one after another into a program.
+struct Reader<'i> {
+    program: Program,
+    /// The order of the elements of the registers of the program.
+    endianness: Endianness,
+    definitions: HashMap<String, Definition<'i>>,
+    /// The names of the gates defined so far, in the order they were defined.
+    defined: Vec<String>,
+}
+
+impl<'i> Reader<'i> {
+    /// The definition of a gate, if it is one of the first visible gates the program defines. Bodies of
+    /// definitions only see the gates defined before them, so a gate defined later with the name of a
+    /// standard gate does not change them.
+    fn definition(&self, name: &str, visible: usize) -> Option<&Definition<'i>> {
+        match self.defined[..visible].iter().any(|defined| defined == name) {
+            true => self.definitions.get(name),
+            false => None,
+        }
+    }
+
+    /// The gates of a single call of a gate on distinct qubits, where the first visible gates the program
+    /// defines can be called.
+    fn gate(&self, call: &Pair<'i, Rule>, name: &str, angles: &[Angle], qubits: &[usize], visible: usize) -> Result<Vec<Operations>, BraKetError> {
+        let signature = match self.definition(name, visible) {
+            Some(Definition::Subcircuit(subcircuit)) => (0, subcircuit.number_of_qubits),
+            Some(Definition::Parameterised { parameters, qubits, .. }) => (parameters.len(), qubits.len()),
+            None => standard_signature(name).ok_or_else(|| error(call, format!("unknown gate {}", name)))?,
+        };
+        if signature != (angles.len(), qubits.len()) {
+            return Err(error(call, format!(
+                "{} takes {} angles and {} qubits, not {} and {}", name, signature.0, signature.1, angles.len(), qubits.len()
+            )));
+        }
+        if let Some(qubit) = qubits.iter().find(|qubit| qubits.iter().filter(|q| q == qubit).count() > 1) {
+            return Err(error(call, format!("{} is applied to qubit {} twice", name, qubit)));
+        }
+
+        match self.definition(name, visible) {
+            Some(Definition::Subcircuit(subcircuit)) => Ok(vec![Operations::Subcircuit(subcircuit.clone(), qubits.to_vec())]),
+            Some(Definition::Parameterised { parameters, qubits: formal, body, visible }) => {
+                let parameters = parameters.iter().cloned().zip(angles.iter().copied()).collect();
+                let qubits = formal.iter().cloned().zip(qubits.iter().copied()).collect();
+                self.expand(body.clone(), &parameters, &qubits, *visible)
+            }
+            None => Ok(standard_gate(name, angles, qubits)),
+        }
+    }
+
+    /// The gates of the body of a gate definition, with the given values of its parameters and qubits,
+    /// which sees the first visible gates the program defines. Barriers inside definitions are dropped.
+    fn expand(
+        &self,
+        body: Pair<'i, Rule>,
+        parameters: &HashMap<String, Angle>,
+        qubits: &HashMap<String, usize>,
+        visible: usize,
+    ) -> Result<Vec<Operations>, BraKetError> {
+        let mut gates = vec![];
+        for call in body.into_inner().filter(|statement| statement.as_rule() == Rule::gate_call) {
+            let Call { name, angles, arguments } = self.call(&call, parameters)?;
+            let qubits = arguments
+                .into_iter()
+                .map(|argument| {
+                    let mut inner = argument.clone().into_inner();
+                    let name = inner.next().unwrap().as_str();
+                    match (qubits.get(name), inner.next()) {
+                        (Some(qubit), None) => Ok(*qubit),
+                        _ => Err(error(&argument, format!("{} is not a qubit of the gate", argument.as_str()))),
+                    }
+                })
+                .collect::<Result<Vec<usize>, BraKetError>>()?;
+            gates.append(&mut self.gate(&call, &name, &angles, &qubits, visible)?);
+        }
+        Ok(gates)
+    }
+
+    /// Reads a gate call, evaluating its angles with the given values of the parameters.
+    fn call(&self, call: &Pair<'i, Rule>, parameters: &HashMap<String, Angle>) -> Result<Call<'i>, BraKetError> {
+        let mut read = Call { name: String::new(), angles: vec![], arguments: vec![] };
+        for part in call.clone().into_inner() {
+            match part.as_rule() {
+                Rule::identifier => read.name = part.as_str().to_owned(),
+                Rule::expressions => {
+                    read.angles = part.into_inner().map(|expression| evaluate(expression, parameters)).collect::<Result<_, _>>()?
+                }
+                _ => read.arguments = part.into_inner().collect(),
+            }
+        }
+        Ok(read)
+    }
+
+    /// The qubits or classical bits an argument refers to, a single one when it is indexed and the whole
+    /// register otherwise. Indices into the little endian OpenQASM registers are reversed for big endian
+    /// programs, whose registers have their most significant element first.
+    fn resolve(&self, argument: &Pair<'i, Rule>, classical: bool) -> Result<Vec<usize>, BraKetError> {
+        let mut inner = argument.clone().into_inner();
+        let name = inner.next().unwrap().as_str();
+        let register = match classical {
+            true => self.program.classical_registers.iter().find(|r| r.name == name).map(|r| &r.bits),
+            false => self.program.quantum_registers.iter().find(|r| r.name == name).map(|r| &r.qubits),
+        };
+        let kind = if classical { "classical" } else { "quantum" };
+        let register = register.ok_or_else(|| error(argument, format!("unknown {} register {}", kind, name)))?;
+        match inner.next() {
+            Some(index) => {
+                let n: usize = index.as_str().parse().map_err(|_| error(&index, format!("{} is too large", index.as_str())))?;
+                let position = match self.endianness {
+                    Endianness::Little => Some(n),
+                    Endianness::Big => register.len().checked_sub(n + 1),
+                };
+                match position.and_then(|position| register.get(position)) {
+                    Some(element) => Ok(vec![*element]),
+                    None => Err(error(argument, format!("{} is outside the register {} of size {}", argument.as_str(), name, register.len()))),
+                }
+            }
+            None => Ok(register.clone()),
+        }
+    }
+
+    /// Applies a statement to each element of the registers it is given, single qubits or bits being
+    /// repeated for each. Every register must have the same size.
+    fn broadcast(statement: &Pair<'i, Rule>, arguments: &[Vec<usize>]) -> Result<Vec<Vec<usize>>, BraKetError> {
+        let sizes: Vec<usize> = arguments.iter().map(|argument| argument.len()).filter(|size| *size != 1).collect();
+        if sizes.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err(error(statement, "registers of different sizes".to_owned()));
+        }
+        let size = sizes.first().copied().unwrap_or(1);
+        Ok((0..size)
+            .map(|n| arguments.iter().map(|argument| if argument.len() == 1 { argument[0] } else { argument[n] }).collect())
+            .collect())
+    }
+
+    fn declare(&mut self, declaration: Pair<'i, Rule>) -> Result<(), BraKetError> {
+        let rule = declaration.as_rule();
+        let mut inner = declaration.clone().into_inner();
+        let name = inner.next().unwrap().as_str();
+        let size: usize = inner.next().unwrap().as_str().parse().map_err(|_| error(&declaration, "register too large".to_owned()))?;
+        let (allocated, kind) = match rule {
+            Rule::qreg => (self.program.number_of_qubits(), "qubits"),
+            _ => (self.program.number_of_classical_bits(), "classical bits"),
+        };
+        if allocated.saturating_add(size) > MAX_REGISTER_SIZE {
+            return Err(error(&declaration, format!("the registers hold more than the {} {} allowed", MAX_REGISTER_SIZE, kind)));
+        }
+        let mut registers = self.program.quantum_registers.iter().map(|r| &r.name).chain(self.program.classical_registers.iter().map(|r| &r.name));
+        if registers.any(|r| r == name) {
+            return Err(error(&declaration, format!("a register named {} already exists", name)));
+        }
+        match rule {
+            Rule::qreg => {
+                self.program.quantum_register(name, size);
+            }
+            _ => {
+                self.program.classical_register(name, size);
+            }
+        }
+        Ok(())
+    }
+
+    fn define(&mut self, definition: Pair<'i, Rule>) -> Result<(), BraKetError> {
+        let mut inner = definition.clone().into_inner();
+        let name = inner.next().unwrap().as_str().to_owned();
+        let mut parameters = vec![];
+        let mut part = inner.next().unwrap();
+        if part.as_rule() == Rule::parameters {
+            if let Some(names) = part.into_inner().next() {
+                parameters = identifiers(names)?;
+            }
+            part = inner.next().unwrap();
+        }
+        let qubits = identifiers(part)?;
+        let body = inner.next().unwrap();
+        if self.definitions.contains_key(&name) {
+            return Err(error(&definition, format!("the gate {} is already defined", name)));
+        }
+        // a body can only call the gates defined before it, so expanding a gate always ends
+        for call in body.clone().into_inner().filter(|statement| statement.as_rule() == Rule::gate_call) {
+            let called = call.clone().into_inner().next().unwrap().as_str();
+            if called == name {
+                return Err(error(&call, format!("the gate {} calls itself", name)));
+            }
+            if !self.definitions.contains_key(called) && standard_signature(called).is_none() {
+                return Err(error(&call, format!("the gate {} calls {}, which is not defined before it", name, called)));
+            }
+        }
+
+        let visible = self.defined.len();
+        let definition = match parameters.is_empty() {
+            true => {
+                let formal = qubits.iter().cloned().zip(0..qubits.len()).collect();
+                let gates = self.expand(body, &HashMap::new(), &formal, visible)?;
+                Definition::Subcircuit(Arc::new(Subcircuit { name: name.clone(), number_of_qubits: qubits.len(), gates }))
+            }
+            false => Definition::Parameterised { parameters, qubits, body, visible },
+        };
+        self.defined.push(name.clone());
+        self.definitions.insert(name, definition);
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: Pair<'i, Rule>) -> Result<(), BraKetError> {
+        match statement.as_rule() {
+            Rule::include => {
+                let file = statement.clone().into_inner().next().unwrap().as_str().trim_matches('"');
+                if file != "qelib1.inc" {
+                    return Err(error(&statement, format!("cannot include {}, only qelib1.inc is known", file)));
+                }
+            }
+            Rule::qreg | Rule::creg => self.declare(statement)?,
+            Rule::gate_definition => self.define(statement)?,
+            Rule::opaque => {}
+            Rule::measure => {
+                let mut inner = statement.clone().into_inner();
+                let qubits = self.resolve(&inner.next().unwrap(), false)?;
+                let bits = self.resolve(&inner.next().unwrap(), true)?;
+                if qubits.len() != bits.len() {
+                    return Err(error(&statement, format!("cannot measure {} qubits into {} bits", qubits.len(), bits.len())));
+                }
+                self.program.measure_register(&qubits, &bits);
+            }
+            Rule::reset => {
+                for qubit in self.resolve(&statement.clone().into_inner().next().unwrap(), false)? {
+                    self.program.reset(qubit);
+                }
+            }
+            Rule::barrier => self.program.add_gate(Operations::Barrier),
+            Rule::conditional => return Err(error(&statement, "classically controlled gates are not supported".to_owned())),
+            Rule::gate_call => {
+                let Call { name, angles, arguments } = self.call(&statement, &HashMap::new())?;
+                let arguments = arguments.iter().map(|argument| self.resolve(argument, false)).collect::<Result<Vec<_>, _>>()?;
+                for qubits in Reader::broadcast(&statement, &arguments)? {
+                    let gates = self.gate(&statement, &name, &angles, &qubits, self.defined.len())?;
+                    self.program.add_gates(gates);
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Reads an OpenQASM 2.0 program. Registers keep their names, gates defined without parameters become
+/// subcircuits and gates with parameters are expanded where they are called. Gates of the standard library
+/// without a counterpart in Operations, ccx and cu3, are decomposed as in qelib1.inc. The elements of the
+/// registers are put in the order of the given endianness, see to_qasm.
+pub fn from_qasm(source: &str, endianness: Endianness) -> Result<Program, BraKetError> {
+    let program = QasmParser::parse(Rule::program, source)
+        .map_err(|e| {
+            let line = match e.line_col {
+                LineColLocation::Pos((line, _)) | LineColLocation::Span((line, _), _) => line,
+            };
+            BraKetError::InvalidQasm { line, message: e.variant.message().into_owned() }
+        })?
+        .next()
+        .unwrap();
+
+    let mut reader = Reader { program: Program::new(), endianness, definitions: HashMap::new(), defined: vec![] };
+    for statement in program.into_inner() {
+        match statement.as_rule() {
+            Rule::version if !matches!(statement.as_str(), "2" | "2.0") => {
+                return Err(error(&statement, format!("OpenQASM {} is not supported, only 2.0", statement.as_str())))
+            }
+            _ => reader.statement(statement)?,
+        }
+    }
+    Ok(reader.program)
+}
@@ -0,0 +1,114 @@
+use crate::draw::{gate_label, Element, Layout, Symbol};
+use crate::program::Program;
+use crate::types::Angle;
+
+/// Escapes the characters of a name which are special in LaTeX.
+fn escape(name: &str) -> String {
+    let mut escaped = String::new();
+    for character in name.chars() {
+        if "_&%$#{}".contains(character) {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+fn gate(name: &str, angles: &[Angle], index: Option<usize>) -> String {
+    gate_label(&format!("\\mathrm{{{}}}", escape(name)), angles, index, "\\pi")
+}
+
+/// The command drawing a symbol on its own, without a vertical wire.
+fn command(symbol: &Symbol) -> String {
+    match symbol {
+        Symbol::Control => "\\control{}".to_owned(),
+        Symbol::Target => "\\targ{}".to_owned(),
+        Symbol::Swap => "\\targX{}".to_owned(),
+        Symbol::Barrier => "\\qw".to_owned(),
+        Symbol::Reset => "\\gate{|0\\rangle}".to_owned(),
+        Symbol::Measure("M", _) => "\\meter{}".to_owned(),
+        Symbol::Measure(basis, _) => format!("\\gate{{M_{{{}}}}}", basis.trim_start_matches('M')),
+        Symbol::Gate { name, angles, index } => format!("\\gate{{{}}}", gate(name, angles, *index)),
+    }
+}
+
+/// The command drawing a symbol with a vertical wire down to the row length rows below it.
+fn command_with_wire(symbol: &Symbol, length: usize) -> String {
+    match symbol {
+        Symbol::Control => format!("\\ctrl{{{}}}", length),
+        Symbol::Swap => format!("\\swap{{{}}}", length),
+        _ => format!("{} \\vqw{{{}}}", command(symbol), length),
+    }
+}
+
+/// The boxes of an element if they are one gate on adjacent qubits in order, which is drawn as a single
+/// box over those wires, as (first row, number of rows, label).
+fn spanning_box(element: &Element) -> Option<(usize, usize, String)> {
+    let (first, symbol) = element.symbols.first()?;
+    let (name, angles) = match symbol {
+        Symbol::Gate { name, angles, .. } => (name, angles),
+        _ => return None,
+    };
+    let in_order = element.symbols.iter().enumerate().all(|(n, (row, symbol))| match symbol {
+        Symbol::Gate { name: other, index, .. } => other == name && *row == first + n && index.is_none_or(|index| index == n),
+        _ => false,
+    });
+    (element.symbols.len() > 1 && in_order).then(|| (*first, element.symbols.len(), gate(name, angles, None)))
+}
+
+/// Writes the cells an element takes up in a column.
+fn draw_element(cells: &mut [Option<String>], element: &Element, number_of_qubits: usize) {
+    if let Some((first, wires, label)) = spanning_box(element) {
+        cells[first] = Some(format!("\\gate[wires={}]{{{}}}", wires, label));
+        for cell in cells[first + 1..first + wires].iter_mut() {
+            *cell = Some("\\qw".to_owned());
+        }
+        return;
+    }
+    let first = element.symbols.iter().map(|(row, _)| *row).min().unwrap();
+    let last = element.symbols.iter().map(|(row, _)| *row).max().unwrap();
+    for (row, symbol) in element.symbols.iter() {
+        let mut text = match element.connected && *row == first && first < last {
+            true => command_with_wire(symbol, last - first),
+            false => command(symbol),
+        };
+        match symbol {
+            Symbol::Measure(_, Some((wire, _))) => text.push_str(&format!(" \\vcw{{{}}}", number_of_qubits + wire - row)),
+            Symbol::Barrier if *row == 0 => text.push_str(" \\slice{}"),
+            _ => {}
+        }
+        cells[*row] = Some(text);
+    }
+}
+
+/// Writes a program as a quantikz environment, with the same layout as its text drawing. The output
+/// needs \usepackage{quantikz} in the preamble of the document.
+pub fn to_quantikz(program: &Program) -> String {
+    let layout = Layout::new(program);
+    let number_of_qubits = layout.qubits.len();
+    let rows = layout.rows();
+    let mut lines: Vec<Vec<String>> = layout
+        .qubits
+        .iter()
+        .chain(layout.classical.iter().map(|(label, _)| label))
+        .map(|label| vec![format!("\\lstick{{{}}}", escape(label))])
+        .collect();
+    for column in layout.columns.iter() {
+        let mut cells = vec![None; rows];
+        for element in column.iter() {
+            draw_element(&mut cells, element, number_of_qubits);
+        }
+        for (row, cell) in cells.into_iter().enumerate() {
+            let wire = if row < number_of_qubits { "\\qw" } else { "\\cw" };
+            lines[row].push(cell.unwrap_or_else(|| wire.to_owned()));
+        }
+    }
+    let mut latex = vec!["\\begin{quantikz}".to_owned()];
+    for (row, mut line) in lines.into_iter().enumerate() {
+        line.push(if row < number_of_qubits { "\\qw" } else { "\\cw" }.to_owned());
+        let separator = if row + 1 < rows { " \\\\" } else { "" };
+        latex.push(format!("{}{}", line.join(" & "), separator));
+    }
+    latex.push("\\end{quantikz}".to_owned());
+    latex.join("\n") + "\n"
+}
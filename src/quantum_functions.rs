@@ -8,16 +8,16 @@ fn qft_no_swap(mut program: Program, n: usize) -> Program {
     for qubit in 0..n {
         let k = n - qubit - 1;
         let angle = PI / (2 << k) as Real;
-        program.crz(n, qubit, angle)
+        program.cphase(n, qubit, angle)
     }
-    return qft_no_swap(program, n)
+    qft_no_swap(program, n)
 }
 
 /// creates a program which performs the quantum fourier transform
 pub fn qft(n: usize) -> Program {
     let mut program = Program::new();
     program = qft_no_swap(program, n);
-    let n_half = n - 1 / 2;
+    let n_half = n / 2;
     if n > 1 {
         for i in 0..n_half {
         program.swap(i, n - i - 1)
@@ -0,0 +1,321 @@
+use std::collections::BTreeMap;
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::basis::{BasisLabel, Endianness};
+use crate::error::BraKetError;
+use crate::program::Program;
+use crate::types::*;
+
+/// The confusion matrix of a group of qubits, whose element (measured, prepared) is the probability of reading
+/// the bitstring measured when the qubits are in the basis state prepared. Bit k of both indices is the k-th
+/// qubit of the group.
+#[derive(Debug, Clone, PartialEq)]
+struct Group {
+    qubits: Vec<usize>,
+    confusion: RMatrix,
+}
+
+/// The readout errors of a device, a confusion matrix for each qubit or for groups of qubits whose errors
+/// are correlated. The bits of the counts it acts on are the qubits, bit k being qubit k in the endianness
+/// the counts are written with.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ReadoutModel {
+    number_of_qubits: usize,
+    groups: Vec<Group>,
+}
+
+/// Checks a confusion matrix is square with a dimension of 2 ^ number_of_qubits, and each of its columns a
+/// probability distribution.
+fn check_confusion_matrix(confusion: &RMatrix, number_of_qubits: usize) -> Result<(), BraKetError> {
+    if confusion.nrows() != confusion.ncols() {
+        return Err(BraKetError::NotSquare { rows: confusion.nrows(), columns: confusion.ncols() });
+    }
+    if confusion.nrows() != 1 << number_of_qubits {
+        return Err(BraKetError::WrongDimension { expected: 1 << number_of_qubits, found: confusion.nrows() });
+    }
+    let stochastic = confusion.iter().all(|p| (0. ..=1.).contains(p)) && confusion.column_iter().all(|column| (column.sum() - 1.).abs() < 1e-9);
+    match stochastic {
+        true => Ok(()),
+        false => Err(BraKetError::NotStochastic),
+    }
+}
+
+/// Applies the matrix of each group to the qubits of the group of a vector indexed by basis state.
+fn apply_groups<'a>(vector: &[Real], groups: impl Iterator<Item = (&'a [usize], RMatrix)>) -> Vec<Real> {
+    let mut vector = vector.to_vec();
+    for (qubits, matrix) in groups {
+        let mask: usize = qubits.iter().map(|qubit| 1 << qubit).sum();
+        let sub_index = |index: usize| qubits.iter().enumerate().map(|(bit, qubit)| ((index >> qubit) & 1) << bit).sum::<usize>();
+        let full_index = |base: usize, sub: usize| qubits.iter().enumerate().fold(base, |index, (bit, qubit)| index | (((sub >> bit) & 1) << qubit));
+        let mut result = vec![0.; vector.len()];
+        for (index, value) in vector.iter().enumerate().filter(|(_, value)| **value != 0.) {
+            let (base, column) = (index & !mask, sub_index(index));
+            for row in 0..matrix.nrows() {
+                result[full_index(base, row)] += matrix[(row, column)] * value;
+            }
+        }
+        vector = result;
+    }
+    vector
+}
+
+/// Projects a vector onto the probability distributions, the closest point of the simplex in the euclidean norm.
+fn project_onto_simplex(vector: &[Real]) -> Vec<Real> {
+    let mut sorted = vector.to_vec();
+    sorted.sort_by(|a, b| b.total_cmp(a));
+    let mut sum = 0.;
+    let mut shift = 0.;
+    for (n, value) in sorted.iter().enumerate() {
+        sum += value;
+        let candidate = (sum - 1.) / (n + 1) as Real;
+        if value - candidate > 0. {
+            shift = candidate;
+        }
+    }
+    vector.iter().map(|value| (value - shift).max(0.)).collect()
+}
+
+impl ReadoutModel {
+    /// A model of number_of_qubits qubits which are read without errors.
+    pub fn new(number_of_qubits: usize) -> ReadoutModel {
+        let groups = (0..number_of_qubits).map(|qubit| Group { qubits: vec![qubit], confusion: RMatrix::identity(2, 2) }).collect();
+        ReadoutModel { number_of_qubits, groups }
+    }
+
+    /// A model with the same probability of misreading 0 and 1 on each of number_of_qubits qubits.
+    pub fn uniform(number_of_qubits: usize, zero_to_one: Real, one_to_zero: Real) -> Result<ReadoutModel, BraKetError> {
+        let mut model = ReadoutModel::new(number_of_qubits);
+        for qubit in 0..number_of_qubits {
+            model.set_qubit_error(qubit, zero_to_one, one_to_zero)?;
+        }
+        Ok(model)
+    }
+
+    pub fn number_of_qubits(&self) -> usize {
+        self.number_of_qubits
+    }
+
+    /// Adds qubits read without errors until the model has number_of_qubits qubits.
+    pub(crate) fn extend(&mut self, number_of_qubits: usize) {
+        for qubit in self.number_of_qubits..number_of_qubits {
+            self.groups.push(Group { qubits: vec![qubit], confusion: RMatrix::identity(2, 2) });
+        }
+        self.number_of_qubits = self.number_of_qubits.max(number_of_qubits);
+    }
+
+    /// Whether the errors of some qubits are correlated.
+    pub(crate) fn is_correlated(&self) -> bool {
+        self.groups.iter().any(|group| group.qubits.len() > 1)
+    }
+
+    /// The probabilities of reading 1 when qubit is in |0> and 0 when it is in |1>, if the qubit is read with
+    /// an error which is not correlated with other qubits.
+    pub(crate) fn qubit_error(&self, qubit: usize) -> Option<[Real; 2]> {
+        let group = self.groups.iter().find(|group| group.qubits == [qubit])?;
+        let flip = [group.confusion[(1, 0)], group.confusion[(0, 1)]];
+        match flip == [0., 0.] {
+            true => None,
+            false => Some(flip),
+        }
+    }
+
+    /// Sets the probabilities with which qubit is read as 1 when it is in |0>, and as 0 when it is in |1>,
+    /// replacing any error on the qubit which is not correlated with other qubits.
+    pub fn set_qubit_error(&mut self, qubit: usize, zero_to_one: Real, one_to_zero: Real) -> Result<&mut ReadoutModel, BraKetError> {
+        let confusion = RMatrix::from_row_slice(2, 2, &[1. - zero_to_one, one_to_zero, zero_to_one, 1. - one_to_zero]);
+        self.set_correlated_error(&[qubit], confusion)
+    }
+
+    /// Sets the confusion matrix of a group of qubits whose readout errors are correlated, the element
+    /// (measured, prepared) being the probability of reading the bitstring measured when the qubits are in
+    /// the basis state prepared, with bit k of both the k-th qubit of the group. The qubits must not be
+    /// in another group of more than one qubit.
+    pub fn set_correlated_error(&mut self, qubits: &[usize], confusion: RMatrix) -> Result<&mut ReadoutModel, BraKetError> {
+        for (n, qubit) in qubits.iter().enumerate() {
+            if *qubit >= self.number_of_qubits {
+                return Err(BraKetError::InvalidQubit { qubit: *qubit, number_of_qubits: self.number_of_qubits });
+            }
+            if qubits[..n].contains(qubit) {
+                return Err(BraKetError::RepeatedQubit(*qubit));
+            }
+        }
+        check_confusion_matrix(&confusion, qubits.len())?;
+        let overlapping = |group: &Group| group.qubits.iter().any(|qubit| qubits.contains(qubit));
+        if self.groups.iter().any(|group| group.qubits.len() > 1 && overlapping(group) && group.qubits != qubits) {
+            return Err(BraKetError::Unsupported("readout errors on overlapping groups of qubits"));
+        }
+        self.groups.retain(|group| !overlapping(group));
+        self.groups.push(Group { qubits: qubits.to_vec(), confusion });
+        Ok(self)
+    }
+
+    /// Estimates the model from the counts of the circuits of calibration_circuits, in the same order, as a
+    /// single group correlating every qubit.
+    pub fn from_calibration(counts: &[BTreeMap<String, usize>], endianness: Endianness) -> Result<ReadoutModel, BraKetError> {
+        if !counts.len().is_power_of_two() {
+            return Err(BraKetError::NotPowerOfTwo(counts.len()));
+        }
+        let number_of_qubits = counts.len().trailing_zeros() as usize;
+        let mut confusion = RMatrix::zeros(counts.len(), counts.len());
+        for (prepared, counts) in counts.iter().enumerate() {
+            let distribution = distribution(counts, number_of_qubits, endianness)?;
+            confusion.set_column(prepared, &RVector::from_vec(distribution));
+        }
+        let mut model = ReadoutModel::new(number_of_qubits);
+        if number_of_qubits > 0 {
+            model.set_correlated_error(&(0..number_of_qubits).collect::<Vec<usize>>(), confusion)?;
+        }
+        Ok(model)
+    }
+
+    /// Estimates a model of uncorrelated errors from the counts of the two circuits of
+    /// tensored_calibration_circuits, all qubits prepared in |0> and all in |1>.
+    pub fn from_tensored_calibration(
+        zeros: &BTreeMap<String, usize>,
+        ones: &BTreeMap<String, usize>,
+        endianness: Endianness,
+    ) -> Result<ReadoutModel, BraKetError> {
+        let number_of_qubits = zeros.keys().next().or(ones.keys().next()).map(|label| label.len()).unwrap_or(0);
+        let zeros = distribution(zeros, number_of_qubits, endianness)?;
+        let ones = distribution(ones, number_of_qubits, endianness)?;
+        // the probability that a qubit is read as 1, as a marginal of the distribution
+        let read_as_one = |distribution: &[Real], qubit: usize| -> Real {
+            distribution.iter().enumerate().filter(|(index, _)| (index >> qubit) & 1 == 1).map(|(_, p)| p).sum()
+        };
+        let mut model = ReadoutModel::new(number_of_qubits);
+        for qubit in 0..number_of_qubits {
+            model.set_qubit_error(qubit, read_as_one(&zeros, qubit), 1. - read_as_one(&ones, qubit))?;
+        }
+        Ok(model)
+    }
+
+    /// The confusion matrix of every qubit, of dimension 2 ^ number_of_qubits, indexed by basis state with qubit
+    /// k as bit k.
+    pub fn confusion_matrix(&self) -> RMatrix {
+        let dimension = 1 << self.number_of_qubits;
+        let mut matrix = RMatrix::zeros(dimension, dimension);
+        for prepared in 0..dimension {
+            let mut basis_state = vec![0.; dimension];
+            basis_state[prepared] = 1.;
+            matrix.set_column(prepared, &RVector::from_vec(self.confuse(&basis_state)));
+        }
+        matrix
+    }
+
+    fn confuse(&self, distribution: &[Real]) -> Vec<Real> {
+        apply_groups(distribution, self.groups.iter().map(|group| (group.qubits.as_slice(), group.confusion.clone())))
+    }
+
+    fn confuse_transpose(&self, vector: &[Real]) -> Vec<Real> {
+        apply_groups(vector, self.groups.iter().map(|group| (group.qubits.as_slice(), group.confusion.transpose())))
+    }
+
+    /// Applies the readout errors to counts of error free measurements, sampling the bitstring read for each shot.
+    pub fn apply(&self, counts: &BTreeMap<String, usize>, seed: u64, endianness: Endianness) -> Result<BTreeMap<String, usize>, BraKetError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let distributions: Vec<Vec<WeightedIndex<Real>>> = self
+            .groups
+            .iter()
+            .map(|group| group.confusion.column_iter().map(|column| WeightedIndex::new(column.iter().copied()).unwrap()).collect())
+            .collect();
+        let mut confused = BTreeMap::new();
+        for (label, count) in counts.iter() {
+            let prepared = label.as_str().index(self.number_of_qubits, endianness)?;
+            for _ in 0..*count {
+                let mut measured = 0;
+                for (group, distributions) in self.groups.iter().zip(distributions.iter()) {
+                    let column = group.qubits.iter().enumerate().map(|(bit, qubit)| ((prepared >> qubit) & 1) << bit).sum::<usize>();
+                    let row = distributions[column].sample(&mut rng);
+                    measured |= group.qubits.iter().enumerate().map(|(bit, qubit)| ((row >> bit) & 1) << qubit).sum::<usize>();
+                }
+                *confused.entry(endianness.bitstring(measured, self.number_of_qubits)).or_insert(0) += 1;
+            }
+        }
+        Ok(confused)
+    }
+
+    /// Corrects counts by applying the inverse of the confusion matrix to their distribution. The result sums to
+    /// one but may have negative elements, a quasi-probability distribution.
+    pub fn mitigate_by_inversion(&self, counts: &BTreeMap<String, usize>, endianness: Endianness) -> Result<BTreeMap<String, Real>, BraKetError> {
+        let measured = distribution(counts, self.number_of_qubits, endianness)?;
+        let inverses = self
+            .groups
+            .iter()
+            .map(|group| Ok((group.qubits.as_slice(), group.confusion.clone().try_inverse().ok_or(BraKetError::NotInvertible)?)))
+            .collect::<Result<Vec<_>, BraKetError>>()?;
+        Ok(labelled(&apply_groups(&measured, inverses.into_iter()), endianness))
+    }
+
+    /// Corrects counts by finding the probability distribution which the readout errors take closest to the
+    /// measured distribution, in the least squares sense. Unlike inversion, the result has no negative elements.
+    pub fn mitigate_by_least_squares(&self, counts: &BTreeMap<String, usize>, endianness: Endianness) -> Result<BTreeMap<String, Real>, BraKetError> {
+        let measured = distribution(counts, self.number_of_qubits, endianness)?;
+        // the step of projected gradient descent is the inverse of the largest singular value of the confusion
+        // matrix squared, the product of those of the groups
+        let norm: Real = self.groups.iter().map(|group| group.confusion.singular_values().max()).product();
+        let step = 1. / (norm * norm);
+        let mut estimate = measured.clone();
+        for _ in 0..10000 {
+            let residual: Vec<Real> = self.confuse(&estimate).iter().zip(measured.iter()).map(|(a, b)| a - b).collect();
+            let gradient = self.confuse_transpose(&residual);
+            let next = project_onto_simplex(&estimate.iter().zip(gradient.iter()).map(|(x, g)| x - step * g).collect::<Vec<Real>>());
+            let change: Real = next.iter().zip(estimate.iter()).map(|(a, b)| (a - b).abs()).sum();
+            estimate = next;
+            if change < 1e-12 {
+                break;
+            }
+        }
+        Ok(labelled(&estimate, endianness))
+    }
+}
+
+/// The distribution of counts over the basis states of number_of_qubits qubits.
+fn distribution(counts: &BTreeMap<String, usize>, number_of_qubits: usize, endianness: Endianness) -> Result<Vec<Real>, BraKetError> {
+    let shots: usize = counts.values().sum();
+    let mut distribution = vec![0.; 1 << number_of_qubits];
+    for (label, count) in counts.iter() {
+        distribution[label.as_str().index(number_of_qubits, endianness)?] += *count as Real / shots.max(1) as Real;
+    }
+    Ok(distribution)
+}
+
+/// Labels the elements of a distribution which are not negligible by their bitstrings.
+fn labelled(distribution: &[Real], endianness: Endianness) -> BTreeMap<String, Real> {
+    let number_of_qubits = distribution.len().trailing_zeros() as usize;
+    distribution
+        .iter()
+        .enumerate()
+        .filter(|(_, p)| p.abs() > 1e-12)
+        .map(|(index, p)| (endianness.bitstring(index, number_of_qubits), *p))
+        .collect()
+}
+
+/// The circuits calibrating the readout of number_of_qubits qubits, one preparing and measuring each of the
+/// 2 ^ number_of_qubits basis states in order. Their counts give the model of ReadoutModel::from_calibration.
+pub fn calibration_circuits(number_of_qubits: usize) -> Vec<Program> {
+    (0..1usize << number_of_qubits)
+        .map(|prepared| {
+            let mut program = Program::new();
+            program.quantum_register("q", number_of_qubits);
+            (0..number_of_qubits).filter(|qubit| (prepared >> qubit) & 1 == 1).for_each(|qubit| program.x(qubit));
+            program.measure_all();
+            program
+        })
+        .collect()
+}
+
+/// The two circuits calibrating uncorrelated readout errors of number_of_qubits qubits, measuring all qubits in
+/// |0> and all in |1>. Their counts give the model of ReadoutModel::from_tensored_calibration.
+pub fn tensored_calibration_circuits(number_of_qubits: usize) -> [Program; 2] {
+    let mut zeros = Program::new();
+    zeros.quantum_register("q", number_of_qubits);
+    zeros.measure_all();
+    let mut ones = Program::new();
+    ones.quantum_register("q", number_of_qubits);
+    (0..number_of_qubits).for_each(|qubit| ones.x(qubit));
+    ones.measure_all();
+    [zeros, ones]
+}
@@ -0,0 +1,55 @@
+use std::ops::Deref;
+
+/// The most classical bits a program may use. The outcome of every bit up to the highest one measured into
+/// is stored and sampled, so measurements into bits beyond this are rejected.
+pub const MAX_CLASSICAL_BITS: usize = 1 << 16;
+
+/// The most qubits, or classical bits, the registers of a program read from OpenQASM or JSON may hold
+/// together, checked before they are allocated so that untrusted input cannot exhaust the memory.
+pub const MAX_REGISTER_SIZE: usize = 1 << 16;
+
+/// A named group of qubits allocated from a program. It dereferences to the slice of qubit indices, so
+/// register[n] is the index of its n-th qubit and &register[a..b] a slice of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuantumRegister {
+    pub name: String,
+    pub qubits: Vec<usize>,
+}
+
+/// A named group of classical bits allocated from a program, which measurements can be stored in. It
+/// dereferences to the slice of bit indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClassicalRegister {
+    pub name: String,
+    pub bits: Vec<usize>,
+}
+
+impl Deref for QuantumRegister {
+    type Target = [usize];
+    fn deref(&self) -> &[usize] {
+        &self.qubits
+    }
+}
+
+impl Deref for ClassicalRegister {
+    type Target = [usize];
+    fn deref(&self) -> &[usize] {
+        &self.bits
+    }
+}
+
+impl QuantumRegister {
+    /// Returns a copy of the register with every qubit index passed through map.
+    pub fn map_qubits<F: Fn(usize) -> usize>(&self, map: F) -> QuantumRegister {
+        QuantumRegister { name: self.name.to_owned(), qubits: self.qubits.iter().map(|qubit| map(*qubit)).collect() }
+    }
+}
+
+impl ClassicalRegister {
+    /// Returns a copy of the register with every bit index passed through map.
+    pub fn map_bits<F: Fn(usize) -> usize>(&self, map: F) -> ClassicalRegister {
+        ClassicalRegister { name: self.name.to_owned(), bits: self.bits.iter().map(|bit| map(*bit)).collect() }
+    }
+}
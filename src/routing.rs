@@ -0,0 +1,368 @@
+use std::collections::VecDeque;
+
+use crate::error::BraKetError;
+use crate::operations::*;
+use crate::program::Program;
+use crate::subcircuit::*;
+use crate::types::Real;
+
+/// The connectivity of a device, as an (undirected) list of pairs of physical qubits on which two qubit
+/// gates can be performed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CouplingMap {
+    /// The number of physical qubits on the device.
+    pub number_of_qubits: usize,
+    /// The pairs of physical qubits which are coupled.
+    pub edges: Vec<(usize, usize)>,
+}
+
+/// The result of routing a program onto a coupling map.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Routing {
+    /// The routed program, acting on physical qubits, with SWAP gates inserted where required.
+    pub program: Program,
+    /// The physical qubit each logical qubit is placed on at the start of the program.
+    pub initial_layout: Vec<usize>,
+    /// The physical qubit each logical qubit ends up on at the end of the program.
+    pub final_layout: Vec<usize>,
+    /// The number of SWAP gates inserted.
+    pub number_of_swaps: usize,
+}
+
+impl CouplingMap {
+    /// A coupling map of the given edges, failing if an edge joins a qubit to itself or to a qubit beyond
+    /// number_of_qubits.
+    pub fn new(number_of_qubits: usize, edges: Vec<(usize, usize)>) -> Result<CouplingMap, BraKetError> {
+        for (a, b) in edges.iter() {
+            if let Some(qubit) = [a, b].into_iter().find(|qubit| **qubit >= number_of_qubits) {
+                return Err(BraKetError::InvalidQubit { qubit: *qubit, number_of_qubits });
+            }
+            if a == b {
+                return Err(BraKetError::RepeatedQubit(*a));
+            }
+        }
+        Ok(CouplingMap { number_of_qubits, edges })
+    }
+
+    /// A line of qubits, each coupled to its neighbours.
+    pub fn line(number_of_qubits: usize) -> CouplingMap {
+        let edges = (1..number_of_qubits).map(|n| (n - 1, n)).collect();
+        CouplingMap { number_of_qubits, edges }
+    }
+
+    /// A rectangular grid of qubits, numbered row by row, each coupled to its nearest neighbours.
+    pub fn grid(rows: usize, columns: usize) -> CouplingMap {
+        let index = |row: usize, column: usize| row * columns + column;
+        let mut edges = vec![];
+        for (row, column) in (0..rows).flat_map(|row| (0..columns).map(move |column| (row, column))) {
+            if column + 1 < columns {
+                edges.push((index(row, column), index(row, column + 1)));
+            }
+            if row + 1 < rows {
+                edges.push((index(row, column), index(row + 1, column)));
+            }
+        }
+        CouplingMap { number_of_qubits: rows * columns, edges }
+    }
+
+    /// A heavy hexagon lattice, a hexagonal lattice with rows of columns vertices (laid out as a brick
+    /// wall) with an additional qubit placed on every edge. The vertex qubits are numbered first, row by
+    /// row, followed by the edge qubits.
+    pub fn heavy_hex(rows: usize, columns: usize) -> CouplingMap {
+        let index = |row: usize, column: usize| row * columns + column;
+        let mut lattice_edges = vec![];
+        for (row, column) in (0..rows).flat_map(|row| (0..columns).map(move |column| (row, column))) {
+            if column + 1 < columns {
+                lattice_edges.push((index(row, column), index(row, column + 1)));
+            }
+            // every vertex has exactly one vertical bond, alternating between up and down
+            if row + 1 < rows && (row + column) % 2 == 0 {
+                lattice_edges.push((index(row, column), index(row + 1, column)));
+            }
+        }
+
+        let mut number_of_qubits = rows * columns;
+        let mut edges = vec![];
+        for (a, b) in lattice_edges {
+            edges.push((a, number_of_qubits));
+            edges.push((number_of_qubits, b));
+            number_of_qubits += 1;
+        }
+        CouplingMap { number_of_qubits, edges }
+    }
+
+    /// Returns true if a two qubit gate can be performed directly between the physical qubits.
+    pub fn are_connected(&self, a: usize, b: usize) -> bool {
+        self.edges.iter().any(|edge| *edge == (a, b) || *edge == (b, a))
+    }
+
+    /// The physical qubits coupled to the qubit.
+    pub fn neighbours(&self, qubit: usize) -> Vec<usize> {
+        let mut neighbours: Vec<usize> = self.edges
+            .iter()
+            .filter_map(|(a, b)| match (*a == qubit, *b == qubit) {
+                (true, _) => Some(*b),
+                (_, true) => Some(*a),
+                _ => None,
+            })
+            .collect();
+        neighbours.sort();
+        neighbours.dedup();
+        neighbours
+    }
+
+    /// The length of the shortest path between every pair of physical qubits, or None if they are
+    /// not connected.
+    pub fn distances(&self) -> Vec<Vec<Option<usize>>> {
+        let neighbours: Vec<Vec<usize>> = (0..self.number_of_qubits).map(|n| self.neighbours(n)).collect();
+        (0..self.number_of_qubits)
+            .map(|source| {
+                // breadth first search from the source
+                let mut distance = vec![None; self.number_of_qubits];
+                distance[source] = Some(0);
+                let mut queue = VecDeque::from([source]);
+                while let Some(qubit) = queue.pop_front() {
+                    for neighbour in neighbours[qubit].iter() {
+                        if distance[*neighbour].is_none() {
+                            distance[*neighbour] = distance[qubit].map(|d| d + 1);
+                            queue.push_back(*neighbour);
+                        }
+                    }
+                }
+                distance
+            })
+            .collect()
+    }
+}
+
+/// The number of two qubit gates considered beyond the front layer when choosing a SWAP.
+static EXTENDED_SET_SIZE: usize = 20;
+/// The weight of the extended set relative to the front layer.
+static EXTENDED_SET_WEIGHT: Real = 0.5;
+/// The increase in the decay factor of a qubit each time it is swapped.
+static DECAY_INCREMENT: Real = 0.001;
+
+/// The qubits an operation depends on, where operations acting on the whole register depend on every qubit.
+fn operation_qubits(gate: &Operations, number_of_qubits: usize) -> Vec<usize> {
+    match gate {
+        Operations::Barrier | Operations::MeasureAll | Operations::ResetAll => (0..number_of_qubits).collect(),
+        _ => which_qubits(gate).into_iter().copied().collect(),
+    }
+}
+
+/// The two logical qubits of a two qubit gate, or None if the gate acts on fewer qubits.
+fn two_qubits(gate: &Operations) -> Option<(usize, usize)> {
+    match which_qubits(gate)[..] {
+        [a, b] => Some((*a, *b)),
+        _ => None,
+    }
+}
+
+/// A single SABRE pass over the gates, starting from the given layout (layout[logical] = physical).
+/// Returns the routed gates, the final layout and the number of SWAP gates inserted, or an error if a two
+/// qubit gate acts on qubits which no SWAP can bring together.
+fn sabre_pass(
+    gates: &[Operations],
+    coupling_map: &CouplingMap,
+    distances: &[Vec<usize>],
+    mut layout: Vec<usize>,
+) -> Result<(Vec<Operations>, Vec<usize>, usize), BraKetError> {
+    let number_of_qubits = coupling_map.number_of_qubits;
+
+    // the dependency graph of the gates, each gate depending on the previous gates acting on its qubits
+    let mut successors: Vec<Vec<usize>> = vec![vec![]; gates.len()];
+    let mut remaining_predecessors: Vec<usize> = vec![0; gates.len()];
+    let mut last_gate: Vec<Option<usize>> = vec![None; number_of_qubits];
+    for (n, gate) in gates.iter().enumerate() {
+        let mut predecessors: Vec<usize> = operation_qubits(gate, number_of_qubits)
+            .iter()
+            .filter_map(|qubit| last_gate[*qubit].replace(n))
+            .collect();
+        predecessors.sort();
+        predecessors.dedup();
+        for predecessor in predecessors {
+            successors[predecessor].push(n);
+            remaining_predecessors[n] += 1;
+        }
+    }
+
+    let mut logical: Vec<usize> = vec![0; number_of_qubits];
+    for (l, p) in layout.iter().enumerate() {
+        logical[*p] = l;
+    }
+
+    let mut routed = vec![];
+    let mut number_of_swaps = 0;
+    let mut swaps_without_progress = 0;
+    let mut decay: Vec<Real> = vec![1.; number_of_qubits];
+    let mut front: Vec<usize> = (0..gates.len()).filter(|n| remaining_predecessors[*n] == 0).collect();
+
+    while !front.is_empty() {
+        // executing every gate in the front layer whose qubits are adjacent
+        let mut executed = false;
+        let mut next_front = vec![];
+        for n in front.iter() {
+            let executable = match two_qubits(&gates[*n]) {
+                Some((a, b)) => distances[layout[a]][layout[b]] == 1,
+                None => true,
+            };
+            if executable {
+                routed.push(map_qubits(&gates[*n], |qubit| layout[qubit]));
+                executed = true;
+                for successor in successors[*n].iter() {
+                    remaining_predecessors[*successor] -= 1;
+                    if remaining_predecessors[*successor] == 0 {
+                        next_front.push(*successor);
+                    }
+                }
+            } else {
+                next_front.push(*n);
+            }
+        }
+        next_front.sort();
+        front = next_front;
+        if executed {
+            decay.iter_mut().for_each(|d| *d = 1.);
+            swaps_without_progress = 0;
+            continue;
+        }
+
+        let front_pairs: Vec<(usize, usize)> = front.iter().filter_map(|n| two_qubits(&gates[*n])).collect();
+
+        let mut swap_candidates: Vec<(usize, usize)> = vec![];
+        if swaps_without_progress > 10 * number_of_qubits {
+            // falling back to moving the first gate's qubits together along a shortest path
+            let (a, b) = front_pairs[0];
+            let next = coupling_map
+                .neighbours(layout[a])
+                .into_iter()
+                .find(|p| distances[*p][layout[b]] < distances[layout[a]][layout[b]]);
+            if let Some(next) = next {
+                swap_candidates.push((layout[a].min(next), layout[a].max(next)));
+            }
+        } else {
+            for (a, b) in front_pairs.iter() {
+                for p in [layout[*a], layout[*b]] {
+                    for neighbour in coupling_map.neighbours(p) {
+                        swap_candidates.push((p.min(neighbour), p.max(neighbour)));
+                    }
+                }
+            }
+        }
+        swap_candidates.sort();
+        swap_candidates.dedup();
+
+        // the extended set of upcoming two qubit gates, used to look ahead
+        let mut extended_pairs: Vec<(usize, usize)> = vec![];
+        let mut visited = vec![false; gates.len()];
+        let mut queue: VecDeque<usize> = front.iter().copied().collect();
+        while let Some(n) = queue.pop_front() {
+            if extended_pairs.len() >= EXTENDED_SET_SIZE {
+                break;
+            }
+            for successor in successors[n].iter() {
+                if !visited[*successor] {
+                    visited[*successor] = true;
+                    if let Some(pair) = two_qubits(&gates[*successor]) {
+                        extended_pairs.push(pair);
+                    }
+                    queue.push_back(*successor);
+                }
+            }
+        }
+
+        // scoring each candidate SWAP by the distances of the gates after it is applied
+        let score = |(p, q): (usize, usize)| {
+            let position = |l: usize| match layout[l] {
+                x if x == p => q,
+                x if x == q => p,
+                x => x,
+            };
+            let cost = |pairs: &[(usize, usize)]| match pairs.len() {
+                0 => 0.,
+                length => pairs.iter().map(|(a, b)| distances[position(*a)][position(*b)] as Real).sum::<Real>() / length as Real,
+            };
+            decay[p].max(decay[q]) * (cost(&front_pairs) + EXTENDED_SET_WEIGHT * cost(&extended_pairs))
+        };
+        let (p, q) = swap_candidates
+            .into_iter()
+            .min_by(|a, b| score(*a).total_cmp(&score(*b)))
+            .ok_or(BraKetError::Unsupported("routing between qubits which the coupling map does not connect"))?;
+
+        routed.push(Operations::SWAP(p, q));
+        let (l_p, l_q) = (logical[p], logical[q]);
+        layout.swap(l_p, l_q);
+        logical.swap(p, q);
+        number_of_swaps += 1;
+        swaps_without_progress += 1;
+        decay[p] += DECAY_INCREMENT;
+        decay[q] += DECAY_INCREMENT;
+        if number_of_swaps % 5 == 0 {
+            decay.iter_mut().for_each(|d| *d = 1.);
+        }
+    }
+    Ok((routed, layout, number_of_swaps))
+}
+
+/// Routes the program onto the coupling map with the SABRE algorithm, inserting SWAP gates so that every
+/// two qubit gate acts on coupled physical qubits. The initial layout is chosen by routing the program
+/// forwards and then backwards from the trivial layout. Subcircuits are expanded before routing. Fails if
+/// the program acts on more qubits than the coupling map has, or needs to bring together qubits which the
+/// coupling map does not connect.
+pub fn route(program: &Program, coupling_map: &CouplingMap) -> Result<Routing, BraKetError> {
+    let number_of_qubits = coupling_map.number_of_qubits;
+    if program.number_of_qubits() > number_of_qubits {
+        return Err(BraKetError::NotEnoughQubits { required: program.number_of_qubits(), available: number_of_qubits });
+    }
+    let distances: Vec<Vec<usize>> = coupling_map
+        .distances()
+        .into_iter()
+        .map(|row| row.into_iter().map(|d| d.unwrap_or(usize::MAX / 2)).collect())
+        .collect();
+
+    let trivial_layout: Vec<usize> = (0..number_of_qubits).collect();
+    let gates = flatten(&program.gates);
+    let reversed: Vec<Operations> = gates.iter().rev().cloned().collect();
+    let (_, forward_layout, _) = sabre_pass(&gates, coupling_map, &distances, trivial_layout)?;
+    let (_, initial_layout, _) = sabre_pass(&reversed, coupling_map, &distances, forward_layout)?;
+    let (gates, final_layout, number_of_swaps) = sabre_pass(&gates, coupling_map, &distances, initial_layout.clone())?;
+
+    let mut routed = Program::new();
+    routed.fusion = program.fusion;
+    routed.add_gates(gates);
+    Ok(Routing {
+        program: routed,
+        initial_layout,
+        final_layout,
+        number_of_swaps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::routing::*;
+
+    #[test]
+    fn test_constructors() {
+        let line = CouplingMap::line(5);
+        assert_eq!(line.edges.len(), 4);
+
+        let grid = CouplingMap::grid(3, 4);
+        assert_eq!(grid.number_of_qubits, 12);
+        assert_eq!(grid.edges.len(), 3 * 3 + 2 * 4);
+        assert!(grid.are_connected(5, 1));
+
+        // a single hexagon has six vertices and six edges
+        let hexagon = CouplingMap::heavy_hex(2, 3);
+        assert_eq!(hexagon.number_of_qubits, 12);
+        assert!((0..12).all(|n| hexagon.neighbours(n).len() == 2));
+    }
+
+    #[test]
+    fn test_distances() {
+        let distances = CouplingMap::line(4).distances();
+        assert_eq!(distances[0][3], Some(3));
+        let disconnected = CouplingMap::new(3, vec![(0, 1)]).unwrap().distances();
+        assert_eq!(disconnected[0][2], None);
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::BTreeMap;
+
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+use rand::rngs::StdRng;
+
+use crate::error::BraKetError;
+use crate::helper_functions::with_seeded_measurement_rng;
+use crate::noise::NoiseModel;
+use crate::operations::{which_qubits, Operations};
+use crate::program::Program;
+use crate::state_traits::StateTraits;
+use crate::subcircuit::flatten;
+use crate::types::Real;
+
+/// A measurement of a qubit, storing the outcome in a classical bit if one is given.
+type Measurement = (usize, Option<usize>);
+
+/// Splits the gates of a program into the gates before its measurements and the measurements at its end, as
+/// (qubit, classical bit) pairs. Fails if a qubit is acted on after it is measured, or is reset or measured
+/// in another basis, as the outcomes then cannot be sampled from a single final state.
+fn final_measurements(program: &Program, number_of_qubits: usize) -> Result<(Vec<Operations>, Vec<Measurement>), BraKetError> {
+    let unsupported = BraKetError::Unsupported("sampling programs which measure or reset qubits before their last gate");
+    let mut gates = vec![];
+    let mut measurements = vec![];
+    for gate in flatten(&program.gates) {
+        match gate {
+            Operations::Barrier => {}
+            Operations::Measure(qubit) => measurements.push((qubit, None)),
+            Operations::MeasureInto(qubit, bit) => measurements.push((qubit, Some(bit))),
+            Operations::MeasureAll => measurements.extend((0..number_of_qubits).map(|qubit| (qubit, None))),
+            Operations::Reset(_) | Operations::ResetAll | Operations::MeasureX(_) | Operations::MeasureY(_) => return Err(unsupported),
+            gate => {
+                if which_qubits(&gate).iter().any(|qubit| measurements.iter().any(|(measured, _)| measured == *qubit)) {
+                    return Err(unsupported);
+                }
+                gates.push(gate)
+            }
+        }
+    }
+    Ok((gates, measurements))
+}
+
+/// The gates without the measurements at their end, those of qubits which no later gate acts on.
+pub fn without_final_measurements(gates: &[Operations]) -> Vec<Operations> {
+    let mut acted_on: Vec<usize> = vec![];
+    let mut all_acted_on = false;
+    let mut kept = vec![];
+    for gate in gates.iter().rev() {
+        let final_measurement = match gate {
+            Operations::Measure(qubit) | Operations::MeasureInto(qubit, _) => !all_acted_on && !acted_on.contains(qubit),
+            Operations::MeasureAll => !all_acted_on && acted_on.is_empty(),
+            _ => false,
+        };
+        if final_measurement {
+            continue;
+        }
+        match gate {
+            Operations::Barrier => {}
+            Operations::MeasureAll | Operations::ResetAll => all_acted_on = true,
+            Operations::Subcircuit(_, qubits) => acted_on.extend(qubits),
+            _ => acted_on.extend(which_qubits(gate)),
+        }
+        kept.push(gate.clone());
+    }
+    kept.reverse();
+    kept
+}
+
+/// Runs the program on a copy of the state and samples the outcomes of its measurements shots times,
+/// counting how often each outcome occurs. The outcomes are labelled by the bitstrings of the classical
+/// bits if the program measures into any, and otherwise by those of the measured qubits in increasing order,
+/// or of every qubit of the state if the program measures none. Bitstrings are written with the endianness
+/// of the state.
+pub fn sample<T: StateTraits + Clone>(program: &Program, state: &T, shots: usize, seed: u64) -> Result<BTreeMap<String, usize>, BraKetError> {
+    sample_counts(program, state, shots, seed, None)
+}
+
+/// Samples the program as sample does, with the errors of the noise model after each gate and readout errors
+/// flipping the sampled bits. A density matrix is run once, as the errors act on it exactly, while a state
+/// vector is run once for each shot, each run sampling a trajectory of the noise.
+pub fn sample_with_noise<T: StateTraits + Clone>(
+    program: &Program,
+    state: &T,
+    shots: usize,
+    seed: u64,
+    model: &NoiseModel,
+) -> Result<BTreeMap<String, usize>, BraKetError> {
+    sample_counts(program, state, shots, seed, Some(model))
+}
+
+fn sample_counts<T: StateTraits + Clone>(
+    program: &Program,
+    state: &T,
+    shots: usize,
+    seed: u64,
+    model: Option<&NoiseModel>,
+) -> Result<BTreeMap<String, usize>, BraKetError> {
+    let number_of_qubits = state.number_of_qubits();
+    state.check_qubit_number(program.which_qubits())?;
+    program.check_classical_bits()?;
+    let (gates, measurements) = final_measurements(program, number_of_qubits)?;
+
+    // the bit of the label each measured qubit is written to
+    let outputs: Vec<(usize, usize)> = match measurements.iter().any(|(_, bit)| bit.is_some()) {
+        true => measurements.iter().filter_map(|(qubit, bit)| bit.map(|bit| (qubit.to_owned(), bit))).collect(),
+        false => {
+            let mut qubits: Vec<usize> = measurements.iter().map(|(qubit, _)| *qubit).collect();
+            qubits.sort();
+            qubits.dedup();
+            if qubits.is_empty() {
+                qubits = (0..number_of_qubits).collect();
+            }
+            qubits.into_iter().enumerate().map(|(bit, qubit)| (qubit, bit)).collect()
+        }
+    };
+    let width = outputs.iter().map(|(_, bit)| bit + 1).max().unwrap_or(0).max(program.number_of_classical_bits());
+    let mut qubits: Vec<usize> = outputs.iter().map(|(qubit, _)| *qubit).collect();
+    qubits.sort();
+    qubits.dedup();
+
+    let mut unitary = Program::new();
+    unitary.set_fusion(program.fusion);
+    unitary.add_gates(gates);
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut frequencies = vec![0; 1 << qubits.len()];
+    let mut final_state = state.clone();
+    match model {
+        None => {
+            unitary.run(&mut final_state)?;
+            let probabilities = final_state.marginal_probabilities(&qubits)?;
+            let distribution = WeightedIndex::new(probabilities.iter().map(|p| p.max(0.))).unwrap();
+            for _ in 0..shots {
+                frequencies[distribution.sample(&mut rng)] += 1;
+            }
+        }
+        // the trajectories of a state vector are drawn with the measurement generator, seeded for this call
+        // alone so that the generator of the caller is left as it was
+        Some(model) => with_seeded_measurement_rng(seed, || -> Result<(), BraKetError> {
+            let runs = if state.exact_channels() { 1.min(shots) } else { shots };
+            for run in 0..runs {
+                final_state = state.clone();
+                unitary.run_with_noise(&mut final_state, model)?;
+                let probabilities = final_state.marginal_probabilities(&qubits)?;
+                let distribution = WeightedIndex::new(probabilities.iter().map(|p| p.max(0.))).unwrap();
+                let run_shots = shots / runs + usize::from(run < shots % runs);
+                for _ in 0..run_shots {
+                    let mut outcome = distribution.sample(&mut rng);
+                    for (position, qubit) in qubits.iter().enumerate() {
+                        if let Some(flip) = model.readout_probabilities(qubit) {
+                            if rng.gen::<Real>() < flip[(outcome >> position) & 1] {
+                                outcome ^= 1 << position;
+                            }
+                        }
+                    }
+                    frequencies[outcome] += 1;
+                }
+            }
+            Ok(())
+        })?,
+    }
+
+    let mut counts = BTreeMap::new();
+    for (outcome, frequency) in frequencies.into_iter().enumerate().filter(|(_, frequency)| *frequency > 0) {
+        let value: usize = outputs
+            .iter()
+            .map(|(qubit, bit)| ((outcome >> qubits.iter().position(|q| q == qubit).unwrap()) & 1) << bit)
+            .fold(0, |value, bit| value | bit);
+        *counts.entry(final_state.endianness().bitstring(value, width)).or_insert(0) += frequency;
+    }
+    Ok(counts)
+}
@@ -0,0 +1,170 @@
+//! The serde representations of matrices and states, behind the serde feature. Complex numbers are written
+//! as [re, im] pairs and matrices as lists of rows, so that the JSON does not depend on how nalgebra stores
+//! them. See the README for the full schema.
+
+use serde::{Deserialize, Serialize};
+
+use crate::basis::Endianness;
+use crate::error::BraKetError;
+use crate::operations::Operations;
+use crate::program::Program;
+use crate::registers::MAX_REGISTER_SIZE;
+use crate::subcircuit::Subcircuit;
+use crate::types::*;
+use crate::{DensityMatrix, StateVector};
+
+fn to_pair(z: &Complex) -> (Real, Real) {
+    (z.re, z.im)
+}
+
+fn from_pair((re, im): (Real, Real)) -> Complex {
+    Complex::new(re, im)
+}
+
+/// Checks that found amplitudes or rows are the dimension of a state of number_of_qubits qubits. The number
+/// comes from untrusted input, so it is bounded before being shifted.
+fn check_dimension(number_of_qubits: usize, found: usize) -> Result<(), BraKetError> {
+    if number_of_qubits >= usize::BITS as usize {
+        return Err(BraKetError::Unsupported("states with as many qubits as bits in a usize"));
+    }
+    match 1 << number_of_qubits {
+        expected if expected == found => Ok(()),
+        expected => Err(BraKetError::WrongDimension { expected, found }),
+    }
+}
+
+/// Writes a square matrix of a gate as a list of rows of [re, im] pairs.
+pub mod matrix {
+    use nalgebra::SMatrix;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{from_pair, to_pair};
+    use crate::types::*;
+
+    pub fn serialize<S: Serializer, const D: usize>(u: &SMatrix<Complex, D, D>, serializer: S) -> Result<S::Ok, S::Error> {
+        let rows: Vec<Vec<(Real, Real)>> = u.row_iter().map(|row| row.iter().map(to_pair).collect()).collect();
+        rows.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, De: Deserializer<'de>, const D: usize>(deserializer: De) -> Result<SMatrix<Complex, D, D>, De::Error> {
+        let rows: Vec<Vec<(Real, Real)>> = Vec::deserialize(deserializer)?;
+        if rows.len() != D || rows.iter().any(|row| row.len() != D) {
+            return Err(De::Error::custom(format!("expected a {}x{} matrix", D, D)));
+        }
+        Ok(SMatrix::from_fn(|i, j| from_pair(rows[i][j])))
+    }
+}
+
+/// The serialized form of a state vector.
+#[derive(Serialize, Deserialize)]
+pub struct StateVectorData {
+    number_of_qubits: usize,
+    /// The amplitudes of the basis states by index, qubit k being bit k of the index.
+    amplitudes: Vec<(Real, Real)>,
+    #[serde(default)]
+    endianness: Endianness,
+    #[serde(default)]
+    classical_register: ClassicalBits,
+    #[serde(default)]
+    classical_bits: ClassicalBits,
+}
+
+impl From<StateVector> for StateVectorData {
+    fn from(state: StateVector) -> Self {
+        StateVectorData {
+            number_of_qubits: state.number_of_qubits,
+            amplitudes: state.state_vector.iter().map(to_pair).collect(),
+            endianness: state.endianness,
+            classical_register: state.classical_register,
+            classical_bits: state.classical_bits,
+        }
+    }
+}
+
+impl TryFrom<StateVectorData> for StateVector {
+    type Error = BraKetError;
+
+    fn try_from(data: StateVectorData) -> Result<Self, BraKetError> {
+        check_dimension(data.number_of_qubits, data.amplitudes.len())?;
+        let mut state = StateVector::try_from(CVector::from_iterator(data.amplitudes.len(), data.amplitudes.into_iter().map(from_pair)))?;
+        state.endianness = data.endianness;
+        match data.classical_register.len() {
+            0 => {}
+            n if n == state.number_of_qubits => state.classical_register = data.classical_register,
+            n => return Err(BraKetError::WrongDimension { expected: state.number_of_qubits, found: n }),
+        }
+        state.classical_bits = data.classical_bits;
+        Ok(state)
+    }
+}
+
+/// The serialized form of a density matrix.
+#[derive(Serialize, Deserialize)]
+pub struct DensityMatrixData {
+    number_of_qubits: usize,
+    /// The rows of the density matrix.
+    elements: Vec<Vec<(Real, Real)>>,
+    #[serde(default)]
+    endianness: Endianness,
+}
+
+impl From<DensityMatrix> for DensityMatrixData {
+    fn from(state: DensityMatrix) -> Self {
+        DensityMatrixData {
+            number_of_qubits: state.number_of_qubits,
+            elements: state.density_matrix.row_iter().map(|row| row.iter().map(to_pair).collect()).collect(),
+            endianness: state.endianness,
+        }
+    }
+}
+
+impl TryFrom<DensityMatrixData> for DensityMatrix {
+    type Error = BraKetError;
+
+    fn try_from(data: DensityMatrixData) -> Result<Self, BraKetError> {
+        let rows = data.elements.len();
+        check_dimension(data.number_of_qubits, rows)?;
+        if let Some(row) = data.elements.iter().find(|row| row.len() != rows) {
+            return Err(BraKetError::NotSquare { rows, columns: row.len() });
+        }
+        let matrix = CMatrix::from_fn(rows, rows, |i, j| from_pair(data.elements[i][j]));
+        let mut state = DensityMatrix::try_from(matrix)?;
+        state.endianness = data.endianness;
+        Ok(state)
+    }
+}
+
+/// The serialized form of a subcircuit, which is checked by Subcircuit::new when read.
+#[derive(Serialize, Deserialize)]
+pub struct SubcircuitData {
+    name: String,
+    number_of_qubits: usize,
+    gates: Vec<Operations>,
+}
+
+impl From<Subcircuit> for SubcircuitData {
+    fn from(subcircuit: Subcircuit) -> Self {
+        SubcircuitData { name: subcircuit.name, number_of_qubits: subcircuit.number_of_qubits, gates: subcircuit.gates }
+    }
+}
+
+impl TryFrom<SubcircuitData> for Subcircuit {
+    type Error = BraKetError;
+
+    fn try_from(data: SubcircuitData) -> Result<Self, BraKetError> {
+        if data.gates.iter().any(|gate| matches!(gate, Operations::MeasureAll | Operations::ResetAll)) {
+            return Err(BraKetError::Unsupported("measuring or resetting all qubits in a subcircuit"));
+        }
+        if data.number_of_qubits > MAX_REGISTER_SIZE {
+            return Err(BraKetError::RegisterTooLarge(data.number_of_qubits));
+        }
+        let mut program = Program::new();
+        program.quantum_register("q", data.number_of_qubits);
+        program.add_gates(data.gates);
+        if program.number_of_qubits() != data.number_of_qubits {
+            return Err(BraKetError::NotEnoughQubits { required: program.number_of_qubits(), available: data.number_of_qubits });
+        }
+        Ok(Subcircuit::new(&data.name, &program))
+    }
+}
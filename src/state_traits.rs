@@ -1,28 +1,60 @@
+use crate::basis::Endianness;
+use crate::error::BraKetError;
+use crate::helper_functions::marginalise;
 use crate::types::{Matrix2x2, Matrix4x4};
-use crate::types::Real;
+use crate::types::{Real, RVector};
 
 /// The traits every quantum state must possess to permit it being acted upon by a quantum program.
 /// In our case both the StateVector and DensityMatrix implement these traits.
 pub trait StateTraits {
+    /// The number of qubits in the state.
+    fn number_of_qubits(&self) -> usize;
+    /// The order in which qubits are written in the labels of basis states.
+    fn endianness(&self) -> Endianness;
     /// Checks the qubit number to make sure it is compatible with the quantum program.
-    fn check_qubit_number(&self, qubits: Vec<&usize>);
+    fn check_qubit_number(&self, qubits: Vec<&usize>) -> Result<(), BraKetError>;
     /// Reinitialises all qubits in their ground state.
     fn reinitialise_all(&mut self);
     /// Sets the every element of the state vector or density matrix to 0. + 0.i. (mainly a convenience function)
     fn zero(&mut self);
     /// Measures the target qubit, collapsing its quantum state.
     fn measure(&mut self, target: &usize);
+    /// Resets the target qubit to |0>, leaving the rest of the state as it would be after measuring it.
+    fn reset(&mut self, target: &usize);
+    /// Measures the target qubit, storing the outcome in a classical bit. Returns an error if the state
+    /// has no classical bits to store it in.
+    fn measure_into(&mut self, target: &usize, bit: &usize) -> Result<(), BraKetError>;
     /// Measures all the qubits, collapsing the quantum state completely.
     fn measure_all(&mut self);
     /// Performs a single qubit unitary gate on the target qubit.
     fn single_qubit_gate(&mut self, target: &usize, u: &Matrix2x2);
-    /// Performs a single qubit kraus operation on the target qubit.
-    fn single_qubit_kraus(&mut self, target: &usize, u: &Matrix2x2);
+    /// Applies the channel with the given kraus operators to the target qubit. A density matrix becomes the
+    /// sum of K rho K^dagger exactly, while a state vector becomes K |psi> for one of the operators, normalised,
+    /// chosen with probability |K |psi>|^2, so that averaging over runs gives the channel.
+    fn single_qubit_kraus(&mut self, target: &usize, kraus: &[Matrix2x2]) -> Result<(), BraKetError>;
     /// Performs a two qubit gate on the target and control qubits.
     fn two_qubit_gate(&mut self, target: &usize, control: &usize, u: &Matrix4x4);
+    /// Applies the channel with the given kraus operators to the target and control qubits, in the same way
+    /// as single_qubit_kraus.
+    fn two_qubit_kraus(&mut self, target: &usize, control: &usize, kraus: &[Matrix4x4]) -> Result<(), BraKetError>;
+    /// Whether channels act on the state exactly, as on a density matrix, rather than by sampling one of their
+    /// kraus operators, as on a state vector.
+    fn exact_channels(&self) -> bool;
+    /// Flips the recorded outcome of the last measurement of the target qubit, and of the classical bit it
+    /// was stored in if any, with probability flip[outcome], as a readout error does. Density matrices do not
+    /// record outcomes, so are left unchanged.
+    fn readout_error(&mut self, target: &usize, bit: Option<&usize>, flip: &[Real; 2]);
     /// calculates the probability of measuring |0> in the target qubit
     fn get_probability(&self, target: &usize) -> Real;
     fn get_expectation(&self, target: &usize) -> Real;
+    /// The probability of measuring each basis state, without collapsing the state. Qubit k is bit k of the
+    /// index of the basis state.
+    fn probabilities(&self) -> RVector;
+    /// The probability of measuring each state of the given qubits, without collapsing the state. Qubit
+    /// qubits[i] is bit i of the index.
+    fn marginal_probabilities(&self, qubits: &[usize]) -> Result<RVector, BraKetError> {
+        marginalise(&self.probabilities(), qubits)
+    }
 }
 
 
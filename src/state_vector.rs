@@ -1,81 +1,102 @@
 use crate::state_traits::StateTraits;
 use crate::types::*;
-use nalgebra::ComplexField;
+use crate::basis::{BasisLabel, Endianness};
+use crate::checkpoint::{self, Kind, Precision};
+use crate::error::BraKetError;
+use nalgebra::{ComplexField, Vector2, Vector4};
 use rayon::prelude::*;
 use std::mem::size_of_val;
 use std::fmt;
+use std::path::Path;
 
 use log::debug;
 
 use crate::helper_functions::*;
+use crate::registers::{ClassicalRegister, MAX_CLASSICAL_BITS};
+use crate::DensityMatrix;
 
 use rand::distributions::WeightedIndex;
 use rand::prelude::*;
+use rand::rngs::StdRng;
 
 
 /// A state vector describing a pure quantum state.
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "crate::serialization::StateVectorData", try_from = "crate::serialization::StateVectorData"))]
 pub struct StateVector {
     /// The number of qubits in the quantum state.
     pub number_of_qubits: usize,
     /// The actual state_vector describing a quantum state.
     pub state_vector: CVector,
     /// A classical register where the outcome of measurements are stored.
-    pub classical_register: ClassicalRegister,
+    pub classical_register: ClassicalBits,
+    /// The classical bits measurements can be stored in, which grow as bits are written to.
+    pub classical_bits: ClassicalBits,
+    /// The order of the qubits in the labels of basis states and the values of classical registers.
+    pub endianness: Endianness,
     state_vector_pointer: StateVectorPointer<Complex>,
 }
 
 impl fmt::Display for StateVector {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "state vector:{}classical register \n{:?}\n",self.state_vector, self.classical_register)
+        writeln!(f, "state vector:")?;
+        for (label, amplitude) in self.amplitudes() {
+            writeln!(f, "|{}> {}", label, amplitude)?;
+        }
+        write!(f, "classical register \n{:?}\n", self.classical_register)
     }
 }
 
 impl Clone for StateVector {
     fn clone(&self) -> Self {
-        StateVector::from(self.state_vector.clone())
+        let mut state_vector = StateVector::from_unchecked(self.state_vector.clone());
+        state_vector.endianness = self.endianness;
+        state_vector.classical_register = self.classical_register.clone();
+        state_vector.classical_bits = self.classical_bits.clone();
+        state_vector
     }
 }
 
-impl From<CVector> for StateVector {
-    /// Create a stave vector from a complex vector
-    fn from(mut state_vector: CVector) -> Self {
-        let shape = state_vector.shape();
-        let number_of_qubits = log2(shape.0 as usize);
-
-        let state_vector_pointer = StateVectorPointer::new(&mut state_vector[0], shape.0);
-        let classical_register = vec![None; number_of_qubits];
+impl TryFrom<CVector> for StateVector {
+    type Error = BraKetError;
 
-        StateVector {
-            number_of_qubits,
-            state_vector,
-            state_vector_pointer,
-            classical_register,
+    /// Create a stave vector from a complex vector, which must be normalised with a power of two length.
+    fn try_from(state_vector: CVector) -> Result<Self, BraKetError> {
+        let length = state_vector.len();
+        if !length.is_power_of_two() {
+            return Err(BraKetError::NotPowerOfTwo(length));
+        }
+        let norm = state_vector.norm_squared();
+        if (norm - 1.).abs() > COMPARISON_PRECISION {
+            return Err(BraKetError::NotNormalised(norm));
         }
+        Ok(StateVector::from_unchecked(state_vector))
     }
 }
 
 impl StateTraits for StateVector {
 
-    /// Checks that the number of qubits required by a program is compatible with the number of qubits in the state vector.
-    fn check_qubit_number(&self, qubits: Vec<&usize>) {
-        let required_number_of_qubits = match qubits.last() {
-            Some(n) => n.to_owned(),
-            None => &0 // no gates in gate_list
-        };
+    /// The number of qubits in the state vector.
+    fn number_of_qubits(&self) -> usize {
+        self.number_of_qubits
+    }
+
+    fn endianness(&self) -> Endianness {
+        self.endianness
+    }
 
-        let number_of_qubits = &self.number_of_qubits;
-        assert!(required_number_of_qubits < number_of_qubits,
-                "fewer qubits in the state vector than required by program {} < {}",
-                required_number_of_qubits, number_of_qubits
-        )
+    /// Checks that the number of qubits required by a program is compatible with the number of qubits in the state vector.
+    fn check_qubit_number(&self, qubits: Vec<&usize>) -> Result<(), BraKetError> {
+        check_qubit_number(qubits, self.number_of_qubits)
     }
 
     /// Reinitialize all qubits in their ground state.
     fn reinitialise_all(&mut self) {
         self.zero();
         self.state_vector[0] = Complex::new(1., 0.);
-        self.classical_register = vec![None; self.number_of_qubits]
+        self.classical_register = vec![None; self.number_of_qubits];
+        self.classical_bits = vec![];
     }
 
     /// Sets the every element of the state vector to 0. + 0.i
@@ -89,6 +110,41 @@ impl StateTraits for StateVector {
         }
     }
 
+    /// Applies one of the kraus operators, chosen with the probability of its outcome.
+    fn two_qubit_kraus(&mut self, target: &usize, control: &usize, kraus: &[Matrix4x4]) -> Result<(), BraKetError> {
+        check_kraus(kraus)?;
+        let swap = |x| swap_two_pairs(x, target, control);
+        let probabilities: Vec<Real> = kraus
+            .iter()
+            .map(|k| {
+                (0..1 << self.number_of_qubits)
+                    .into_par_iter()
+                    .step_by(4)
+                    .map(|n: usize| unsafe { (k * Vector4::from_fn(|i, _| self.read(swap(n + i)))).norm_squared() })
+                    .sum()
+            })
+            .collect();
+        let dist = WeightedIndex::new(&probabilities).unwrap();
+        let chosen = with_measurement_rng(|rng| dist.sample(rng));
+        self.two_qubit_gate(target, control, &kraus[chosen].unscale(probabilities[chosen].sqrt()));
+        Ok(())
+    }
+
+    fn exact_channels(&self) -> bool {
+        false
+    }
+
+    fn readout_error(&mut self, target: &usize, bit: Option<&usize>, flip: &[Real; 2]) {
+        if let Some(outcome) = self.classical_register[*target] {
+            if with_measurement_rng(|rng| rng.gen::<Real>() < flip[outcome as usize]) {
+                self.classical_register[*target] = Some(!outcome);
+                if let Some(bit) = bit {
+                    self.classical_bits[*bit] = Some(!outcome);
+                }
+            }
+        }
+    }
+
     fn get_probability(self: &StateVector, target: &usize) -> Real {
         let swap = |x| swap_pair(x, target);
         (0..1 << self.number_of_qubits)
@@ -103,6 +159,14 @@ impl StateTraits for StateVector {
         p - (1. - p)
     }
 
+    fn probabilities(&self) -> RVector {
+        let probabilities: Vec<Real> = (0..1 << self.number_of_qubits)
+            .into_par_iter()
+            .map(|n: usize| unsafe { self.read(n).modulus_squared() })
+            .collect();
+        RVector::from_vec(probabilities)
+    }
+
     /// Measures the target qubit
     fn measure(&mut self, target: &usize) {
         debug!("state vector before: \n{}", self.state_vector);
@@ -110,10 +174,9 @@ impl StateTraits for StateVector {
 
         let p0 = self.get_probability(target);
         // sampling randomly from the qubit probability distribution
-        let mut rng = thread_rng();
         let probabilities = [p0, 1. - p0];
-        let dist = WeightedIndex::new(&probabilities).unwrap();
-        let qubit_state = dist.sample(&mut rng);
+        let dist = WeightedIndex::new(probabilities).unwrap();
+        let qubit_state = with_measurement_rng(|rng| dist.sample(rng));
 
         // updating the classical register
         self.classical_register[target.to_owned()] = Some(qubit_state == 1);
@@ -129,7 +192,7 @@ impl StateTraits for StateVector {
                 let i1 = swap(n + 1);
                 match qubit_state {
                     0 => { // qubit in state |0>
-                        let s0 = self.read(i1);
+                        let s0 = self.read(i0);
                         self.write(i0, s0 / p_sqrt);
                         self.write(i1, Complex::new(0., 0.))
                     }
@@ -141,6 +204,44 @@ impl StateTraits for StateVector {
                 }
             })
     }
+    /// Resets the target qubit by measuring it and moving the surviving amplitudes onto |0>
+    fn reset(&mut self, target: &usize) {
+        let swap = |x| swap_pair(x, target);
+
+        let p0 = self.get_probability(target);
+        let probabilities = [p0, 1. - p0];
+        let dist = WeightedIndex::new(probabilities).unwrap();
+        let qubit_state = with_measurement_rng(|rng| dist.sample(rng));
+
+        let p_sqrt = probabilities[qubit_state].sqrt();
+        (0..1 << self.number_of_qubits)
+            .into_par_iter()
+            .step_by(2)
+            .for_each(|n: usize| unsafe {
+                let i0 = swap(n);
+                let i1 = swap(n + 1);
+                let s = match qubit_state {
+                    0 => self.read(i0),
+                    _ => self.read(i1),
+                };
+                self.write(i0, s / p_sqrt);
+                self.write(i1, Complex::new(0., 0.));
+            })
+    }
+
+    /// Measures the target qubit, storing the outcome in a classical bit as well as the classical register
+    fn measure_into(&mut self, target: &usize, bit: &usize) -> Result<(), BraKetError> {
+        if *bit >= MAX_CLASSICAL_BITS {
+            return Err(BraKetError::InvalidBit(*bit));
+        }
+        self.measure(target);
+        if self.classical_bits.len() <= *bit {
+            self.classical_bits.resize(bit + 1, None);
+        }
+        self.classical_bits[*bit] = self.classical_register[*target];
+        Ok(())
+    }
+
     /// Measures all qubits
     fn measure_all(&mut self) {
 
@@ -152,8 +253,7 @@ impl StateTraits for StateVector {
 
         // sampling from the probability distribution
         let dist = WeightedIndex::new(&probabilities).unwrap();
-        let mut rng = thread_rng();
-        let s = dist.sample(&mut rng);
+        let s = with_measurement_rng(|rng| dist.sample(rng));
 
         // updating the classical register
         for n in 0..self.number_of_qubits {
@@ -187,8 +287,24 @@ impl StateTraits for StateVector {
         }
     }
 
-    fn single_qubit_kraus(&mut self, _target: &usize, _u: &Matrix2x2) {
-        panic!("Kraus operators cannot be performed on state vectors");
+    /// Applies one of the kraus operators, chosen with the probability of its outcome.
+    fn single_qubit_kraus(&mut self, target: &usize, kraus: &[Matrix2x2]) -> Result<(), BraKetError> {
+        check_kraus(kraus)?;
+        let swap = |x| swap_pair(x, target);
+        let probabilities: Vec<Real> = kraus
+            .iter()
+            .map(|k| {
+                (0..1 << self.number_of_qubits)
+                    .into_par_iter()
+                    .step_by(2)
+                    .map(|n: usize| unsafe { (k * Vector2::new(self.read(swap(n)), self.read(swap(n + 1)))).norm_squared() })
+                    .sum()
+            })
+            .collect();
+        let dist = WeightedIndex::new(&probabilities).unwrap();
+        let chosen = with_measurement_rng(|rng| dist.sample(rng));
+        self.single_qubit_gate(target, &kraus[chosen].unscale(probabilities[chosen].sqrt()));
+        Ok(())
     }
 
     /// Performs a two qubit gate
@@ -226,12 +342,12 @@ impl StateTraits for StateVector {
 impl PartialEq for StateVector {
     fn eq(&self, other: &Self) -> bool {
         let mut result = false;
-        if self.number_of_qubits == other.number_of_qubits {
-            if self.state_vector.shape() == other.state_vector.shape() {
-                let difference = &self.state_vector - &other.state_vector;
-                if difference.iter().all(|d| d.abs() < COMPARISON_PRECISION) {
-                    result = true;
-                }
+        if self.number_of_qubits == other.number_of_qubits
+            && self.state_vector.shape() == other.state_vector.shape()
+        {
+            let difference = &self.state_vector - &other.state_vector;
+            if difference.iter().all(|d| d.abs() < COMPARISON_PRECISION) {
+                result = true;
             }
         }
         result
@@ -241,28 +357,35 @@ impl PartialEq for StateVector {
 
 
 impl StateVector {
+    /// The state |00...0> of number_of_qubits qubits.
+    ///
+    /// # Panics
+    /// If the state is too large to be allocated, see try_new.
     pub fn new(number_of_qubits: usize) -> StateVector {
-        let hilbert_dim = 1 << number_of_qubits;
-        let mut state_vector = {
-            // calculating the hilbert dim
-            // printing the size of the density matrix to be created
-            {
-                let state_vector_footprint = (hilbert_dim << 2) * size_of_val(&Complex::new(0., 0.));
-                let bytes_to_gigabyte: usize = 2 << 33;
-                debug!(
-                    "Allocating density matrix of size: {:.4} Gb",
-                    (state_vector_footprint as f32) / (bytes_to_gigabyte as f32)
-                );
-            }
+        StateVector::try_new(number_of_qubits).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// The state |00...0> of number_of_qubits qubits, failing if there is not enough memory for its
+    /// 2^number_of_qubits amplitudes.
+    pub fn try_new(number_of_qubits: usize) -> Result<StateVector, BraKetError> {
+        let hilbert_dim = 1usize.checked_shl(number_of_qubits.try_into().unwrap_or(u32::MAX));
+        // printing the size of the state vector to be created
+        if let Some(hilbert_dim) = hilbert_dim {
+            let state_vector_footprint = hilbert_dim as f32 * size_of_val(&Complex::new(0., 0.)) as f32;
+            debug!("Allocating state vector of size: {:.4} Gb", state_vector_footprint / (1u64 << 30) as f32);
+        }
+        let mut state_vector = CVector::from_vec(try_zeros(hilbert_dim, number_of_qubits)?);
+        // setting the first element to 1 to represent initialisation in the |000...> state
+        state_vector[0] = Complex::new(1., 0.);
+        Ok(StateVector::from_unchecked(state_vector))
+    }
 
-            // creating the density matrix
-            let mut state_vector = CVector::from_element(hilbert_dim, Complex::new(0., 0.));
-            // setting the (0, 0) element to 1 to represent initialisation in the |000...> state
-            state_vector[0] = Complex::new(1., 0.);
-            state_vector
-        };
-        let state_vector_pointer = StateVectorPointer::new(&mut state_vector[0], hilbert_dim);
+    /// A state vector wrapping the vector as it is.
+    fn from_unchecked(mut state_vector: CVector) -> StateVector {
+        let length = state_vector.len();
+        let number_of_qubits = log2(length);
 
+        let state_vector_pointer = StateVectorPointer::new(&mut state_vector[0], length);
         let classical_register = vec![None; number_of_qubits];
 
         StateVector {
@@ -270,33 +393,192 @@ impl StateVector {
             state_vector,
             state_vector_pointer,
             classical_register,
+            classical_bits: vec![],
+            endianness: Endianness::default(),
+        }
+    }
+
+    /// The computational basis state with the given index, qubit k being bit k of the index.
+    pub fn basis(number_of_qubits: usize, index: usize) -> Result<StateVector, BraKetError> {
+        if number_of_qubits >= usize::BITS as usize || index >= 1 << number_of_qubits {
+            return Err(BraKetError::InvalidBasisLabel { label: index.to_string(), number_of_qubits });
+        }
+        let mut state_vector = StateVector::new(number_of_qubits);
+        state_vector.state_vector.swap_rows(0, index);
+        Ok(state_vector)
+    }
+
+    /// The computational basis state written as a little endian bitstring, the rightmost character being
+    /// qubit 0.
+    pub fn from_bitstring(bitstring: &str) -> Result<StateVector, BraKetError> {
+        StateVector::basis(bitstring.len(), bitstring.index(bitstring.len(), Endianness::Little)?)
+    }
+
+    /// The product state with qubit k at the point (theta, phi) of the Bloch sphere given by angles[k], that
+    /// is in the state cos(theta / 2) |0> + e^(i phi) sin(theta / 2) |1>.
+    pub fn product(angles: &[(Angle, Angle)]) -> StateVector {
+        let number_of_qubits = angles.len();
+        let state_vector = CVector::from_fn(1 << number_of_qubits, |index, _| {
+            angles.iter().enumerate().fold(Complex::new(1., 0.), |amplitude, (qubit, (theta, phi))| {
+                match (index >> qubit) & 1 {
+                    0 => amplitude * (theta / 2.).cos(),
+                    _ => amplitude * Complex::new(phi.cos(), phi.sin()) * (theta / 2.).sin(),
+                }
+            })
+        });
+        StateVector::from_unchecked(state_vector)
+    }
+
+    /// A pure state drawn from the Haar measure, reproducibly for a given seed.
+    pub fn random(number_of_qubits: usize, seed: u64) -> StateVector {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let state_vector = CVector::from_fn(1 << number_of_qubits, |_, _| complex_gaussian(&mut rng));
+        StateVector::from_unchecked(state_vector.normalize())
+    }
+
+    /// The tensor product of the two states, the qubits of other following those of self as in
+    /// Program::tensor.
+    pub fn tensor(&self, other: &StateVector) -> StateVector {
+        let mut state_vector = StateVector::from_unchecked(other.state_vector.kronecker(&self.state_vector));
+        state_vector.endianness = self.endianness;
+        state_vector
+    }
+
+    /// Saves the state to a binary checkpoint file, exactly, see save_with_precision.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        self.save_with_precision(path, Precision::Double)
+    }
+
+    /// Saves the state to a binary checkpoint file with a header holding the number of qubits, the
+    /// endianness, the precision and a checksum of the amplitudes. The classical registers are not saved.
+    pub fn save_with_precision<P: AsRef<Path>>(&self, path: P, precision: Precision) -> Result<(), BraKetError> {
+        let amplitudes = self.state_vector.iter().cloned();
+        checkpoint::save(path.as_ref(), Kind::StateVector, self.number_of_qubits, self.endianness, precision, amplitudes)
+    }
+
+    /// Loads a state saved with save, checking the checksum and that the state is normalised.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<StateVector, BraKetError> {
+        let (_, endianness, amplitudes) = checkpoint::load(path.as_ref(), Kind::StateVector)?;
+        let mut state_vector = StateVector::try_from(CVector::from_vec(amplitudes))?;
+        state_vector.endianness = endianness;
+        Ok(state_vector)
+    }
+
+    /// Saves the amplitudes as a one dimensional complex128 NumPy array, indexed with qubit k as bit k.
+    pub fn save_npy<P: AsRef<Path>>(&self, path: P) -> Result<(), BraKetError> {
+        checkpoint::save_npy(path.as_ref(), &[self.state_vector.len()], self.state_vector.iter().cloned())
+    }
+
+    /// Loads the amplitudes from a one dimensional complex NumPy array, indexed with qubit k as bit k.
+    pub fn load_npy<P: AsRef<Path>>(path: P) -> Result<StateVector, BraKetError> {
+        let (shape, amplitudes) = checkpoint::load_npy(path.as_ref())?;
+        if shape.len() != 1 {
+            return Err(BraKetError::InvalidFile(format!("expected a vector but found an array of shape {:?}", shape)));
         }
+        StateVector::try_from(CVector::from_vec(amplitudes))
+    }
+
+    /// Sets the order of the qubits in the labels of basis states and the values of classical registers.
+    pub fn set_endianness(&mut self, endianness: Endianness) {
+        self.endianness = endianness;
+    }
+
+    /// The amplitude of a basis state, labelled by a bitstring or an integer in the endianness of the state.
+    pub fn amplitude<L: BasisLabel>(&self, label: L) -> Result<Complex, BraKetError> {
+        Ok(self.state_vector[label.index(self.number_of_qubits, self.endianness)?])
+    }
+
+    /// The basis states with a nonzero amplitude, as bitstrings in the endianness of the state, with their
+    /// amplitudes.
+    pub fn amplitudes(&self) -> impl Iterator<Item = (String, Complex)> + '_ {
+        self.state_vector
+            .iter()
+            .enumerate()
+            .filter(|(_, amplitude)| amplitude.norm_sqr() > COMPARISON_PRECISION * COMPARISON_PRECISION)
+            .map(|(index, amplitude)| (self.endianness.bitstring(index, self.number_of_qubits), *amplitude))
     }
 
-    pub fn get_measured_qubit_state(&self, target: usize) -> bool {
-        match self.classical_register[target] {
-            Some(qubit_state) => qubit_state,
-            None => panic!("qubit {} not measured yet", target)
+    pub fn get_measured_qubit_state(&self, target: usize) -> Result<bool, BraKetError> {
+        match self.classical_register.get(target) {
+            Some(Some(qubit_state)) => Ok(*qubit_state),
+            Some(None) => Err(BraKetError::QubitNotMeasured(target)),
+            None => Err(BraKetError::InvalidQubit { qubit: target, number_of_qubits: self.number_of_qubits }),
         }
     }
 
-    pub fn get_measured_overall_state(&self) -> Int {
+    /// The outcomes of measuring every qubit as an integer label in the endianness of the state.
+    pub fn get_measured_overall_state(&self) -> Result<Int, BraKetError> {
         let mut overall_state = 0;
         for qubit in 0..self.number_of_qubits {
-            let qubit_state = self.get_measured_qubit_state(qubit);
+            let qubit_state = self.get_measured_qubit_state(qubit)?;
             overall_state += (1 & (qubit_state as Int)) << qubit;
         }
-        overall_state
+        Ok(self.endianness.convert(overall_state as usize, self.number_of_qubits) as Int)
+    }
+
+    /// The outcome stored in a classical bit.
+    pub fn get_classical_bit(&self, bit: usize) -> Result<bool, BraKetError> {
+        match self.classical_bits.get(bit) {
+            Some(Some(bit_state)) => Ok(*bit_state),
+            _ => Err(BraKetError::BitNotMeasured(bit)),
+        }
+    }
+
+    /// The value stored in a classical register, with its first bit the least significant for little
+    /// endian states and the most significant for big endian ones.
+    pub fn get_measured_register(&self, register: &ClassicalRegister) -> Result<Int, BraKetError> {
+        let mut value = 0;
+        for (n, bit) in register.iter().enumerate() {
+            value += (self.get_classical_bit(*bit)? as Int) << n;
+        }
+        Ok(self.endianness.convert(value as usize, register.len()) as Int)
+    }
+
+    /// The density matrix of the given qubits once the rest are traced out, qubits[i] becoming qubit i of
+    /// the reduced state.
+    pub fn reduced_density_matrix(&self, qubits: &[usize]) -> Result<DensityMatrix, BraKetError> {
+        check_qubits(qubits, self.number_of_qubits)?;
+        let environment: Vec<usize> = (0..self.number_of_qubits).filter(|qubit| !qubits.contains(qubit)).collect();
+        let kept_indices = scattered_indices(qubits);
+        let environment_indices = scattered_indices(&environment);
+
+        let rows: Vec<Vec<Complex>> = kept_indices
+            .par_iter()
+            .map(|j| {
+                kept_indices
+                    .iter()
+                    .map(|k| {
+                        environment_indices
+                            .iter()
+                            .map(|e| unsafe { self.read(j | e) * self.read(k | e).conj() })
+                            .sum()
+                    })
+                    .collect()
+            })
+            .collect();
+        let dim = kept_indices.len();
+        let mut density_matrix = DensityMatrix::from_unchecked(CMatrix::from_fn(dim, dim, |j, k| rows[j][k]));
+        density_matrix.endianness = self.endianness;
+        Ok(density_matrix)
     }
 
     pub fn reset_classical_register(&mut self) {
         self.classical_register = vec![None; self.number_of_qubits];
+        self.classical_bits = vec![];
     }
 
+    /// Writes an element of the state vector through the shared pointer.
+    ///
+    /// # Safety
+    /// No other thread may access the same index concurrently.
     pub unsafe fn write(&self, index: usize, value: Complex) {
         self.state_vector_pointer.write(index, value);
     }
 
+    /// Reads an element of the state vector through the shared pointer.
+    ///
+    /// # Safety
+    /// No other thread may write to the same index concurrently.
     pub unsafe fn read(&self, index: usize) -> Complex {
         self.state_vector_pointer.read(index)
     }
@@ -0,0 +1,51 @@
+use crate::operations::*;
+use crate::program::Program;
+
+/// A named block of gates, defined once on its own qubits 0..number_of_qubits and inserted into programs
+/// as a single Operations::Subcircuit node. The gates are only expanded onto the qubits the subcircuit is
+/// placed on when it is run.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(into = "crate::serialization::SubcircuitData", try_from = "crate::serialization::SubcircuitData"))]
+pub struct Subcircuit {
+    pub name: String,
+    pub number_of_qubits: usize,
+    pub gates: Vec<Operations>,
+}
+
+impl Subcircuit {
+    /// Defines a subcircuit from the gates of a program.
+    ///
+    /// # Panics
+    /// If the program measures or resets all qubits, which has no meaning on a part of the register.
+    pub fn new(name: &str, program: &Program) -> Subcircuit {
+        assert!(
+            program.gates.iter().all(|gate| !matches!(gate, Operations::MeasureAll | Operations::ResetAll)),
+            "a subcircuit cannot measure or reset all qubits"
+        );
+        Subcircuit { name: name.to_owned(), number_of_qubits: program.number_of_qubits(), gates: program.gates.clone() }
+    }
+
+    /// The gates of the subcircuit placed on the given qubits, with nested subcircuits left as they are.
+    pub fn expand(&self, qubits: &[usize]) -> Vec<Operations> {
+        self.gates.iter().map(|gate| map_qubits(gate, |qubit| qubits[qubit])).collect()
+    }
+
+    /// The inverse of the subcircuit, or None if it contains measurements.
+    pub fn inverse(&self) -> Option<Subcircuit> {
+        let gates: Option<Vec<Operations>> = self.gates.iter().rev().map(dagger).collect();
+        Some(Subcircuit { name: format!("{}_dg", self.name), number_of_qubits: self.number_of_qubits, gates: gates? })
+    }
+}
+
+/// Recursively replaces every subcircuit by its gates.
+pub fn flatten(gates: &[Operations]) -> Vec<Operations> {
+    let mut flattened = vec![];
+    for gate in gates.iter() {
+        match gate {
+            Operations::Subcircuit(subcircuit, qubits) => flattened.append(&mut flatten(&subcircuit.expand(qubits))),
+            _ => flattened.push(gate.to_owned()),
+        }
+    }
+    flattened
+}
@@ -0,0 +1,159 @@
+use crate::draw::{gate_label, Element, Layout, Symbol};
+use crate::program::Program;
+
+/// The distance between two wires.
+const ROW: f64 = 40.;
+/// The margin around the circuit.
+const MARGIN: f64 = 20.;
+/// The width of a character of the monospace font.
+const CHARACTER: f64 = 8.;
+/// The height of a gate box.
+const BOX: f64 = 28.;
+
+const STYLE: &str = "\
+line, path, rect, circle { stroke: black; stroke-width: 1.5; fill: none; }
+rect, circle.target { fill: white; }
+circle.control { fill: black; }
+line.classical { stroke-width: 1; }
+line.barrier { stroke: grey; stroke-dasharray: 4 3; }
+rect.barrier { fill: lightgrey; fill-opacity: 0.5; stroke: none; }
+text { font-family: monospace; font-size: 13px; text-anchor: middle; dominant-baseline: central; }
+text.label { text-anchor: end; }
+text.small { font-size: 10px; }
+";
+
+/// Escapes the characters of text which are special in XML.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn text_width(text: &str) -> f64 {
+    text.chars().count() as f64 * CHARACTER
+}
+
+/// The width a symbol takes up on its wire.
+fn symbol_width(symbol: &Symbol) -> f64 {
+    match symbol {
+        Symbol::Control => 10.,
+        Symbol::Target => 20.,
+        Symbol::Swap => 12.,
+        Symbol::Barrier => 12.,
+        Symbol::Measure(..) => BOX,
+        Symbol::Reset => text_width("|0⟩") + 12.,
+        Symbol::Gate { name, angles, index } => (text_width(&gate_label(name, angles, *index, "π")) + 12.).max(BOX),
+    }
+}
+
+fn line(svg: &mut Vec<String>, (x1, y1): (f64, f64), (x2, y2): (f64, f64), class: Option<&str>) {
+    let class = class.map(|class| format!(" class=\"{}\"", class)).unwrap_or_default();
+    svg.push(format!("<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"{}/>", x1, y1, x2, y2, class));
+}
+
+/// A classical wire, drawn as two thin lines.
+fn double_line(svg: &mut Vec<String>, (x1, y1): (f64, f64), (x2, y2): (f64, f64)) {
+    let (dx, dy) = if y1 == y2 { (0., 1.5) } else { (1.5, 0.) };
+    line(svg, (x1 - dx, y1 - dy), (x2 - dx, y2 - dy), Some("classical"));
+    line(svg, (x1 + dx, y1 + dy), (x2 + dx, y2 + dy), Some("classical"));
+}
+
+fn text(svg: &mut Vec<String>, (x, y): (f64, f64), content: &str, class: Option<&str>) {
+    let class = class.map(|class| format!(" class=\"{}\"", class)).unwrap_or_default();
+    svg.push(format!("<text x=\"{}\" y=\"{}\"{}>{}</text>", x, y, class, escape(content)));
+}
+
+/// A box of the given width centred on a point, with text in it.
+fn labelled_box(svg: &mut Vec<String>, (x, y): (f64, f64), width: f64, label: &str) {
+    svg.push(format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>", x - width / 2., y - BOX / 2., width, BOX));
+    text(svg, (x, y), label, None);
+}
+
+fn draw_symbol(svg: &mut Vec<String>, symbol: &Symbol, (x, y): (f64, f64)) {
+    match symbol {
+        Symbol::Control => svg.push(format!("<circle class=\"control\" cx=\"{}\" cy=\"{}\" r=\"5\"/>", x, y)),
+        Symbol::Target => {
+            svg.push(format!("<circle class=\"target\" cx=\"{}\" cy=\"{}\" r=\"10\"/>", x, y));
+            line(svg, (x - 10., y), (x + 10., y), None);
+            line(svg, (x, y - 10.), (x, y + 10.), None);
+        }
+        Symbol::Swap => {
+            line(svg, (x - 6., y - 6.), (x + 6., y + 6.), None);
+            line(svg, (x - 6., y + 6.), (x + 6., y - 6.), None);
+        }
+        Symbol::Barrier => {
+            svg.push(format!("<rect class=\"barrier\" x=\"{}\" y=\"{}\" width=\"12\" height=\"{}\"/>", x - 6., y - ROW / 2., ROW));
+            line(svg, (x, y - ROW / 2.), (x, y + ROW / 2.), Some("barrier"));
+        }
+        Symbol::Reset => labelled_box(svg, (x, y), symbol_width(symbol), "|0⟩"),
+        Symbol::Gate { name, angles, index } => labelled_box(svg, (x, y), symbol_width(symbol), &gate_label(name, angles, *index, "π")),
+        Symbol::Measure(basis, _) => {
+            labelled_box(svg, (x, y), BOX, "");
+            svg.push(format!("<path d=\"M {} {} A 9 9 0 0 1 {} {}\"/>", x - 9., y + 6., x + 9., y + 6.));
+            line(svg, (x, y + 6.), (x + 7., y - 7.), None);
+            if let Some(basis) = basis.strip_prefix('M').filter(|basis| !basis.is_empty()) {
+                text(svg, (x - 8., y - 7.), basis, Some("small"));
+            }
+        }
+    }
+}
+
+/// Draws an element on a column centred on x, its vertical connections first so that its symbols are drawn over them.
+fn draw_element(svg: &mut Vec<String>, element: &Element, x: f64, y: impl Fn(usize) -> f64, number_of_qubits: usize) {
+    let rows: Vec<usize> = element.symbols.iter().map(|(row, _)| *row).collect();
+    let (first, last) = (*rows.iter().min().unwrap(), *rows.iter().max().unwrap());
+    if element.connected && first < last {
+        line(svg, (x, y(first)), (x, y(last)), None);
+    }
+    for (row, symbol) in element.symbols.iter() {
+        if let Symbol::Measure(_, Some((wire, position))) = symbol {
+            let classical = y(number_of_qubits + wire);
+            double_line(svg, (x, y(*row)), (x, classical - 5.));
+            svg.push(format!("<path d=\"M {} {} L {} {} L {} {} Z\" style=\"fill: black\"/>", x - 5., classical - 7., x + 5., classical - 7., x, classical));
+            text(svg, (x, classical + 12.), &position.to_string(), Some("small"));
+        }
+    }
+    for (row, symbol) in element.symbols.iter() {
+        draw_symbol(svg, symbol, (x, y(*row)));
+    }
+}
+
+/// Draws a program as a standalone SVG image with the same layout as its text drawing, without wrapping.
+pub fn to_svg(program: &Program) -> String {
+    let layout = Layout::new(program);
+    let number_of_qubits = layout.qubits.len();
+    let rows = layout.rows();
+    let labels: Vec<&str> = layout.qubits.iter().chain(layout.classical.iter().map(|(label, _)| label)).map(|label| label.as_str()).collect();
+    let label_width = labels.iter().map(|label| text_width(label)).fold(0., f64::max);
+    let y = |row: usize| MARGIN + ROW * (row as f64 + 0.5);
+
+    // the centres of the columns
+    let mut x = MARGIN + label_width + 15.;
+    let mut centres = vec![];
+    for column in layout.columns.iter() {
+        let width = column.iter().flat_map(|element| element.symbols.iter()).map(|(_, symbol)| symbol_width(symbol)).fold(0., f64::max) + 20.;
+        centres.push(x + width / 2.);
+        x += width;
+    }
+    let end = x + 15.;
+    let (width, height) = (end + MARGIN, 2. * MARGIN + ROW * rows as f64);
+
+    let mut svg = vec![
+        format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">", width, height, width, height),
+        format!("<style>\n{}</style>", STYLE),
+        format!("<rect width=\"{}\" height=\"{}\" style=\"fill: white; stroke: none\"/>", width, height),
+    ];
+    for (row, label) in labels.iter().enumerate() {
+        text(&mut svg, (MARGIN + label_width, y(row)), label, Some("label"));
+        let start = MARGIN + label_width + 5.;
+        match row < number_of_qubits {
+            true => line(&mut svg, (start, y(row)), (end, y(row)), None),
+            false => double_line(&mut svg, (start, y(row)), (end, y(row))),
+        }
+    }
+    for (column, x) in layout.columns.iter().zip(centres) {
+        for element in column.iter() {
+            draw_element(&mut svg, element, x, y, number_of_qubits);
+        }
+    }
+    svg.push("</svg>".to_owned());
+    svg.join("\n") + "\n"
+}
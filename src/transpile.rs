@@ -0,0 +1,529 @@
+use nalgebra::{ComplexField, Matrix4, SymmetricEigen};
+
+use crate::fusion::*;
+use crate::macros::*;
+use crate::operations::*;
+use crate::program::Program;
+use crate::subcircuit::*;
+use crate::types::*;
+
+/// The native gate sets a program can be transpiled into. Single qubit gates are always expressed
+/// with RZ and SX, the variant chooses the entangling gate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NativeGateSet {
+    /// RZ, SX and CZ.
+    CZ,
+    /// RZ, SX and SISWAP.
+    SISWAP,
+}
+
+impl NativeGateSet {
+    /// The native entangling gate acting on qubit_0 and qubit_1.
+    fn entangler(&self, qubit_0: usize, qubit_1: usize) -> Operations {
+        match self {
+            NativeGateSet::CZ => Operations::CZ(qubit_0, qubit_1),
+            NativeGateSet::SISWAP => Operations::SISWAP(qubit_0, qubit_1),
+        }
+    }
+
+    /// The matrix of the native entangling gate, which is symmetric in its two qubits.
+    fn entangler_matrix(&self) -> Matrix4x4 {
+        match self {
+            NativeGateSet::CZ => CZ,
+            NativeGateSet::SISWAP => SISWAP,
+        }
+    }
+}
+
+/// The precision to which decompositions are required to reproduce the original unitary.
+static TRANSPILE_PRECISION: Real = 1e-9;
+
+/// Decomposes a single qubit unitary as u = e^(i alpha) RZ(phi) RY(theta) RZ(lambda), returning
+/// (alpha, phi, theta, lambda).
+pub fn zyz_decomposition(u: &Matrix2x2) -> (Angle, Angle, Angle, Angle) {
+    let alpha = u.determinant().argument() / 2.;
+    let v = u * expi!(-alpha);
+    let (a, b) = (v[(0, 0)], v[(1, 0)]);
+    let theta = 2. * b.abs().atan2(a.abs());
+    let phi = b.argument() - a.argument();
+    let lambda = -a.argument() - b.argument();
+    (alpha, phi, theta, lambda)
+}
+
+/// Wraps an angle into the range (-pi, pi].
+fn wrap_angle(angle: Angle) -> Angle {
+    let wrapped = angle.rem_euclid(2. * PI);
+    match wrapped > PI {
+        true => wrapped - 2. * PI,
+        false => wrapped,
+    }
+}
+
+/// Pushes an RZ gate unless it is the identity (up to a global phase).
+fn push_rz(gates: &mut Vec<Operations>, qubit: usize, angle: Angle) {
+    let angle = wrap_angle(angle);
+    if angle.abs() > TRANSPILE_PRECISION {
+        gates.push(Operations::RZ(qubit, angle))
+    }
+}
+
+/// Expresses a single qubit unitary as RZ(lambda) SX RZ(theta - pi) SX RZ(phi + pi) (in the order
+/// they are applied), up to a global phase. If the unitary is diagonal a single RZ is used.
+pub fn single_qubit_to_native(qubit: usize, u: &Matrix2x2) -> Vec<Operations> {
+    let (_, phi, theta, lambda) = zyz_decomposition(u);
+    let mut gates = vec![];
+    if theta.abs() < TRANSPILE_PRECISION {
+        push_rz(&mut gates, qubit, phi + lambda);
+    } else {
+        push_rz(&mut gates, qubit, lambda);
+        gates.push(Operations::SX(qubit));
+        push_rz(&mut gates, qubit, theta - PI);
+        gates.push(Operations::SX(qubit));
+        push_rz(&mut gates, qubit, phi + PI);
+    }
+    gates
+}
+
+/// The magic (Bell) basis, in which local unitaries become real orthogonal matrices.
+fn magic_basis() -> Matrix4x4 {
+    let s = 1. / SQRT_2;
+    nalgebra::matrix![
+        c!(s, 0.), c!(0., 0.), c!(0., 0.), c!(0., s);
+        c!(0., 0.), c!(0., s), c!(s, 0.), c!(0., 0.);
+        c!(0., 0.), c!(0., s), c!(-s, 0.), c!(0., 0.);
+        c!(s, 0.), c!(0., 0.), c!(0., 0.), c!(0., -s);
+    ]
+}
+
+/// A single qubit unitary acting on qubit_0 of a two qubit unitary.
+fn on_qubit_0(u: &Matrix2x2) -> Matrix4x4 {
+    IDENTITY.kronecker(u)
+}
+
+/// A single qubit unitary acting on qubit_1 of a two qubit unitary.
+fn on_qubit_1(u: &Matrix2x2) -> Matrix4x4 {
+    u.kronecker(&IDENTITY)
+}
+
+/// The single qubit unitary exp(i angle pauli).
+fn pauli_exponential(angle: Angle, pauli: &Matrix2x2) -> Matrix2x2 {
+    IDENTITY * c!(angle.cos(), 0.) + pauli * c!(0., angle.sin())
+}
+
+/// The Paulis X, Y and Z acting on both qubits, XX, YY and ZZ.
+fn pauli_pairs() -> [Matrix4x4; 3] {
+    [SIGMA_X, SIGMA_Y, SIGMA_Z].map(|pauli| pauli.kronecker(&pauli))
+}
+
+/// The interaction exp(i (a XX + b YY + c ZZ)) with coordinates (a, b, c). As the three terms commute
+/// it is the product of their exponentials, each cos(angle) + i sin(angle) PP.
+fn interaction(coordinates: &[Real; 3]) -> Matrix4x4 {
+    let mut n = Matrix4x4::identity();
+    for (pair, angle) in pauli_pairs().iter().zip(coordinates) {
+        n *= Matrix4x4::identity() * c!(angle.cos(), 0.) + pair * c!(0., angle.sin());
+    }
+    n
+}
+
+/// Diagonalises a complex symmetric unitary matrix m = p d p^T with a real orthogonal p.
+///
+/// The real and imaginary parts of m are commuting real symmetric matrices, so p is found as the
+/// eigenvectors of cos(psi) Re(m) + sin(psi) Im(m). A direction psi only separates the eigenvalues of m
+/// if it is away from the midpoints of their phases, so a fixed set of directions is tried and the one
+/// leaving the smallest off diagonal part is kept. This is deterministic and always returns a matrix.
+fn real_orthogonal_diagonalisation(m: &Matrix4x4) -> Matrix4<Real> {
+    let real = m.map(|x| x.re);
+    let imaginary = m.map(|x| x.im);
+    let mut best = (Real::INFINITY, Matrix4::identity());
+    for k in 0..12 {
+        let psi = PI * k as Real / 12.;
+        let eigen = match SymmetricEigen::try_new(real * psi.cos() + imaginary * psi.sin(), Real::EPSILON, 1000) {
+            Some(eigen) => eigen,
+            None => continue,
+        };
+        let p = eigen.eigenvectors;
+        let d = p.map(|x| c!(x, 0.)).transpose() * m * p.map(|x| c!(x, 0.));
+        let off_diagonal: Real = (0..4)
+            .flat_map(|i| (0..4).map(move |j| (i, j)))
+            .filter(|(i, j)| i != j)
+            .map(|(i, j)| d[(i, j)].norm_sqr())
+            .sum();
+        if off_diagonal < best.0 {
+            best = (off_diagonal, p);
+        }
+    }
+    best.1
+}
+
+/// Splits a 4x4 unitary which is a tensor product of single qubit unitaries into the unitaries
+/// acting on (qubit_0, qubit_1).
+fn tensor_factors(k: &Matrix4x4) -> (Matrix2x2, Matrix2x2) {
+    let (mut i, mut j) = (0, 0);
+    for (m, n) in (0..4).flat_map(|m| (0..4).map(move |n| (m, n))) {
+        if k[(m, n)].abs() > k[(i, j)].abs() {
+            (i, j) = (m, n);
+        }
+    }
+    // k[(2 * a + b, 2 * c + d)] = u_1[(a, c)] * u_0[(b, d)]
+    let u_0 = Matrix2x2::from_fn(|b, d| k[((i & 2) + b, (j & 2) + d)]);
+    let u_0 = u_0 / u_0.determinant().sqrt();
+    let u_1 = Matrix2x2::from_fn(|a, c| k[(2 * a + (i & 1), 2 * c + (j & 1))] / u_0[(i & 1, j & 1)]);
+    (u_0, u_1)
+}
+
+/// The Cartan (KAK) decomposition of a two qubit unitary u = e^(i phase) after interaction(coordinates) before,
+/// where after and before are tensor products of single qubit unitaries.
+#[derive(Debug, Clone)]
+struct CartanDecomposition {
+    phase: Real,
+    after: Matrix4x4,
+    coordinates: [Real; 3],
+    before: Matrix4x4,
+}
+
+impl CartanDecomposition {
+    /// Decomposes u by diagonalising u_b^T u_b, where u_b is u rescaled to a special unitary and written in
+    /// the magic basis, in which local unitaries are real orthogonal and the interaction is diagonal.
+    fn new(u: &Matrix4x4) -> CartanDecomposition {
+        let q = magic_basis();
+        let alpha = u.determinant().argument() / 4.;
+        let u_b = q.adjoint() * u * expi!(-alpha) * q;
+        let m = u_b.transpose() * u_b;
+
+        let mut p = real_orthogonal_diagonalisation(&m);
+        if p.determinant() < 0. {
+            p.column_mut(0).neg_mut();
+        }
+        let p_complex = p.map(|x| c!(x, 0.));
+        let d = p_complex.transpose() * m * p_complex;
+
+        // u_b = k_1 diag(roots) p^T with k_1 real orthogonal, the signs of the square roots chosen so that
+        // det k_1 = 1
+        let mut roots = [0, 1, 2, 3].map(|k| match d[(k, k)].abs() > 0. {
+            true => (d[(k, k)] / d[(k, k)].abs()).sqrt(),
+            false => c!(1., 0.),
+        });
+        if roots.iter().product::<Complex>().re < 0. {
+            roots[0] = -roots[0];
+        }
+        let inverse_roots = Matrix4x4::from_diagonal(&nalgebra::Vector4::from(roots.map(|root| root.conj())));
+        let k_1 = (u_b * p_complex * inverse_roots).map(|x| c!(x.re, 0.));
+
+        // the interaction is diagonal in the magic basis, with diagonals of +/- 1 for XX, YY and ZZ, which
+        // together with the identity are orthogonal
+        let angles = roots.map(|root| root.argument());
+        let mut coordinates = [0.; 3];
+        for (coordinate, pair) in coordinates.iter_mut().zip(pauli_pairs().iter()) {
+            let signs = q.adjoint() * pair * q;
+            *coordinate = (0..4).map(|k| signs[(k, k)].re * angles[k]).sum::<Real>() / 4.;
+        }
+        CartanDecomposition {
+            phase: alpha + angles.iter().sum::<Real>() / 4.,
+            after: q * k_1 * q.adjoint(),
+            coordinates,
+            before: q * p_complex.transpose() * q.adjoint(),
+        }
+    }
+
+    /// Subtracts steps * pi / 2 from a coordinate, using exp(i pi / 2 PP) = i PP.
+    fn shift(&mut self, coordinate: usize, steps: i32) {
+        let pair = pauli_pairs()[coordinate];
+        for _ in 0..steps.rem_euclid(4) {
+            self.before = pair * self.before;
+        }
+        self.phase += PI / 2. * steps as Real;
+        self.coordinates[coordinate] -= PI / 2. * steps as Real;
+    }
+
+    /// Flips the sign of two coordinates by conjugating the interaction with the Pauli on qubit_0 which
+    /// anticommutes with both of their terms.
+    fn flip(&mut self, coordinate_0: usize, coordinate_1: usize) {
+        let pauli = on_qubit_0(&[SIGMA_X, SIGMA_Y, SIGMA_Z][3 - coordinate_0 - coordinate_1]);
+        self.after *= pauli;
+        self.before = pauli * self.before;
+        self.coordinates[coordinate_0] = -self.coordinates[coordinate_0];
+        self.coordinates[coordinate_1] = -self.coordinates[coordinate_1];
+    }
+
+    /// Swaps two coordinates by conjugating the interaction with a Clifford on both qubits which exchanges
+    /// their Paulis up to sign, S for (X, Y), H for (X, Z) and SX for (Y, Z).
+    fn swap(&mut self, coordinate_0: usize, coordinate_1: usize) {
+        let clifford = [SX, H, S][3 - coordinate_0 - coordinate_1];
+        let clifford = clifford.kronecker(&clifford);
+        self.after *= clifford.adjoint();
+        self.before = clifford * self.before;
+        self.coordinates.swap(coordinate_0, coordinate_1);
+    }
+
+    /// Brings the coordinates into the Weyl chamber pi / 4 >= a >= b >= |c|, with c >= 0 if a = pi / 4,
+    /// in which two unitaries are equal up to single qubit gates if and only if their coordinates are.
+    fn canonicalise(mut self) -> CartanDecomposition {
+        for coordinate in 0..3 {
+            let steps = (self.coordinates[coordinate] / (PI / 2.)).round();
+            self.shift(coordinate, steps as i32);
+        }
+        for _ in 0..2 {
+            for coordinate in 0..2 {
+                if self.coordinates[coordinate].abs() < self.coordinates[coordinate + 1].abs() {
+                    self.swap(coordinate, coordinate + 1);
+                }
+            }
+        }
+        if self.coordinates[0] < 0. {
+            self.flip(0, 2);
+        }
+        if self.coordinates[1] < 0. {
+            self.flip(1, 2);
+        }
+        if self.coordinates[0] > PI / 4. - TRANSPILE_PRECISION && self.coordinates[2] < 0. {
+            self.shift(0, 1);
+            self.flip(0, 2);
+        }
+        self
+    }
+}
+
+/// The unitary l_n e ... l_1 e l_0 of single qubit layers l_k interleaved with entangling gates e.
+fn layers_unitary(layers: &[Matrix4x4], entangler: &Matrix4x4) -> Matrix4x4 {
+    let mut v = layers[0];
+    for layer in layers[1..].iter() {
+        v = layer * entangler * v;
+    }
+    v
+}
+
+/// Given single qubit layers implementing a unitary with the same canonical coordinates as u, returns
+/// layers implementing u itself up to a global phase, by comparing the Cartan decompositions of the two.
+fn match_layers(u: &Matrix4x4, mut layers: Vec<Matrix4x4>, entangler: &Matrix4x4) -> Vec<Matrix4x4> {
+    let target = CartanDecomposition::new(u).canonicalise();
+    let template = CartanDecomposition::new(&layers_unitary(&layers, entangler)).canonicalise();
+    // u ~ after_u interaction before_u and v ~ after_v interaction before_v, so
+    // u ~ after_u after_v^dagger v before_v^dagger before_u
+    layers[0] = layers[0] * template.before.adjoint() * target.before;
+    let last = layers.len() - 1;
+    layers[last] = target.after * template.after.adjoint() * layers[last];
+    layers
+}
+
+/// Whether canonical coordinates are equal within the transpiler precision.
+fn same_coordinates(coordinates: &[Real; 3], other: &[Real; 3]) -> bool {
+    coordinates.iter().zip(other).all(|(a, b)| (a - b).abs() < TRANSPILE_PRECISION)
+}
+
+/// Single qubit layers around CZ gates implementing a unitary with the given canonical coordinates (a, b, c).
+///
+/// With CNOT = H CZ H, conjugating by CNOT(qubit_0, qubit_1) maps X_0 to X_0 X_1, Z_1 to Z_0 Z_1 and Y_0 Y_1
+/// to -X_0 Z_1, while conjugating by CZ maps X_0 to X_0 Z_1. So
+/// interaction(a, b, c) = CNOT exp(i a X_0) exp(i c Z_1) CZ exp(-i b X_0) CZ CNOT, and the trailing
+/// CZ CNOT = S_0 S_1 CNOT S_1^dagger saves one entangling gate. If c = 0 the middle CZs are not needed.
+fn cz_layers(coordinates: &[Real; 3]) -> Vec<Matrix4x4> {
+    let [a, b, c] = *coordinates;
+    let identity = Matrix4x4::identity();
+    let hadamard = on_qubit_1(&H);
+    if same_coordinates(coordinates, &[0., 0., 0.]) {
+        vec![identity]
+    } else if same_coordinates(coordinates, &[PI / 4., 0., 0.]) {
+        vec![identity, identity]
+    } else if c.abs() < TRANSPILE_PRECISION {
+        // CNOT exp(i a X_0) exp(i b Z_1) CNOT = exp(i a XX) exp(i b ZZ)
+        let middle = on_qubit_0(&pauli_exponential(a, &SIGMA_X)) * on_qubit_1(&pauli_exponential(b, &SIGMA_Z));
+        vec![hadamard, hadamard * middle * hadamard, hadamard]
+    } else {
+        let middle = on_qubit_0(&pauli_exponential(a, &SIGMA_X)) * on_qubit_1(&pauli_exponential(c, &SIGMA_Z));
+        vec![
+            hadamard * on_qubit_1(&SDG),
+            on_qubit_0(&pauli_exponential(-b, &SIGMA_X)) * on_qubit_0(&S) * on_qubit_1(&S) * hadamard,
+            hadamard * middle,
+            hadamard,
+        ]
+    }
+}
+
+/// Whether canonical coordinates (a, b, c) can be reached with two SISWAP gates, which is the case if
+/// a >= b + |c|.
+fn two_siswaps_suffice(coordinates: &[Real; 3]) -> bool {
+    coordinates[0] + TRANSPILE_PRECISION >= coordinates[1] + coordinates[2].abs()
+}
+
+/// Single qubit layers around two SISWAP gates implementing a unitary with canonical coordinates (a, b, c)
+/// satisfying a >= b + |c|, following Huang et al., Towards ultra-high fidelity quantum operations: SQiSW
+/// gate as a native two-qubit gate (2021).
+fn two_siswap_layers(coordinates: &[Real; 3]) -> Vec<Matrix4x4> {
+    let [a, b, c] = *coordinates;
+    let clamped_acos = |x: Real| x.clamp(-1., 1.).acos();
+    let product = (a + b - c).sin() * (a - b + c).sin() * (-a - b - c).sin() * (-a + b + c).sin();
+    let root = product.clamp(0., 1.).sqrt();
+    let sum = (2. * a).cos() - (2. * b).cos() + (2. * c).cos();
+    let alpha = clamped_acos(sum + 2. * root);
+    let beta = clamped_acos(sum - 2. * root);
+    let numerator = 4. * (a.cos() * c.cos() * b.sin()).powi(2);
+    let denominator = numerator + ((2. * a).cos() * (2. * b).cos() * (2. * c).cos()).clamp(0., 1.);
+    let gamma = match denominator > 0. {
+        true => clamped_acos(if c < 0. { -1. } else { 1. } * (numerator / denominator).sqrt()),
+        false => 0.,
+    };
+    let middle = on_qubit_0(&rx_matrix(&-beta)) * on_qubit_1(&(rz_matrix(&-gamma) * rx_matrix(&-alpha) * rz_matrix(&-gamma)));
+    let identity = Matrix4x4::identity();
+    vec![identity, middle, identity]
+}
+
+/// Single qubit layers around SISWAP gates implementing a unitary with the given canonical coordinates.
+///
+/// SISWAP itself has coordinates (pi / 8, pi / 8, 0). Outside the region covered by two SISWAPs the
+/// interaction is split as interaction(p) = interaction(p - s) interaction(s), where s has two coordinates
+/// of +/- pi / 8 and so is one SISWAP up to single qubit gates, chosen so that p - s needs only two.
+fn siswap_layers(coordinates: &[Real; 3]) -> Vec<Matrix4x4> {
+    let identity = Matrix4x4::identity();
+    if same_coordinates(coordinates, &[0., 0., 0.]) {
+        return vec![identity];
+    }
+    if same_coordinates(coordinates, &[PI / 8., PI / 8., 0.]) {
+        return vec![identity, identity];
+    }
+    if two_siswaps_suffice(coordinates) {
+        return two_siswap_layers(coordinates);
+    }
+
+    let mut shifts = vec![];
+    for (i, j) in [(0, 1), (0, 2), (1, 2)] {
+        for (sign_i, sign_j) in [(1., 1.), (1., -1.), (-1., 1.), (-1., -1.)] {
+            let mut shift = [0.; 3];
+            shift[i] = sign_i * PI / 8.;
+            shift[j] = sign_j * PI / 8.;
+            shifts.push(shift);
+        }
+    }
+    // the shift leaving the largest margin a - b - |c|
+    let margin = |shift: &[Real; 3]| {
+        let rest = [0, 1, 2].map(|k| coordinates[k] - shift[k]);
+        let canonical = CartanDecomposition::new(&interaction(&rest)).canonicalise().coordinates;
+        canonical[0] - canonical[1] - canonical[2].abs()
+    };
+    let shift = shifts
+        .iter()
+        .max_by(|a, b| margin(a).total_cmp(&margin(b)))
+        .copied()
+        .unwrap_or_default();
+    let rest = [0, 1, 2].map(|k| coordinates[k] - shift[k]);
+
+    let siswap = SISWAP;
+    let first = match_layers(&interaction(&shift), vec![identity, identity], &siswap);
+    let rest_unitary = interaction(&rest);
+    let rest_coordinates = CartanDecomposition::new(&rest_unitary).canonicalise().coordinates;
+    let mut second = match_layers(&rest_unitary, two_siswap_layers(&rest_coordinates), &siswap);
+    second[0] *= first[1];
+    vec![first[0], second[0], second[1], second[2]]
+}
+
+/// Decomposes a two qubit unitary, whose rows and columns are indexed by bit(qubit_0) + 2 * bit(qubit_1),
+/// into at most three native entangling gates interleaved with single qubit unitaries, up to a global phase.
+///
+/// The Cartan decomposition gives the canonical coordinates of u, for which a circuit of the fewest
+/// entangling gates is written down directly, and the single qubit gates around it are then matched to u.
+pub fn two_qubit_to_native(qubit_0: usize, qubit_1: usize, u: &Matrix4x4, gate_set: &NativeGateSet) -> Vec<Operations> {
+    let entangler = gate_set.entangler_matrix();
+    let coordinates = CartanDecomposition::new(u).canonicalise().coordinates;
+    let layers = match gate_set {
+        NativeGateSet::CZ => cz_layers(&coordinates),
+        NativeGateSet::SISWAP => siswap_layers(&coordinates),
+    };
+    let layers = match_layers(u, layers, &entangler);
+
+    let mut gates = vec![];
+    for (n, layer) in layers.iter().enumerate() {
+        if n > 0 {
+            gates.push(gate_set.entangler(qubit_0, qubit_1));
+        }
+        let (u_0, u_1) = tensor_factors(layer);
+        gates.append(&mut single_qubit_to_native(qubit_0, &u_0));
+        gates.append(&mut single_qubit_to_native(qubit_1, &u_1));
+    }
+    gates
+}
+
+/// Rewrites the program into the native gate set. Subcircuits are expanded and runs of gates fused into
+/// single and two qubit unitaries, which are then decomposed with the ZYZ and KAK decompositions respectively.
+pub fn transpile(program: &Program, gate_set: &NativeGateSet) -> Vec<Operations> {
+    let mut gates = vec![];
+    for gate in fuse(&flatten(&program.gates), &Fusion::TwoQubit).iter() {
+        if let Some((qubit, u)) = single_qubit_unitary(gate) {
+            gates.append(&mut single_qubit_to_native(qubit, &u));
+        } else if let Some((qubit_0, qubit_1, u)) = two_qubit_unitary(gate) {
+            gates.append(&mut two_qubit_to_native(qubit_0, qubit_1, &u, gate_set));
+        } else {
+            gates.push(gate.to_owned());
+        }
+    }
+    gates
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::transpile::*;
+
+    fn native_unitary(gates: &[Operations]) -> Matrix4x4 {
+        let mut u = Matrix4x4::identity();
+        for gate in gates {
+            if let Some((qubit, v)) = single_qubit_unitary(gate) {
+                u = match qubit {
+                    0 => IDENTITY.kronecker(&v) * u,
+                    _ => v.kronecker(&IDENTITY) * u,
+                };
+            } else if let Some((_, _, v)) = two_qubit_unitary(gate) {
+                u = v * u;
+            }
+        }
+        u
+    }
+
+    #[test]
+    fn test_zyz_decomposition() {
+        let u = r_matrix(&0.3, &1.2, &-2.1) * expi!(0.7);
+        let (alpha, phi, theta, lambda) = zyz_decomposition(&u);
+        let v = rz_matrix(&phi) * ry_matrix(&theta) * rz_matrix(&lambda) * expi!(alpha);
+        assert!((u - v).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_tensor_factors() {
+        let u_0 = r_matrix(&0.3, &1.2, &-2.1);
+        let u_1 = r_matrix(&1.3, &0.2, &0.4);
+        let (v_0, v_1) = tensor_factors(&u_1.kronecker(&u_0));
+        assert!((u_1.kronecker(&u_0) - v_1.kronecker(&v_0)).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_cartan_decomposition() {
+        let u = on_qubit_1(&r_matrix(&0.3, &1.2, &-2.1)) * interaction(&[1.1, -0.4, 2.3]) * on_qubit_0(&H) * CY;
+        let cartan = CartanDecomposition::new(&u);
+        let v = cartan.after * interaction(&cartan.coordinates) * cartan.before * expi!(cartan.phase);
+        assert!((u - v).norm() < 1e-12);
+
+        let canonical = cartan.canonicalise();
+        let v = canonical.after * interaction(&canonical.coordinates) * canonical.before * expi!(canonical.phase);
+        assert!((u - v).norm() < 1e-12);
+        let [a, b, c] = canonical.coordinates;
+        assert!(PI / 4. >= a && a >= b && b >= c.abs());
+        let (after_0, after_1) = tensor_factors(&canonical.after);
+        assert!((after_1.kronecker(&after_0) - canonical.after).norm() < 1e-12);
+    }
+
+    #[test]
+    fn test_entangler_count() {
+        for (u, gate_set, required) in [
+            (SWAP, NativeGateSet::CZ, 3),
+            (CNOT, NativeGateSet::CZ, 1),
+            (Matrix4x4::identity(), NativeGateSet::CZ, 0),
+            (SWAP, NativeGateSet::SISWAP, 3),
+            (CNOT, NativeGateSet::SISWAP, 2),
+            (SISWAP, NativeGateSet::SISWAP, 1),
+        ] {
+            let gates = two_qubit_to_native(0, 1, &u, &gate_set);
+            let count = gates.iter().filter(|gate| matches!(gate, Operations::CZ(_, _) | Operations::SISWAP(_, _))).count();
+            assert_eq!(count, required);
+            let overlap = (native_unitary(&gates).adjoint() * u).trace().abs() / 4.;
+            assert!((1. - overlap).abs() < 1e-9);
+        }
+    }
+}
+
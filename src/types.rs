@@ -23,12 +23,15 @@ pub type CVector = DVector<Complex>;
 /// A real vector
 pub type RVector = DVector<Real>;
 
+/// A real matrix
+pub type RMatrix = DMatrix<Real>;
+
 /// A 2x2 complex matrix
 pub type Matrix2x2 = SMatrix<Complex, 2, 2>;
 
 pub type Matrix4x4 = SMatrix<Complex, 4, 4>;
 
-pub type ClassicalRegister = Vec<Option<bool>>;
+pub type ClassicalBits = Vec<Option<bool>>;
 
 /// An angle in radians
 pub type Angle = Real;
@@ -71,6 +74,36 @@ pub static S: Matrix2x2 = matrix![
     c!(0., 0.), c!(0., 1.);
 ];
 
+/// The inverse of the s gate
+pub static SDG: Matrix2x2 = matrix![
+    c!(1., 0.), c!(0., 0.);
+    c!(0., 0.), c!(0., -1.);
+];
+
+/// The t gate, the square root of the s gate
+pub static T: Matrix2x2 = matrix![
+    c!(1., 0.), c!(0., 0.);
+    c!(0., 0.), c!(1. / SQRT_2, 1. / SQRT_2);
+];
+
+/// The inverse of the t gate
+pub static TDG: Matrix2x2 = matrix![
+    c!(1., 0.), c!(0., 0.);
+    c!(0., 0.), c!(1. / SQRT_2, -1. / SQRT_2);
+];
+
+/// The square root of the x pauli matrix
+pub static SX: Matrix2x2 = matrix![
+    c!(0.5, 0.5), c!(0.5, -0.5);
+    c!(0.5, -0.5), c!(0.5, 0.5);
+];
+
+/// The inverse of the square root of the x pauli matrix
+pub static SXDG: Matrix2x2 = matrix![
+    c!(0.5, -0.5), c!(0.5, 0.5);
+    c!(0.5, 0.5), c!(0.5, -0.5);
+];
+
 /// The hadamard matrix
 pub static H: Matrix2x2 = matrix![
     c!(1. / SQRT_2 as Real, 0.), c!(1. / SQRT_2 as Real, 0.);
@@ -84,6 +117,20 @@ pub static CNOT: Matrix4x4 = matrix![
     c!(0., 0.), c!(0., 0.),c!(1., 0.),c!(0., 0.);
 ];
 
+pub static CY: Matrix4x4 = matrix![
+    c!(1., 0.), c!(0., 0.),c!(0., 0.),c!(0., 0.);
+    c!(0., 0.), c!(1., 0.),c!(0., 0.),c!(0., 0.);
+    c!(0., 0.), c!(0., 0.),c!(0., 0.),c!(0., -1.);
+    c!(0., 0.), c!(0., 0.),c!(0., 1.),c!(0., 0.);
+];
+
+pub static CH: Matrix4x4 = matrix![
+    c!(1., 0.), c!(0., 0.),c!(0., 0.),c!(0., 0.);
+    c!(0., 0.), c!(1., 0.),c!(0., 0.),c!(0., 0.);
+    c!(0., 0.), c!(0., 0.),c!(1. / SQRT_2, 0.),c!(1. / SQRT_2, 0.);
+    c!(0., 0.), c!(0., 0.),c!(1. / SQRT_2, 0.),c!(-1. / SQRT_2, 0.);
+];
+
 pub static CZ: Matrix4x4 = matrix![
     c!(1., 0.), c!(0., 0.),c!(0., 0.),c!(0., 0.);
     c!(0., 0.), c!(1., 0.),c!(0., 0.),c!(0., 0.);
@@ -106,6 +153,14 @@ pub static SISWAP: Matrix4x4 = matrix![
 ];
 
 
+/// The echoed cross resonance gate, (IX - XY) / sqrt(2) with the first qubit on the left
+pub static ECR: Matrix4x4 = matrix![
+    c!(0., 0.), c!(1. / SQRT_2, 0.), c!(0., 0.), c!(0., 1. / SQRT_2);
+    c!(1. / SQRT_2, 0.), c!(0., 0.), c!(0., -1. / SQRT_2), c!(0., 0.);
+    c!(0., 0.), c!(0., 1. / SQRT_2), c!(0., 0.), c!(1. / SQRT_2, 0.);
+    c!(0., -1. / SQRT_2), c!(0., 0.), c!(1. / SQRT_2, 0.), c!(0., 0.);
+];
+
 pub static SWAP: Matrix4x4 = matrix![
     c!(1., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
     c!(0., 0.), c!(0., 0.), c!(1., 0.), c!(0., 0.);
@@ -134,7 +189,7 @@ impl<T> DensityMatrixPointer<T> {
     }
 
     pub unsafe fn offset(&self, indices: (usize, usize)) -> *mut T {
-        self.pointer.add(self.flatten_index(indices) as usize)
+        self.pointer.add(self.flatten_index(indices))
     }
 
     pub unsafe fn read(&self, indices: (usize, usize)) -> T {
@@ -159,7 +214,7 @@ impl<T> StateVectorPointer<T> {
     pub fn new(value: &mut T, size: usize) -> Self {
         StateVectorPointer {
             pointer: value as *mut T,
-            size: size
+            size
         }
     }
     pub unsafe fn read(&self, index: usize) -> T {
@@ -0,0 +1,108 @@
+use std::fmt;
+
+use nalgebra::SMatrix;
+
+use crate::operations::*;
+use crate::registers::MAX_CLASSICAL_BITS;
+use crate::types::*;
+
+/// A problem with a gate of a program, found by Program::validate. For a gate inside a subcircuit the
+/// index is that of the subcircuit in the program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationIssue {
+    /// The gate acts on a qubit beyond those of the state.
+    QubitOutOfRange { gate: usize, qubit: usize },
+    /// The gate acts on the same qubit more than once.
+    RepeatedQubit { gate: usize, qubit: usize },
+    /// The matrix of an arbitrary gate is not unitary.
+    NotUnitary { gate: usize },
+    /// An angle of the gate is infinite or not a number.
+    NotFinite { gate: usize, angle: Angle },
+    /// A subcircuit placed on a different number of qubits than it acts on.
+    WrongPlacement { gate: usize, expected: usize, found: usize },
+    /// The gate measures into a classical bit beyond the most a program may use.
+    BitOutOfRange { gate: usize, bit: usize },
+}
+
+impl ValidationIssue {
+    /// The index of the gate with the problem.
+    pub fn gate(&self) -> usize {
+        match self {
+            ValidationIssue::QubitOutOfRange { gate, .. }
+            | ValidationIssue::RepeatedQubit { gate, .. }
+            | ValidationIssue::NotUnitary { gate }
+            | ValidationIssue::NotFinite { gate, .. }
+            | ValidationIssue::WrongPlacement { gate, .. }
+            | ValidationIssue::BitOutOfRange { gate, .. } => *gate,
+        }
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::QubitOutOfRange { gate, qubit } => write!(f, "gate {} acts on missing qubit {}", gate, qubit),
+            ValidationIssue::RepeatedQubit { gate, qubit } => write!(f, "gate {} uses qubit {} twice", gate, qubit),
+            ValidationIssue::NotUnitary { gate } => write!(f, "gate {} is not unitary", gate),
+            ValidationIssue::NotFinite { gate, angle } => write!(f, "gate {} has the angle {}", gate, angle),
+            ValidationIssue::WrongPlacement { gate, expected, found } => {
+                write!(f, "gate {} places a subcircuit of {} qubits on {}", gate, expected, found)
+            }
+            ValidationIssue::BitOutOfRange { gate, bit } => {
+                write!(f, "gate {} measures into classical bit {}, beyond the {} a program may use", gate, bit, MAX_CLASSICAL_BITS)
+            }
+        }
+    }
+}
+
+/// Whether the entries of u^dagger u are within the comparison precision of the identity.
+fn is_unitary<const D: usize>(u: &SMatrix<Complex, D, D>) -> bool {
+    let difference = u.adjoint() * u - SMatrix::<Complex, D, D>::identity();
+    difference.iter().all(|d| d.norm_sqr() < COMPARISON_PRECISION * COMPARISON_PRECISION)
+}
+
+/// Appends the problems of a gate acting on number_of_qubits qubits to issues, reporting them at index.
+fn validate_gate(gate: &Operations, index: usize, number_of_qubits: usize, issues: &mut Vec<ValidationIssue>) {
+    let qubits = which_qubits(gate);
+    for (n, qubit) in qubits.iter().enumerate() {
+        if **qubit >= number_of_qubits {
+            issues.push(ValidationIssue::QubitOutOfRange { gate: index, qubit: **qubit });
+        }
+        if qubits[..n].contains(qubit) {
+            issues.push(ValidationIssue::RepeatedQubit { gate: index, qubit: **qubit });
+        }
+    }
+    for angle in gate_angles(gate) {
+        if !angle.is_finite() {
+            issues.push(ValidationIssue::NotFinite { gate: index, angle });
+        }
+    }
+    match gate {
+        Operations::ArbitrarySingle(_, u) if !is_unitary(u) => issues.push(ValidationIssue::NotUnitary { gate: index }),
+        Operations::ArbitaryTwo(_, _, u) if !is_unitary(u) => issues.push(ValidationIssue::NotUnitary { gate: index }),
+        Operations::MeasureInto(_, bit) if *bit >= MAX_CLASSICAL_BITS => {
+            issues.push(ValidationIssue::BitOutOfRange { gate: index, bit: *bit })
+        }
+        Operations::Subcircuit(subcircuit, qubits) => {
+            if qubits.len() != subcircuit.number_of_qubits {
+                issues.push(ValidationIssue::WrongPlacement { gate: index, expected: subcircuit.number_of_qubits, found: qubits.len() });
+            }
+            for inner in subcircuit.gates.iter() {
+                validate_gate(inner, index, subcircuit.number_of_qubits, issues);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Checks every gate of a program run on number_of_qubits qubits, returning all the problems found.
+pub fn validate(gates: &[Operations], number_of_qubits: usize) -> Result<(), Vec<ValidationIssue>> {
+    let mut issues = vec![];
+    for (index, gate) in gates.iter().enumerate() {
+        validate_gate(gate, index, number_of_qubits, &mut issues);
+    }
+    match issues.is_empty() {
+        true => Ok(()),
+        false => Err(issues),
+    }
+}
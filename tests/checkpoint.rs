@@ -0,0 +1,115 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bra_ket::*;
+
+/// A path in the temporary directory unique to the test.
+fn temporary(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("bra_ket_{}_{}", std::process::id(), name))
+}
+
+fn prepared_state() -> StateVector {
+    let mut program = qft(4);
+    program.ry(2, 0.3);
+    program.cnot(1, 3);
+    let mut state = StateVector::random(4, 3);
+    program.run(&mut state).unwrap();
+    state
+}
+
+#[test]
+fn state_vectors_round_trip() {
+    let path = temporary("state.bk");
+    let mut state = prepared_state();
+    state.set_endianness(Endianness::Big);
+    state.save(&path).unwrap();
+    let loaded = StateVector::load(&path).unwrap();
+    assert_eq!(loaded.state_vector, state.state_vector);
+    assert_eq!(loaded.endianness, Endianness::Big);
+
+    // the header is followed by 16 bytes per amplitude
+    assert_eq!(fs::metadata(&path).unwrap().len(), 25 + 16 * 16);
+    state.save_with_precision(&path, Precision::Single).unwrap();
+    assert_eq!(fs::metadata(&path).unwrap().len(), 25 + 8 * 16);
+    assert_eq!(&StateVector::load(&path).unwrap(), &state);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn density_matrices_round_trip() {
+    let path = temporary("density.bk");
+    let state = DensityMatrix::random(3, 11);
+    state.save(&path).unwrap();
+    assert_eq!(DensityMatrix::load(&path).unwrap().density_matrix, state.density_matrix);
+    assert!(matches!(StateVector::load(&path), Err(BraKetError::InvalidFile(_))));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn corrupted_checkpoints_are_rejected() {
+    let path = temporary("corrupted.bk");
+    prepared_state().save(&path).unwrap();
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[40] ^= 1;
+    fs::write(&path, &bytes).unwrap();
+    assert!(matches!(StateVector::load(&path), Err(BraKetError::InvalidFile(_))));
+
+    // headers claiming more qubits than the elements can be counted for
+    for (qubits, offset) in [(64u32, 0), (32, 1)] {
+        let mut header = bytes[..25].to_vec();
+        header[10] = offset;
+        header[13..17].copy_from_slice(&qubits.to_le_bytes());
+        fs::write(&path, &header).unwrap();
+        let error = match offset {
+            0 => StateVector::load(&path).unwrap_err(),
+            _ => DensityMatrix::load(&path).unwrap_err(),
+        };
+        assert_eq!(error, BraKetError::InvalidFile("too many qubits".to_owned()));
+    }
+
+    fs::write(&path, b"not a checkpoint at all, just some text").unwrap();
+    assert!(matches!(StateVector::load(&path), Err(BraKetError::InvalidFile(_))));
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(StateVector::load(&path), Err(BraKetError::Io(_))));
+}
+
+#[test]
+fn npy_files_round_trip() {
+    let path = temporary("state.npy");
+    let state = prepared_state();
+    state.save_npy(&path).unwrap();
+    let bytes = fs::read(&path).unwrap();
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_length = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_length) % 64, 0);
+    let header = String::from_utf8(bytes[10..10 + header_length].to_vec()).unwrap();
+    assert!(header.starts_with("{'descr': '<c16', 'fortran_order': False, 'shape': (16,), }"));
+    assert!(header.ends_with('\n'));
+    assert_eq!(&StateVector::load_npy(&path).unwrap(), &state);
+    assert!(DensityMatrix::load_npy(&path).is_err());
+
+    let density = DensityMatrix::random(2, 1);
+    density.save_npy(&path).unwrap();
+    assert_eq!(&DensityMatrix::load_npy(&path).unwrap(), &density);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn fortran_ordered_npy_files() {
+    // the density matrix [[0.25, 0.5i], [-0.5i, 0.75]] stored column by column as complex64
+    let header = "{'descr': '<c8', 'fortran_order': True, 'shape': (2, 2), }";
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend((header.len() as u16).to_le_bytes());
+    bytes.extend(header.as_bytes());
+    for (re, im) in [(0.25f32, 0f32), (0., -0.5), (0., 0.5), (0.75, 0.)] {
+        bytes.extend(re.to_le_bytes());
+        bytes.extend(im.to_le_bytes());
+    }
+    let path = temporary("fortran.npy");
+    fs::write(&path, &bytes).unwrap();
+    let density = DensityMatrix::load_npy(&path).unwrap();
+    assert_eq!(density.density_matrix[(0, 1)], c!(0., 0.5));
+    assert_eq!(density.density_matrix[(1, 0)], c!(0., -0.5));
+    assert_eq!(density.density_matrix[(1, 1)], c!(0.75, 0.));
+    fs::remove_file(&path).unwrap();
+}
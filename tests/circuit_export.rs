@@ -0,0 +1,109 @@
+use bra_ket::*;
+
+fn every_operation() -> Program {
+    let mut inner = Program::new();
+    inner.h(0);
+    inner.cnot(0, 1);
+    let bell = inner.define("bell_pair");
+
+    let mut program = Program::new();
+    program.add_gates(vec![
+        Operations::H(0),
+        Operations::RX(1, PI / 2.),
+        Operations::CNOT(0, 2),
+        Operations::CZ(1, 2),
+        Operations::CY(2, 0),
+        Operations::CRZ(0, 1, -PI / 4.),
+        Operations::SWAP(0, 1),
+        Operations::ISWAP(1, 2),
+        Operations::FSim(0, 1, PI, 0.3),
+        Operations::Givens(2, 0, 2. * PI / 3.),
+        Operations::ECR(0, 1),
+        Operations::ArbitaryTwo(1, 2, Matrix4x4::identity()),
+        Operations::U3(0, 0., PI, -PI / 16.),
+        Operations::MeasureX(1),
+        Operations::MeasureY(2),
+        Operations::Barrier,
+        Operations::Reset(0),
+        Operations::ResetAll,
+    ]);
+    program.add_subcircuit(&bell, &[1, 2]);
+    program.add_gates(vec![Operations::MeasureAll]);
+    program
+}
+
+#[test]
+fn quantikz_bell_circuit() {
+    let mut program = Program::new();
+    let q = program.quantum_register("q", 2);
+    let c = program.classical_register("c", 2);
+    program.h(q[0]);
+    program.cnot(q[0], q[1]);
+    program.rz(q[1], PI / 2.);
+    program.measure_into(q[0], c[0]);
+    program.measure_into(q[1], c[1]);
+    let expected = r"\begin{quantikz}
+\lstick{q[0]} & \gate{\mathrm{H}} & \ctrl{1} & \qw & \meter{} \vcw{2} & \qw & \qw \\
+\lstick{q[1]} & \qw & \targ{} & \gate{\mathrm{RZ}(\pi/2)} & \qw & \meter{} \vcw{1} & \qw \\
+\lstick{c} & \cw & \cw & \cw & \cw & \cw & \cw
+\end{quantikz}
+";
+    assert_eq!(program.to_quantikz(), expected);
+}
+
+#[test]
+fn quantikz_multi_qubit_gates() {
+    let latex = every_operation().to_quantikz();
+    for command in [
+        r"\gate{\mathrm{Y}} \vqw{2}",
+        r"\control{}",
+        r"\swap{1}",
+        r"\targX{}",
+        r"\gate[wires=2]{\mathrm{ISWAP}}",
+        r"\gate[wires=2]{\mathrm{FSim}(\pi,0.3)}",
+        r"\gate{\mathrm{Givens}(2\pi/3):1} \vqw{2}",
+        r"\gate{\mathrm{Givens}(2\pi/3):0}",
+        r"\gate[wires=2]{\mathrm{ECR}}",
+        r"\gate{\mathrm{U3}(0,\pi,-\pi/16)}",
+        r"\gate{M_{X}}",
+        r"\gate{M_{Y}}",
+        r"\slice{}",
+        r"\gate{|0\rangle}",
+        r"\gate[wires=2]{\mathrm{bell\_pair}}",
+        r"\meter{}",
+    ] {
+        assert!(latex.contains(command), "{} is missing from\n{}", command, latex);
+    }
+    // every row has the same number of cells
+    let rows: Vec<&str> = latex.lines().filter(|line| line.starts_with(r"\lstick")).collect();
+    assert_eq!(rows.len(), 3);
+    assert!(rows.iter().all(|row| row.matches(" & ").count() == rows[0].matches(" & ").count()));
+}
+
+#[test]
+fn svg_draws_every_operation() {
+    let svg = every_operation().to_svg();
+    assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    for label in [">H<", ">RX(π/2)<", ">Y<", ">RZ(-π/4)<", ">ISWAP<", ">FSim(π,0.3)<", ">Givens(2π/3):0<", ">ECR:1<", ">U:0<", ">|0⟩<", ">bell_pair:0<", ">q0<"] {
+        assert!(svg.contains(label), "{} is missing", label);
+    }
+    assert_eq!(svg.matches("class=\"control\"").count(), 5);
+    assert_eq!(svg.matches("class=\"target\"").count(), 1);
+    assert_eq!(svg.matches("class=\"barrier\"").count(), 6);
+}
+
+#[test]
+fn svg_measurements_reach_classical_wires() {
+    let mut inner = Program::new();
+    inner.x(0);
+    let mut program = Program::new();
+    program.add_subcircuit(&inner.define("a<b"), &[0]);
+    program.measure_into(0, 1);
+    let svg = program.to_svg();
+    assert!(svg.contains(">a&lt;b<"));
+    assert!(!svg.contains("a<b"));
+    // the wire of the classical bits and the line from the measurement to it
+    assert_eq!(svg.matches("class=\"classical\"").count(), 4);
+    assert!(svg.contains(">c<") && svg.contains(">1<"));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "cli")]
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const BELL: &str = "OPENQASM 2.0;
+include \"qelib1.inc\";
+qreg q[2];
+creg c[2];
+h q[0];
+cx q[0], q[1];
+measure q -> c;
+";
+
+/// Writes a circuit to a file in the temporary directory unique to the test.
+fn circuit(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("bra_ket_cli_{}_{}", std::process::id(), name));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn bra_ket(arguments: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_bra_ket")).args(arguments).output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(output.status.success(), "failed with {}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn counts_of_a_qasm_circuit() {
+    let path = circuit("bell.qasm", BELL);
+    let file = path.to_str().unwrap();
+    let human = stdout(&bra_ket(&["--seed", "5", "--shots", "100", file]));
+    let lines: Vec<&str> = human.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("00 ") && lines[1].starts_with("11 "));
+
+    let json: serde_json::Value = serde_json::from_str(&stdout(&bra_ket(&["--seed", "5", "--shots", "100", "--output", "json", file]))).unwrap();
+    assert_eq!(json["shots"], 100);
+    assert_eq!(json["counts"]["00"].as_u64().unwrap() + json["counts"]["11"].as_u64().unwrap(), 100);
+    assert_eq!(format!("00 {}", json["counts"]["00"]), lines[0]);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn expectation_values_and_states() {
+    let path = circuit("bell_expectation.qasm", BELL);
+    let file = path.to_str().unwrap();
+    for backend in ["statevector", "density"] {
+        let output = stdout(&bra_ket(&["--backend", backend, "--show", "expectation", "--observable", "Z0Z1", "--observable", "Y0Y1", "--observable", "X1", file]));
+        assert_eq!(output, "<X1> = 0.000000\n<Y0Y1> = -1.000000\n<Z0Z1> = 1.000000\n");
+    }
+
+    let json: serde_json::Value = serde_json::from_str(&stdout(&bra_ket(&["--show", "state", "--output", "json", "--backend", "density", file]))).unwrap();
+    assert_eq!(json["number_of_qubits"], 2);
+    // measuring the qubits of the density matrix removes the coherence between |00> and |11>
+    assert!((json["elements"][0][0][0].as_f64().unwrap() - 0.5).abs() < 1e-9);
+    assert!((json["elements"][3][3][0].as_f64().unwrap() - 0.5).abs() < 1e-9);
+    assert!(json["elements"][0][3][0].as_f64().unwrap().abs() < 1e-9);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn qasm_registers_keep_their_values() {
+    let path = circuit("flip.qasm", "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[2];\ncreg c[2];\nx q[0];\nmeasure q -> c;\n");
+    let file = path.to_str().unwrap();
+    for endianness in ["little", "big"] {
+        assert_eq!(stdout(&bra_ket(&["--endianness", endianness, file])), "01 1024\n");
+    }
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn json_circuits() {
+    let mut program = bra_ket::Program::new();
+    program.x(1);
+    let path = circuit("program.json", &serde_json::to_string(&program).unwrap());
+    let output = stdout(&bra_ket(&["--endianness", "big", path.to_str().unwrap()]));
+    assert_eq!(output, "01 1024\n");
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn noisy_counts() {
+    let path = circuit("bell_noise.qasm", BELL);
+    let file = path.to_str().unwrap();
+    for backend in ["statevector", "density"] {
+        let output = stdout(&bra_ket(&["--backend", backend, "--noise", "0.1,0.2,0.05", "--seed", "3", "--shots", "400", "--output", "json", file]));
+        let json: serde_json::Value = serde_json::from_str(&output).unwrap();
+        let counts = json["counts"].as_object().unwrap();
+        // errors produce the outcomes a noiseless Bell state never gives
+        assert!(counts.contains_key("01") || counts.contains_key("10"));
+        assert_eq!(counts.values().map(|count| count.as_u64().unwrap()).sum::<u64>(), 400);
+    }
+    let noiseless = stdout(&bra_ket(&["--noise", "0,0,0", "--seed", "3", file]));
+    assert!(noiseless.lines().all(|line| line.starts_with("00 ") || line.starts_with("11 ")));
+    assert_eq!(bra_ket(&["--noise", "0.1,2", file]).status.code(), Some(2));
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn errors() {
+    assert_eq!(bra_ket(&["--shots"]).status.code(), Some(2));
+    assert_eq!(bra_ket(&["--backend", "analog", "file"]).status.code(), Some(2));
+
+    let path = circuit("broken.qasm", "OPENQASM 2.0;\nqreg q[1];\nfoo q[0];\n");
+    let output = bra_ket(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("line 3"));
+    fs::remove_file(path).unwrap();
+
+    // states too large to allocate are reported rather than aborting
+    let path = circuit("wide.qasm", "OPENQASM 2.0;\nqreg q[64];\n");
+    let output = bra_ket(&[path.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("not enough memory for a state of 64 qubits"));
+    fs::remove_file(path).unwrap();
+}
@@ -13,8 +13,8 @@ fn x0() {
         let mut state = StateVector::new(1);
         let mut density = DensityMatrix::new(1);
 
-        program.run(&mut state);
-        program.run(&mut density);
+        program.run(&mut state).unwrap();
+        program.run(&mut density).unwrap();
 
         assert_eq!(&density, &DensityMatrix::from(state))
     }
@@ -32,8 +32,8 @@ fn y0() {
         let mut state = StateVector::new(1);
         let mut density = DensityMatrix::new(1);
 
-        program.run(&mut state);
-        program.run(&mut density);
+        program.run(&mut state).unwrap();
+        program.run(&mut density).unwrap();
 
         assert_eq!(&density, &DensityMatrix::from(state))
     }
@@ -47,8 +47,8 @@ fn h0() {
     let mut state = StateVector::new(1);
     let mut density = DensityMatrix::new(1);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
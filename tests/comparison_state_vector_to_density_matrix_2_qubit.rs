@@ -11,8 +11,8 @@ fn h0_cnot01() {
     let mut state = StateVector::new(2);
     let mut density = DensityMatrix::new(2);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -27,8 +27,8 @@ fn h0_cnot10() {
     let mut state = StateVector::new(2);
     let mut density = DensityMatrix::new(2);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -42,8 +42,8 @@ fn h0_h1() {
     let mut state = StateVector::new(2);
     let mut density = DensityMatrix::new(2);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -58,8 +58,8 @@ fn x0_x1() {
     let mut state = StateVector::new(2);
     let mut density = DensityMatrix::new(2);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -73,8 +73,8 @@ fn y1() {
     let mut state = StateVector::new(2);
     let mut density = DensityMatrix::new(2);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -89,8 +89,8 @@ fn h0_z1() {
     let mut state = StateVector::new(2);
     let mut density = DensityMatrix::new(2);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -9,8 +9,8 @@ fn h0() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -23,8 +23,8 @@ fn h1() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -37,8 +37,8 @@ fn h2() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -52,8 +52,8 @@ fn h0_cnot01() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -67,8 +67,8 @@ fn h0_cnot02() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -82,8 +82,8 @@ fn h2_cnot21() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -97,8 +97,8 @@ fn h2_cnot20() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -114,8 +114,8 @@ fn ghz_0() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -131,8 +131,8 @@ fn ghz_1() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
@@ -148,8 +148,8 @@ fn ghz_2() {
     let mut state = StateVector::new(3);
     let mut density = DensityMatrix::new(3);
 
-    program.run(&mut state);
-    program.run(&mut density);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
 
     assert_eq!(&density, &DensityMatrix::from(state))
 }
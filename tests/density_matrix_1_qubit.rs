@@ -15,13 +15,13 @@ fn x0() {
 
         let mut program = Program::new();
         program.rx(0, angle);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         let (c, s) = ((angle / 2.).cos(), (angle / 2.).sin());
-        let required_state = DensityMatrix::from(dmatrix![
+        let required_state = DensityMatrix::try_from(dmatrix![
             c!(c * c, 0.), c!(0.,c * s);
             c!(0., -c * s), c!(s * s, 0.);
-        ]);
+        ]).unwrap();
         assert_eq!(&required_state, &state)
     }
 }
@@ -37,14 +37,14 @@ fn y0() {
 
         let mut program = Program::new();
         program.ry(0, angle);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         let (c, s) = ((angle / 2.).cos(), (angle / 2.).sin());
 
-        let required_state = DensityMatrix::from(dmatrix![
+        let required_state = DensityMatrix::try_from(dmatrix![
             c!(c * c, 0.), c!(c * s, 0.);
             c!(c * s, 0.), c!(s * s, 0.);
-        ]);
+        ]).unwrap();
 
         assert_eq!(&required_state, &state)
     }
@@ -61,14 +61,14 @@ fn z0() {
 
         let mut program = Program::new();
         program.ry(0, angle);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         let (c, s) = ((angle / 2.).cos(), (angle / 2.).sin());
 
-        let required_state = DensityMatrix::from(dmatrix![
+        let required_state = DensityMatrix::try_from(dmatrix![
             c!(c * c, 0.), c!(c * s, 0.);
             c!(c * s, 0.), c!(s * s, 0.);
-        ]);
+        ]).unwrap();
 
         assert_eq!(&required_state, &state)
     }
@@ -85,10 +85,10 @@ fn xy_commutation() {
     let mut program = Program::new();
     program.x(0);
     program.y(0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
     other_program.z(0);
-    other_program.run(&mut other_state);
+    other_program.run(&mut other_state).unwrap();
 
     assert_eq!(&state, &other_state)
 }
@@ -105,10 +105,10 @@ fn xz_commutation() {
 
     program.rx(0, angle);
     program.rz(0, angle);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
     other_program.ry(0, 3. * angle);
-    other_program.run(&mut other_state);
+    other_program.run(&mut other_state).unwrap();
 
     assert_eq!(&state, &other_state)
 }
@@ -125,13 +125,13 @@ fn m0() {
 
         program.rx(0, angle);
         program.measure(0);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         let (c, s) = ((angle / 2.).cos(), (angle / 2.).sin());
 
-        let required_state = DensityMatrix::from(dmatrix![
+        let required_state = DensityMatrix::try_from(dmatrix![
         c!(c * c, 0.), c!(0., 0.);
-        c!(0., 0.), c!(s * s, 0.);]);
+        c!(0., 0.), c!(s * s, 0.);]).unwrap();
 
         assert_eq!(&required_state, &state)
     }
@@ -143,12 +143,12 @@ fn h0() {
     let mut program = Program::new();
 
     program.h(0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(1. / 2., 0.), c!(1. / 2., 0.);
         c!(1. / 2., 0.), c!(1. / 2., 0.);
-    ]);
+    ]).unwrap();
 
     assert_eq!(&required_state, &state)
 }
@@ -168,12 +168,12 @@ fn r0() {
         let omega = 2. * PI * range.gen::<Angle>();
 
         program.r(0, phi, theta, omega);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         other_program.rz(0, phi);
         other_program.ry(0, theta);
         other_program.rz(0, omega);
-        other_program.run(&mut other_state);
+        other_program.run(&mut other_state).unwrap();
 
         assert_eq!(&state, &other_state)
     }
@@ -189,10 +189,10 @@ fn s() {
 
     program.s(0);
     program.s(0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
     other_program.z(0);
-    other_program.run(&mut other_state);
+    other_program.run(&mut other_state).unwrap();
 
     assert_eq!(&state, &other_state)
 }
@@ -206,7 +206,7 @@ fn reset_all() {
     let mut state = DensityMatrix::new(1);
     let required_state = DensityMatrix::new(1);
 
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
     assert_eq!(state, required_state)
 }
 
@@ -223,8 +223,8 @@ fn purity() {
     let mut pure_state = DensityMatrix::new(1);
     let mut impure_state = DensityMatrix::new(1);
 
-    pure_program.run(&mut pure_state);
-    impure_program.run(&mut impure_state);
+    pure_program.run(&mut pure_state).unwrap();
+    impure_program.run(&mut impure_state).unwrap();
 
     assert!(pure_state.is_pure());
     assert!(!impure_state.is_pure());
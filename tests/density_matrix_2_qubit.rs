@@ -10,14 +10,14 @@ fn h0_cnot01() {
     let mut program = Program::new();
     program.h(0);
     program.cnot(0, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -28,14 +28,14 @@ fn h0_cnot10() {
 
     program.h(0);
     program.cnot(1, 0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0.5, 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
         c!(0.5, 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -46,14 +46,14 @@ fn h0_h1() {
 
     program.h(0);
     program.h(1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.);
         c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.);
         c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.);
         c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.), c!(0.25, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -64,14 +64,14 @@ fn x0_x1() {
 
     program.rx(0, PI);
     program.rx(1, PI);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(1., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -84,14 +84,14 @@ fn x0_x1_m0_m1() {
     program.rx(1, PI / 2.);
     program.measure(0);
     program.measure(1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0.25, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0.25, 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0.25, 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.25, 0.);
-    ]);
+    ]).unwrap();
 
     assert_eq!(&required_state, &state);
 }
@@ -102,14 +102,14 @@ fn y1() {
     let mut program = Program::new();
 
     program.ry(1, PI);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(1., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
 
     assert_eq!(&required_state, &state);
 }
@@ -121,14 +121,14 @@ fn h0_z1() {
 
     program.h(0);
     program.rz(0, PI);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
         c!(0.5, 0.), c!(-0.5, 0.), c!(0., 0.), c!(0., 0.);
         c!(-0.5, 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
         c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -141,7 +141,7 @@ fn iswap01() {
     let mut other_program = Program::new();
 
     program.iswap(0, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
     // reference implementation from https://qiskit.org/documentation/stubs/qiskit.circuit.library.iSwapGate.html
     other_program.s(0);
@@ -150,7 +150,7 @@ fn iswap01() {
     other_program.cnot(0, 1);
     other_program.cnot(1, 0);
     other_program.h(1);
-    other_program.run(&mut other_state);
+    other_program.run(&mut other_state).unwrap();
 
     assert_eq!(&other_state, &state);
 }
@@ -166,10 +166,10 @@ fn root_iswap01() {
 
     program.siswap(0, 1);
     program.siswap(0, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
     other_program.iswap(0, 1);
-    other_program.run(&mut other_state);
+    other_program.run(&mut other_state).unwrap();
 
     assert_eq!(&other_state, &state);
 }
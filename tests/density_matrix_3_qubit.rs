@@ -7,9 +7,9 @@ fn h0() {
     let mut state = DensityMatrix::new(3);
     let mut program = Program::new();
     program.h(0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0.5, 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -18,7 +18,7 @@ fn h0() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -28,9 +28,9 @@ fn h1() {
     let mut program = Program::new();
 
     program.h(1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0.5, 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -39,7 +39,7 @@ fn h1() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -49,9 +49,9 @@ fn h2() {
     let mut program = Program::new();
 
     program.h(2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -60,7 +60,7 @@ fn h2() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -71,9 +71,9 @@ fn h0_cnot01() {
 
     program.h(0);
     program.cnot(0, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -82,7 +82,7 @@ fn h0_cnot01() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -93,9 +93,9 @@ fn h0_cnot02() {
 
     program.h(0);
     program.cnot(0, 2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -104,7 +104,7 @@ fn h0_cnot02() {
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -115,9 +115,9 @@ fn h2_cnot21() {
 
     program.h(2);
     program.cnot(2, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -126,7 +126,7 @@ fn h2_cnot21() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -137,9 +137,9 @@ fn h2_cnot20() {
 
     program.h(2);
     program.cnot(2, 0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -148,7 +148,7 @@ fn h2_cnot20() {
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -160,9 +160,9 @@ fn ghz_0() {
     program.h(0);
     program.cnot(0, 1);
     program.cnot(0, 2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -171,7 +171,7 @@ fn ghz_0() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -183,9 +183,9 @@ fn ghz_1() {
     program.h(1);
     program.cnot(1, 0);
     program.cnot(1, 2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -194,7 +194,7 @@ fn ghz_1() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -206,9 +206,9 @@ fn ghz_2() {
     program.h(2);
     program.cnot(2, 1);
     program.cnot(2, 0);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -217,7 +217,7 @@ fn ghz_2() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -234,9 +234,9 @@ fn hhh_measure() {
     program.measure(1);
     program.measure(2);
 
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0.125, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0.125, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0.125, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -245,7 +245,7 @@ fn hhh_measure() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.125, 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.125, 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.125, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -268,9 +268,9 @@ fn deutsch_jozsa_balanced() {
     program.measure(0);
     program.measure(1);
 
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = DensityMatrix::from(dmatrix![
+    let required_state = DensityMatrix::try_from(dmatrix![
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
@@ -279,6 +279,6 @@ fn deutsch_jozsa_balanced() {
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.);
             c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(-0.5, 0.), c!(0., 0.), c!(0., 0.), c!(0., 0.), c!(0.5, 0.);
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
@@ -0,0 +1,105 @@
+use bra_ket::*;
+
+#[test]
+fn gates_are_packed_into_columns() {
+    let mut program = Program::new();
+    program.h(0);
+    program.x(2);
+    program.cnot(0, 1);
+    program.cz(2, 3);
+    program.rx(1, PI / 2.);
+    let expected = "\
+q0: ──┤H├──●─────────────
+           │
+q1: ───────⊕──┤RX(π/2)├──
+
+q2: ──┤X├──●─────────────
+           │
+q3: ───────●─────────────
+";
+    assert_eq!(program.to_string(), expected);
+}
+
+#[test]
+fn connectors_cross_other_wires() {
+    let mut program = Program::new();
+    let q = program.quantum_register("q", 3);
+    let c = program.classical_register("c", 2);
+    program.swap(q[0], q[2]);
+    program.y(q[1]);
+    program.measure_into(q[0], c[1]);
+    let expected = "\
+q[0]: ──×───────┤M├──
+        │        ║
+q[1]: ──┼──┤Y├───╫───
+        │        ║
+q[2]: ──×────────╫───
+                 ║
+   c: ═══════════╩═══
+                 1
+";
+    assert_eq!(program.to_string(), expected);
+}
+
+#[test]
+fn every_operation_is_drawn() {
+    let mut inner = Program::new();
+    inner.h(0);
+    inner.cnot(0, 1);
+    let bell = inner.define("bell");
+
+    let mut program = Program::new();
+    program.add_gates(vec![
+        Operations::S(0),
+        Operations::Sdg(1),
+        Operations::T(0),
+        Operations::SXdg(1),
+        Operations::CRZ(0, 1, -PI / 4.),
+        Operations::CPhase(1, 0, 0.25),
+        Operations::ISWAP(0, 1),
+        Operations::SISWAP(0, 1),
+        Operations::FSim(0, 1, PI, 3. * PI / 2.),
+        Operations::Givens(1, 0, 2. * PI / 3.),
+        Operations::ArbitaryTwo(0, 1, Matrix4x4::identity()),
+        Operations::U3(0, 0., PI, -PI / 16.),
+        Operations::MeasureX(0),
+        Operations::MeasureY(1),
+        Operations::Barrier,
+        Operations::ResetAll,
+        Operations::MeasureAll,
+    ]);
+    program.add_subcircuit(&bell, &[1, 0]);
+    let drawing = format!("{:1000}", program);
+    for label in [
+        "┤S├", "┤Sdg├", "┤T├", "┤SXdg├", "┤RZ(-π/4)├", "┤P(0.25)├", "┤ISWAP├", "┤SISWAP├", "┤FSim(π,3π/2)├",
+        "┤Givens(2π/3):0├", "┤U:1├", "┤U3(0,π,-π/16)├", "┤MX├", "┤MY├", "░", "|0>", "┤M├", "┤bell:1├",
+    ] {
+        assert!(drawing.contains(label), "{} is missing from\n{}", label, drawing);
+    }
+    assert_eq!(drawing.lines().count(), 3);
+}
+
+#[test]
+fn wide_circuits_are_wrapped() {
+    let mut program = Program::new();
+    for n in 0..30 {
+        program.rz(n % 2, n as Real);
+    }
+    let drawing = format!("{:60}", program);
+    assert!(drawing.lines().all(|line| line.chars().count() <= 60));
+    let q0: Vec<&str> = drawing.lines().filter(|line| line.starts_with("q0:")).collect();
+    assert_eq!(q0.len(), 3);
+    assert!(q0[0].ends_with('»') && q0[1].ends_with('»') && !q0[2].ends_with('»'));
+    assert!(!q0[0].contains('«') && q0[1].starts_with("q0: «") && q0[2].starts_with("q0: «"));
+    assert!(format!("{}", program).lines().all(|line| line.chars().count() <= 80));
+}
+
+#[test]
+fn programs_without_qubits_are_drawn() {
+    let mut program = Program::new();
+    program.barrier();
+    program.measure_all();
+    assert_eq!(format!("{}", program), format!("{}", Program::new()));
+    assert_eq!(program.to_svg(), Program::new().to_svg());
+    assert_eq!(program.to_quantikz(), Program::new().to_quantikz());
+}
@@ -0,0 +1,124 @@
+use bra_ket::*;
+
+/// The state |q2 q1 q0> = |011> in the little endian convention, made by flipping qubits 0 and 1.
+fn flipped() -> (Program, StateVector) {
+    let mut program = Program::new();
+    program.x(0);
+    program.x(1);
+    let mut state = StateVector::new(3);
+    program.run(&mut state).unwrap();
+    (program, state)
+}
+
+fn close(a: Complex, b: Complex) -> bool {
+    (a - b).norm_sqr() < 1e-12
+}
+
+#[test]
+fn amplitudes_by_label() {
+    let (_, mut state) = flipped();
+    let one = Complex::new(1., 0.);
+    assert_eq!(state.endianness, Endianness::Little);
+    assert!(close(state.amplitude("011").unwrap(), one));
+    assert!(close(state.amplitude(3).unwrap(), one));
+    assert!(close(state.amplitude("110").unwrap(), Complex::new(0., 0.)));
+
+    state.set_endianness(Endianness::Big);
+    assert!(close(state.amplitude("110").unwrap(), one));
+    assert!(close(state.amplitude(6).unwrap(), one));
+    assert!(close(state.amplitude(3).unwrap(), Complex::new(0., 0.)));
+
+    let density = DensityMatrix::from(state);
+    assert_eq!(density.endianness, Endianness::Big);
+    assert!(close(density.element("110", "110").unwrap(), one));
+    assert!(close(density.element(6, 6).unwrap(), one));
+}
+
+#[test]
+fn iterating_over_nonzero_amplitudes() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 2);
+    let mut state = StateVector::new(3);
+    program.run(&mut state).unwrap();
+
+    let labels: Vec<String> = state.amplitudes().map(|(label, _)| label).collect();
+    assert_eq!(labels, vec!["000", "101"]);
+    assert!(state.amplitudes().all(|(_, amplitude)| close(amplitude, Complex::new(1. / SQRT_2, 0.))));
+
+    let mut program = Program::new();
+    program.x(0);
+    program.run(&mut state).unwrap();
+    let labels: Vec<String> = state.amplitudes().map(|(label, _)| label).collect();
+    assert_eq!(labels, vec!["001", "100"]);
+
+    state.set_endianness(Endianness::Big);
+    let labels: Vec<String> = state.amplitudes().map(|(label, _)| label).collect();
+    assert_eq!(labels, vec!["100", "001"]);
+    assert!(state.to_string().contains("|100>"));
+}
+
+#[test]
+fn classical_values_follow_endianness() {
+    let (mut program, mut state) = flipped();
+    program.measure_all();
+    let c = program.classical_register("c", 3);
+    program.measure_register(&[0, 1, 2], &c);
+    state.reinitialise_all();
+    program.run(&mut state).unwrap();
+    assert_eq!(state.get_measured_overall_state().unwrap(), 0b011);
+    assert_eq!(state.get_measured_register(&c).unwrap(), 0b011);
+
+    state.set_endianness(Endianness::Big);
+    assert_eq!(state.get_measured_overall_state().unwrap(), 0b110);
+    assert_eq!(state.get_measured_register(&c).unwrap(), 0b110);
+}
+
+#[test]
+fn labels_round_trip() {
+    for endianness in [Endianness::Little, Endianness::Big] {
+        for index in 0..16 {
+            let label = endianness.bitstring(index, 4);
+            assert_eq!(label.as_str().index(4, endianness).unwrap(), index);
+            assert_eq!(u64::from_str_radix(&label, 2).unwrap().index(4, endianness).unwrap(), index);
+        }
+    }
+}
+
+#[test]
+fn invalid_labels() {
+    let state = StateVector::new(2);
+    assert!(matches!(state.amplitude("011"), Err(BraKetError::InvalidBasisLabel { .. })));
+    assert!(matches!(state.amplitude("0a"), Err(BraKetError::InvalidBasisLabel { .. })));
+    assert!(matches!(state.amplitude(4), Err(BraKetError::InvalidBasisLabel { .. })));
+
+    // too many qubits for the basis states to be indexed
+    assert!(matches!(1u64.index(64, Endianness::Little), Err(BraKetError::InvalidBasisLabel { .. })));
+    assert!(matches!("0".repeat(70).as_str().index(70, Endianness::Big), Err(BraKetError::InvalidBasisLabel { .. })));
+    assert!(matches!(StateVector::basis(64, 1), Err(BraKetError::InvalidBasisLabel { .. })));
+}
+
+#[test]
+fn qasm_registers_follow_endianness() {
+    let mut program = Program::new();
+    let a = program.quantum_register("a", 3);
+    let c = program.classical_register("c", 3);
+    program.x(a[0]);
+    program.measure_register(&a, &c);
+
+    // the first element of a big endian register is its most significant
+    let qasm = program.to_qasm_with_endianness(Endianness::Big);
+    assert!(qasm.lines().any(|line| line == "x a[2];"), "{}", qasm);
+    assert!(qasm.lines().any(|line| line == "measure a[2] -> c[2];"), "{}", qasm);
+    assert_eq!(Program::from_qasm_with_endianness(&qasm, Endianness::Big).unwrap().gates, program.gates);
+
+    // the register keeps its value in the export
+    let mut state = StateVector::new(3);
+    state.set_endianness(Endianness::Big);
+    program.run(&mut state).unwrap();
+    let mut exported = StateVector::new(3);
+    let imported = Program::from_qasm(&qasm).unwrap();
+    imported.run(&mut exported).unwrap();
+    assert_eq!(state.get_measured_register(&c).unwrap(), 0b100);
+    assert_eq!(exported.get_measured_register(&imported.classical_registers[0]).unwrap(), 0b100);
+}
@@ -0,0 +1,71 @@
+use nalgebra::{dmatrix, DMatrix};
+use bra_ket::*;
+
+#[test]
+fn running_on_too_few_qubits() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 3);
+
+    let mut state = StateVector::new(2);
+    let error = program.run(&mut state).unwrap_err();
+    assert_eq!(error, BraKetError::NotEnoughQubits { required: 4, available: 2 });
+    assert_eq!(&state, &StateVector::new(2));
+
+    let mut density = DensityMatrix::new(3);
+    assert_eq!(program.run(&mut density).unwrap_err(), BraKetError::NotEnoughQubits { required: 4, available: 3 });
+    assert_eq!(error.to_string(), "the program requires 4 qubits but the state has 2");
+}
+
+#[test]
+fn running_an_empty_program() {
+    let program = Program::new();
+    assert!(program.run(&mut StateVector::new(1)).is_ok());
+    assert!(program.run(&mut DensityMatrix::new(1)).is_ok());
+}
+
+#[test]
+fn reading_unmeasured_results() {
+    let mut program = Program::new();
+    program.x(1);
+    program.measure(1);
+    program.measure_into(1, 2);
+    let mut state = StateVector::new(2);
+    program.run(&mut state).unwrap();
+
+    assert_eq!(state.get_measured_qubit_state(1), Ok(true));
+    assert_eq!(state.get_measured_qubit_state(0), Err(BraKetError::QubitNotMeasured(0)));
+    assert_eq!(state.get_measured_qubit_state(5), Err(BraKetError::InvalidQubit { qubit: 5, number_of_qubits: 2 }));
+    assert_eq!(state.get_measured_overall_state(), Err(BraKetError::QubitNotMeasured(0)));
+    assert_eq!(state.get_classical_bit(2), Ok(true));
+    assert_eq!(state.get_classical_bit(0), Err(BraKetError::BitNotMeasured(0)));
+    assert_eq!(state.get_classical_bit(7), Err(BraKetError::BitNotMeasured(7)));
+}
+
+#[test]
+fn invalid_density_matrices() {
+    let o = c!(0., 0.);
+    let rectangular = DMatrix::from_element(2, 4, o);
+    assert_eq!(DensityMatrix::try_from(rectangular).unwrap_err(), BraKetError::NotSquare { rows: 2, columns: 4 });
+    let odd = DMatrix::from_diagonal_element(3, 3, c!(1. / 3., 0.));
+    assert_eq!(DensityMatrix::try_from(odd).unwrap_err(), BraKetError::NotPowerOfTwo(3));
+    let unnormalised = dmatrix![c!(1., 0.), o; o, c!(1., 0.)];
+    assert_eq!(DensityMatrix::try_from(unnormalised).unwrap_err(), BraKetError::NotNormalised(2.));
+}
+
+#[test]
+fn invalid_qubit_lists() {
+    let state = StateVector::new(3);
+    assert_eq!(state.reduced_density_matrix(&[0, 3]).unwrap_err(), BraKetError::InvalidQubit { qubit: 3, number_of_qubits: 3 });
+    assert_eq!(DensityMatrix::new(3).partial_trace(&[2, 2]).unwrap_err(), BraKetError::RepeatedQubit(2));
+    assert!(state.entanglement_entropy(&[4]).is_err());
+}
+
+#[test]
+fn kraus_operators_which_are_not_a_channel() {
+    let mut state = StateVector::new(2);
+    let half = Matrix2x2::identity().scale(0.5);
+    assert_eq!(state.single_qubit_kraus(&0, &[half]), Err(BraKetError::NotTracePreserving));
+    assert_eq!(DensityMatrix::new(2).two_qubit_kraus(&0, &1, &[Matrix4x4::identity(), Matrix4x4::identity()]), Err(BraKetError::NotTracePreserving));
+    assert_eq!(state.single_qubit_kraus(&1, &[Matrix2x2::identity()]), Ok(()));
+}
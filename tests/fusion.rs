@@ -0,0 +1,80 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use bra_ket::*;
+
+/// creates a seeded random program of single and two qubit gates with the odd measurement
+fn random_program(n: usize, number_of_gates: usize, seed: u64) -> Program {
+    let mut range = StdRng::seed_from_u64(seed);
+    let mut program = Program::new();
+    for _ in 0..number_of_gates {
+        let qubit = range.gen_range(0..n);
+        let other = (qubit + range.gen_range(1..n)) % n;
+        let angle = 2. * PI * range.gen::<Angle>();
+        match range.gen_range(0..10) {
+            0 => program.h(qubit),
+            1 => program.rx(qubit, angle),
+            2 => program.ry(qubit, angle),
+            3 => program.r(qubit, angle, 2. * angle, 3. * angle),
+            4 => program.s(qubit),
+            5 => program.cnot(qubit, other),
+            6 => program.cz(qubit, other),
+            7 => program.siswap(qubit, other),
+            8 => program.swap(qubit, other),
+            _ => program.add_gate(Operations::Barrier),
+        }
+    }
+    program
+}
+
+#[test]
+fn fused_state_vector() {
+    for seed in 0..10 {
+        let mut program = random_program(4, 40, seed);
+
+        program.set_fusion(Fusion::None);
+        let mut unfused_state = StateVector::new(4);
+        program.run(&mut unfused_state).unwrap();
+
+        for fusion in [Fusion::SingleQubit, Fusion::TwoQubit] {
+            program.set_fusion(fusion);
+            let mut fused_state = StateVector::new(4);
+            program.run(&mut fused_state).unwrap();
+            assert_eq!(&unfused_state, &fused_state)
+        }
+    }
+}
+
+#[test]
+fn fused_density_matrix() {
+    for seed in 0..10 {
+        let mut program = random_program(3, 30, seed);
+        program.measure(1);
+
+        program.set_fusion(Fusion::None);
+        let mut unfused_state = DensityMatrix::new(3);
+        program.run(&mut unfused_state).unwrap();
+
+        program.set_fusion(Fusion::TwoQubit);
+        let mut fused_state = DensityMatrix::new(3);
+        program.run(&mut fused_state).unwrap();
+        assert_eq!(&unfused_state, &fused_state)
+    }
+}
+
+#[test]
+fn fused_gate_count() {
+    let mut program = Program::new();
+    program.h(0);
+    program.h(1);
+    program.cnot(0, 1);
+    program.rz(1, PI / 4.);
+    program.cnot(0, 1);
+    program.h(0);
+    program.measure_all();
+
+    program.set_fusion(Fusion::SingleQubit);
+    assert_eq!(program.fused().gates.len(), 7);
+
+    program.set_fusion(Fusion::TwoQubit);
+    assert_eq!(program.fused().gates.len(), 2);
+}
@@ -0,0 +1,134 @@
+use nalgebra::dmatrix;
+use bra_ket::*;
+
+fn assert_equal(a: &CMatrix, b: &CMatrix) {
+    assert!((a - b).norm() < 1e-9, "{} != {}", a, b);
+}
+
+/// the unitary of a single gate, with two qubit gates applied as gate(1, 0) so that their first qubit
+/// is the most significant bit of the basis index, as in the textbook matrices
+fn unitary(gate: Operations, number_of_qubits: usize) -> CMatrix {
+    let mut program = Program::new();
+    program.add_gate(gate);
+    program.unitary(number_of_qubits).unwrap()
+}
+
+#[test]
+fn single_qubit_gates() {
+    let (o, l) = (c!(0., 0.), c!(1., 0.));
+    let e = |angle: Angle| c!(angle.cos(), angle.sin());
+    let half = |z: Complex| z * 0.5;
+
+    assert_equal(&unitary(Operations::T(0), 1), &dmatrix![l, o; o, e(PI / 4.)]);
+    assert_equal(&unitary(Operations::Tdg(0), 1), &dmatrix![l, o; o, e(-PI / 4.)]);
+    assert_equal(&unitary(Operations::Sdg(0), 1), &dmatrix![l, o; o, c!(0., -1.)]);
+    assert_equal(&unitary(Operations::SX(0), 1), &dmatrix![half(c!(1., 1.)), half(c!(1., -1.)); half(c!(1., -1.)), half(c!(1., 1.))]);
+    assert_equal(&unitary(Operations::SXdg(0), 1), &dmatrix![half(c!(1., -1.)), half(c!(1., 1.)); half(c!(1., 1.)), half(c!(1., -1.))]);
+    assert_equal(&unitary(Operations::Phase(0, 0.3), 1), &dmatrix![l, o; o, e(0.3)]);
+    assert_equal(&unitary(Operations::U1(0, 0.3), 1), &dmatrix![l, o; o, e(0.3)]);
+
+    let (theta, phi, lambda): (Angle, Angle, Angle) = (0.4, 1.1, -0.7);
+    let (c, s) = ((theta / 2.).cos(), (theta / 2.).sin());
+    let u3 = dmatrix![l * c, -e(lambda) * s; e(phi) * s, e(phi + lambda) * c];
+    assert_equal(&unitary(Operations::U3(0, theta, phi, lambda), 1), &u3);
+    let u2 = dmatrix![l, -e(lambda); e(phi), e(phi + lambda)] / c!(SQRT_2, 0.);
+    assert_equal(&unitary(Operations::U2(0, phi, lambda), 1), &u2);
+}
+
+#[test]
+fn single_qubit_relations() {
+    let square = |gate: Operations| {
+        let mut program = Program::new();
+        program.add_gate(gate.clone());
+        program.add_gate(gate);
+        program.unitary(1).unwrap()
+    };
+    assert_equal(&square(Operations::T(0)), &unitary(Operations::S(0), 1));
+    assert_equal(&square(Operations::SX(0)), &unitary(Operations::X(0), 1));
+    assert_equal(&square(Operations::Tdg(0)), &unitary(Operations::Sdg(0), 1));
+}
+
+#[test]
+fn controlled_gates() {
+    let (o, l, i) = (c!(0., 0.), c!(1., 0.), c!(0., 1.));
+    let h = c!(1. / SQRT_2, 0.);
+    let e = |angle: Angle| c!(angle.cos(), angle.sin());
+    let theta: Angle = 0.9;
+    let (c, s) = ((theta / 2.).cos(), (theta / 2.).sin());
+
+    assert_equal(&unitary(Operations::CY(1, 0), 2), &dmatrix![l, o, o, o; o, l, o, o; o, o, o, -i; o, o, i, o]);
+    assert_equal(&unitary(Operations::CH(1, 0), 2), &dmatrix![l, o, o, o; o, l, o, o; o, o, h, h; o, o, h, -h]);
+    assert_equal(&unitary(Operations::CRX(1, 0, theta), 2), &dmatrix![l, o, o, o; o, l, o, o; o, o, l * c, -i * s; o, o, -i * s, l * c]);
+    assert_equal(&unitary(Operations::CRY(1, 0, theta), 2), &dmatrix![l, o, o, o; o, l, o, o; o, o, l * c, -l * s; o, o, l * s, l * c]);
+    assert_equal(&unitary(Operations::CRZ(1, 0, theta), 2), &dmatrix![l, o, o, o; o, l, o, o; o, o, e(-theta / 2.), o; o, o, o, e(theta / 2.)]);
+    assert_equal(&unitary(Operations::CPhase(1, 0, theta), 2), &dmatrix![l, o, o, o; o, l, o, o; o, o, l, o; o, o, o, e(theta)]);
+}
+
+#[test]
+fn crz_builder_uses_its_angle() {
+    let mut program = Program::new();
+    program.crz(0, 1, 0.5);
+    assert_eq!(program.gates, vec![Operations::CRZ(0, 1, 0.5)]);
+}
+
+#[test]
+fn two_qubit_gates() {
+    let (o, l, i) = (c!(0., 0.), c!(1., 0.), c!(0., 1.));
+    let e = |angle: Angle| c!(angle.cos(), angle.sin());
+    let theta: Angle = 0.9;
+    let (c, s) = ((theta / 2.).cos(), (theta / 2.).sin());
+
+    assert_equal(&unitary(Operations::RXX(1, 0, theta), 2), &dmatrix![l * c, o, o, -i * s; o, l * c, -i * s, o; o, -i * s, l * c, o; -i * s, o, o, l * c]);
+    assert_equal(&unitary(Operations::RYY(1, 0, theta), 2), &dmatrix![l * c, o, o, i * s; o, l * c, -i * s, o; o, -i * s, l * c, o; i * s, o, o, l * c]);
+    assert_equal(&unitary(Operations::RZZ(1, 0, theta), 2), &dmatrix![e(-theta / 2.), o, o, o; o, e(theta / 2.), o, o; o, o, e(theta / 2.), o; o, o, o, e(-theta / 2.)]);
+
+    let phi: Angle = 0.4;
+    let (c, s) = (theta.cos(), theta.sin());
+    assert_equal(&unitary(Operations::FSim(1, 0, theta, phi), 2), &dmatrix![l, o, o, o; o, l * c, -i * s, o; o, -i * s, l * c, o; o, o, o, e(-phi)]);
+    assert_equal(&unitary(Operations::Givens(1, 0, theta), 2), &dmatrix![l, o, o, o; o, l * c, -l * s, o; o, l * s, l * c, o; o, o, o, l]);
+
+    let r = 1. / SQRT_2;
+    let ecr = dmatrix![o, l, o, i; l, o, -i, o; o, i, o, l; -i, o, l, o] * c!(r, 0.);
+    assert_equal(&unitary(Operations::ECR(1, 0), 2), &ecr);
+}
+
+#[test]
+fn backends_agree_on_new_gates() {
+    let mut program = Program::new();
+    program.h(0);
+    program.h(1);
+    program.t(0);
+    program.sx(1);
+    program.u3(2, 0.3, 0.2, 0.1);
+    program.cy(0, 2);
+    program.ch(1, 0);
+    program.crx(2, 1, 0.4);
+    program.cphase(0, 1, 0.7);
+    program.rxx(0, 2, 0.2);
+    program.ryy(1, 2, 0.5);
+    program.fsim(0, 1, 0.6, 0.3);
+    program.givens(2, 0, 0.8);
+    program.ecr(1, 2);
+
+    let mut state = StateVector::new(3);
+    let mut density = DensityMatrix::new(3);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
+    assert_eq!(&density, &DensityMatrix::from(state));
+
+    // the inverse and transpiled forms of the new gates are consistent with their matrices
+    assert_equal(&(program.clone() + program.inverse().unwrap()).unitary(3).unwrap(), &CMatrix::identity(8, 8));
+    let transpiled = program.transpile(&NativeGateSet::CZ).unitary(3).unwrap();
+    let u = program.unitary(3).unwrap();
+    let overlap = (u.adjoint() * &transpiled).trace() / c!(8., 0.);
+    assert!((overlap.norm_sqr() - 1.).abs() < 1e-6);
+}
+
+#[test]
+fn qft_is_the_discrete_fourier_transform() {
+    let n = 3;
+    let dim = 1 << n;
+    let omega = 2. * PI / dim as Real;
+    let dft = CMatrix::from_fn(dim, dim, |y, x| c!((omega * (x * y) as Real).cos(), (omega * (x * y) as Real).sin()) / c!((dim as Real).sqrt(), 0.));
+    assert_equal(&qft(n).unitary(n).unwrap(), &dft);
+}
@@ -21,7 +21,7 @@ fn grover_two_qubit() {
     program.measure_all();
 
     let mut state = StateVector::new(2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
     program.draw();
 
     println!("{:?}", state)
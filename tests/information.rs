@@ -0,0 +1,120 @@
+use bra_ket::*;
+
+fn close(a: Real, b: Real) -> bool {
+    (a - b).abs() < 1e-6
+}
+
+fn bell_pair() -> Program {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program
+}
+
+/// The mixed state of a qubit after an RY rotation that is measured.
+fn measured_rotation(theta: Angle) -> DensityMatrix {
+    let mut program = Program::new();
+    program.ry(0, theta);
+    program.measure(0);
+    let mut state = DensityMatrix::new(1);
+    program.run(&mut state).unwrap();
+    state
+}
+
+#[test]
+fn fidelity_of_pure_and_mixed_states() {
+    let mut a = StateVector::new(1);
+    let mut b = StateVector::new(1);
+    let mut rotate = Program::new();
+    rotate.ry(0, 1.1);
+    rotate.run(&mut b).unwrap();
+    let expected = (0.55 as Real).cos().powi(2);
+
+    assert!(close(a.fidelity(&b), expected));
+    assert!(close(a.fidelity(&DensityMatrix::from(b.clone())), expected));
+    assert!(close(DensityMatrix::from(a.clone()).fidelity(&b), expected));
+    assert!(close(DensityMatrix::from(a.clone()).fidelity(&DensityMatrix::from(b.clone())), expected));
+
+    // |0> against the mixture p |0><0| + (1 - p) |1><1|
+    let mixed = measured_rotation(1.1);
+    assert!(close(a.fidelity(&mixed), expected));
+    assert!(close(mixed.fidelity(&mixed), 1.));
+
+    // diagonal states have the classical fidelity of their distributions
+    let other = measured_rotation(0.4);
+    let (p, q) = (expected, (0.2 as Real).cos().powi(2));
+    let classical = ((p * q).sqrt() + ((1. - p) * (1. - q)).sqrt()).powi(2);
+    assert!(close(mixed.fidelity(&other), classical));
+    assert!(close(other.fidelity(&mixed), classical));
+
+    let mut flip = Program::new();
+    flip.x(0);
+    flip.run(&mut a).unwrap();
+    assert!(close(a.fidelity(&StateVector::new(1)), 0.));
+}
+
+#[test]
+fn trace_distance_between_states() {
+    let zero = StateVector::new(1);
+    let mut plus = StateVector::new(1);
+    let mut program = Program::new();
+    program.h(0);
+    program.run(&mut plus).unwrap();
+
+    let expected = (0.5 as Real).sqrt();
+    assert!(close(zero.trace_distance(&plus), expected));
+    assert!(close(zero.trace_distance(&DensityMatrix::from(plus.clone())), expected));
+    assert!(close(DensityMatrix::from(zero.clone()).trace_distance(&DensityMatrix::from(plus.clone())), expected));
+    assert!(close(plus.trace_distance(&plus), 0.));
+
+    // diagonal states are as far apart as their distributions
+    let (a, b) = (measured_rotation(1.1), measured_rotation(0.4));
+    let difference = (0.55 as Real).cos().powi(2) - (0.2 as Real).cos().powi(2);
+    assert!(close(a.trace_distance(&b), difference.abs()));
+}
+
+#[test]
+fn purity_and_entropies() {
+    let pure = DensityMatrix::from(StateVector::new(2));
+    assert!(close(pure.purity(), 1.));
+    assert!(close(pure.von_neumann_entropy(), 0.));
+    assert!(close(StateVector::new(2).purity(), 1.));
+
+    let mut program = Program::new();
+    program.h(0);
+    program.h(1);
+    program.measure_all();
+    let mut mixed = DensityMatrix::new(2);
+    program.run(&mut mixed).unwrap();
+    assert!(!mixed.is_pure());
+    assert!(close(mixed.purity(), 0.25));
+    assert!(close(mixed.von_neumann_entropy(), 2.));
+    for alpha in [0., 0.5, 1., 2., 5.] {
+        assert!(close(mixed.renyi_entropy(alpha), 2.));
+    }
+
+    // for a qubit with probabilities p, 1 - p
+    let state = measured_rotation(1.1);
+    let p = (0.55 as Real).cos().powi(2);
+    let shannon = -p * p.log2() - (1. - p) * (1. - p).log2();
+    assert!(close(state.von_neumann_entropy(), shannon));
+    assert!(close(state.renyi_entropy(1.), shannon));
+    assert!(close(state.renyi_entropy(2.), -state.purity().log2()));
+    assert!(state.renyi_entropy(2.) < shannon && shannon < state.renyi_entropy(0.5));
+}
+
+#[test]
+fn entanglement_entropy_of_bipartitions() {
+    let mut state = StateVector::new(3);
+    bell_pair().run(&mut state).unwrap();
+    assert!(close(state.entanglement_entropy(&[0]).unwrap(), 1.));
+    assert!(close(state.entanglement_entropy(&[1]).unwrap(), 1.));
+    assert!(close(state.entanglement_entropy(&[2]).unwrap(), 0.));
+    assert!(close(state.entanglement_entropy(&[0, 2]).unwrap(), 1.));
+    assert!(close(state.entanglement_entropy(&[0, 1]).unwrap(), 0.));
+
+    let density = DensityMatrix::from(state);
+    assert!(close(density.entanglement_entropy(&[1]).unwrap(), 1.));
+    assert!(close(density.entanglement_entropy(&[0, 1]).unwrap(), 0.));
+    assert!(close(density.entanglement_entropy(&[1, 2]).unwrap(), 1.));
+}
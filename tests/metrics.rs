@@ -0,0 +1,54 @@
+use bra_ket::*;
+
+#[test]
+fn ghz_depth() {
+    let n = 5;
+    let mut program = Program::new();
+    program.h(0);
+    for qubit in 0..n - 1 {
+        program.cnot(qubit, qubit + 1);
+    }
+    assert_eq!(program.depth(), n);
+    assert_eq!(program.two_qubit_depth(), n - 1);
+}
+
+#[test]
+fn qft_gate_counts() {
+    let n = 4;
+    let program = qft(n);
+    let counts = program.gate_counts();
+    assert_eq!(counts["H"], n);
+    assert_eq!(counts.values().sum::<usize>(), program.gates.len());
+}
+
+#[test]
+fn layers_partition_the_program() {
+    let program = qft(5);
+    let layers = program.layers();
+    assert_eq!(layers.len(), program.depth());
+    assert_eq!(layers.iter().map(|layer| layer.len()).sum::<usize>(), program.gates.len());
+    for layer in layers.iter() {
+        let mut qubits: Vec<usize> = vec![];
+        for gate in layer.iter() {
+            let mut gate_program = Program::new();
+            gate_program.add_gate((*gate).clone());
+            qubits.extend(gate_program.which_qubits());
+        }
+        let number_of_qubits = qubits.len();
+        qubits.sort();
+        qubits.dedup();
+        assert_eq!(qubits.len(), number_of_qubits);
+    }
+}
+
+#[test]
+fn barrier_separates_layers() {
+    let mut program = Program::new();
+    program.h(0);
+    program.add_gate(Operations::Barrier);
+    program.h(1);
+    program.measure_all();
+    assert_eq!(program.depth(), 3);
+    assert_eq!(program.layers().len(), 3);
+    assert_eq!(program.layers()[1], vec![Operations::H(1)]);
+}
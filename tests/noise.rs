@@ -0,0 +1,202 @@
+use bra_ket::*;
+
+fn assert_close(a: Real, b: Real, tolerance: Real) {
+    assert!((a - b).abs() < tolerance, "{} differs from {}", a, b);
+}
+
+#[test]
+fn amplitude_and_phase_damping_on_density_matrices() {
+    let mut program = Program::new();
+    program.x(0);
+    program.h(1);
+    let mut model = NoiseModel::new();
+    model.add_gate_error(Gates::Named("X".to_owned()), Channel::AmplitudeDamping(0.3)).unwrap();
+    model.add_gate_error(Gates::Named("H".to_owned()), Channel::PhaseDamping(0.36)).unwrap();
+
+    let mut state = DensityMatrix::new(2);
+    program.run_with_noise(&mut state, &model).unwrap();
+    let qubit_0 = state.partial_trace(&[1]).unwrap();
+    let qubit_1 = state.partial_trace(&[0]).unwrap();
+    assert_close(qubit_0.element(1, 1).unwrap().re, 0.7, 1e-12);
+    assert_close(qubit_1.element(0, 1).unwrap().re, 0.5 * 0.8, 1e-12);
+    assert_close(qubit_1.element(0, 0).unwrap().re, 0.5, 1e-12);
+    assert_close(state.probabilities().sum(), 1., 1e-12);
+}
+
+#[test]
+fn depolarizing_after_one_and_two_qubit_gates() {
+    let mut program = Program::new();
+    program.x(0);
+    let mut state = DensityMatrix::new(1);
+    program.run_with_noise(&mut state, &NoiseModel::uniform(0.2, 0., 0., 1).unwrap()).unwrap();
+    assert_close(state.probabilities()[1], 1. - 0.2 / 2., 1e-12);
+
+    let mut program = Program::new();
+    program.cnot(0, 1);
+    let mut state = DensityMatrix::new(3);
+    program.run_with_noise(&mut state, &NoiseModel::uniform(0.2, 0.4, 0., 3).unwrap()).unwrap();
+    // the two qubits are replaced by the maximally mixed state with probability 0.4, and qubit 2 untouched
+    let probabilities = state.probabilities();
+    assert_close(probabilities[0], 0.6 + 0.4 / 4., 1e-12);
+    for index in 1..4 {
+        assert_close(probabilities[index], 0.4 / 4., 1e-12);
+    }
+    assert_close(probabilities.iter().skip(4).sum(), 0., 1e-12);
+}
+
+#[test]
+fn errors_on_single_qubits() {
+    let mut program = Program::new();
+    program.x(0);
+    program.x(1);
+    program.cnot(2, 1);
+    let mut model = NoiseModel::new();
+    model.add_qubit_error(Gates::All, 1, Channel::AmplitudeDamping(1.)).unwrap();
+    let mut state = DensityMatrix::new(3);
+    program.run_with_noise(&mut state, &model).unwrap();
+    assert_close(state.probabilities()[0b001], 1., 1e-12);
+}
+
+#[test]
+fn measurements_and_resets_are_not_followed_by_gate_errors() {
+    let mut program = Program::new();
+    program.measure(0);
+    program.reset(1);
+    program.barrier();
+    let mut model = NoiseModel::new();
+    model.add_gate_error(Gates::All, Channel::Depolarizing(1.)).unwrap();
+    let mut state = DensityMatrix::new(2);
+    program.run_with_noise(&mut state, &model).unwrap();
+    assert_eq!(state, DensityMatrix::new(2));
+}
+
+#[test]
+fn sampled_trajectories_average_to_the_exact_channel() {
+    set_seed(7);
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    let mut model = NoiseModel::uniform(0.1, 0.3, 0., 2).unwrap();
+    model.add_qubit_error(Gates::TwoQubit, 1, Channel::AmplitudeDamping(0.2)).unwrap();
+
+    let mut exact = DensityMatrix::new(2);
+    program.run_with_noise(&mut exact, &model).unwrap();
+
+    let runs = 4000;
+    let mut average = [0.; 4];
+    for _ in 0..runs {
+        let mut state = StateVector::new(2);
+        program.run_with_noise(&mut state, &model).unwrap();
+        assert_close(state.probabilities().sum(), 1., 1e-9);
+        for (total, p) in average.iter_mut().zip(state.probabilities().iter()) {
+            *total += p / runs as Real;
+        }
+    }
+    for (sampled, exact) in average.iter().zip(exact.probabilities().iter()) {
+        assert_close(*sampled, *exact, 0.03);
+    }
+}
+
+#[test]
+fn readout_errors_flip_recorded_outcomes() {
+    set_seed(11);
+    let mut program = Program::new();
+    program.x(0);
+    program.measure_into(0, 0);
+    program.measure_into(1, 1);
+    let mut model = NoiseModel::new();
+    model.set_readout_error(0, 0., 1.).unwrap();
+    model.set_readout_error(1, 0.25, 0.).unwrap();
+
+    let runs = 2000;
+    let mut ones = 0;
+    for _ in 0..runs {
+        let mut state = StateVector::new(2);
+        program.run_with_noise(&mut state, &model).unwrap();
+        // the recorded outcome is flipped, but the qubit is not
+        assert!(!state.get_classical_bit(0).unwrap());
+        assert_close(state.probabilities()[0b01] + state.probabilities()[0b11], 1., 1e-12);
+        ones += state.get_classical_bit(1).unwrap() as usize;
+    }
+    assert_close(ones as Real / runs as Real, 0.25, 0.03);
+
+    // density matrices have no classical bits to record outcomes in, the measurements only dephase them
+    let mut state = DensityMatrix::new(2);
+    program.run_with_noise(&mut state, &model).unwrap();
+    assert_close(state.probabilities()[0b01] + state.probabilities()[0b11], 1., 1e-12);
+}
+
+#[test]
+fn noiseless_models_match_run() {
+    let program = qft(3);
+    let mut noisy = StateVector::new(3);
+    let mut noiseless = StateVector::new(3);
+    program.run_with_noise(&mut noisy, &NoiseModel::new()).unwrap();
+    program.run(&mut noiseless).unwrap();
+    assert_eq!(noisy, noiseless);
+}
+
+#[test]
+fn sampling_with_noise() {
+    let mut program = Program::new();
+    program.x(0);
+    program.measure_all();
+    let mut model = NoiseModel::new();
+    model.set_readout_error(0, 0., 0.25).unwrap();
+    model.add_gate_error(Gates::Named("X".to_owned()), Channel::AmplitudeDamping(0.2)).unwrap();
+    // the qubit is in |1> with probability 0.8 and is then read as 1 with probability 0.75
+    for counts in [
+        program.sample_with_noise(&StateVector::new(1), 4000, 3, &model).unwrap(),
+        program.sample_with_noise(&DensityMatrix::new(1), 4000, 3, &model).unwrap(),
+    ] {
+        assert_eq!(counts.values().sum::<usize>(), 4000);
+        assert_close(counts["1"] as Real / 4000., 0.6, 0.03);
+    }
+    assert_eq!(program.sample_with_noise(&StateVector::new(1), 100, 3, &NoiseModel::new()).unwrap(), program.sample(&StateVector::new(1), 100, 3).unwrap());
+}
+
+#[test]
+fn invalid_probabilities() {
+    let mut model = NoiseModel::new();
+    assert_eq!(model.add_gate_error(Gates::All, Channel::Depolarizing(1.5)).unwrap_err(), BraKetError::InvalidProbability(1.5));
+    assert!(model.add_qubit_error(Gates::All, 0, Channel::PhaseDamping(-0.1)).is_err());
+    assert!(model.set_readout_error(0, 0.1, 2.).is_err());
+    assert!(NoiseModel::uniform(0.1, 0.2, 1.5, 2).is_err());
+    assert_eq!(model, NoiseModel::new());
+}
+
+#[test]
+fn readout_models_in_noise_models() {
+    let mut model = NoiseModel::new();
+    model.set_readout_error(2, 0.1, 0.2).unwrap();
+    assert_eq!(model.readout_model().number_of_qubits(), 3);
+    assert_eq!(model.readout_probabilities(&2), Some([0.1, 0.2]));
+    assert_eq!(model.readout_probabilities(&0), None);
+
+    model.set_readout_model(ReadoutModel::uniform(2, 0.05, 0.1).unwrap()).unwrap();
+    assert_eq!(model.readout_probabilities(&1), Some([0.05, 0.1]));
+    assert_eq!(model.readout_probabilities(&2), None);
+    let mut correlated = ReadoutModel::new(2);
+    correlated.set_correlated_error(&[0, 1], RMatrix::identity(4, 4)).unwrap();
+    assert!(matches!(model.set_readout_model(correlated), Err(BraKetError::Unsupported(_))));
+}
+
+#[test]
+fn sampling_with_noise_keeps_the_measurement_generator() {
+    let mut program = Program::new();
+    program.h(0);
+    program.measure_all();
+    let model = NoiseModel::uniform(0.1, 0., 0.1, 1).unwrap();
+    let outcomes = |sample: bool| {
+        set_seed(5);
+        if sample {
+            program.sample_with_noise(&StateVector::new(1), 20, 9, &model).unwrap();
+        }
+        (0..20).map(|_| {
+            let mut state = StateVector::new(1);
+            program.run(&mut state).unwrap();
+            state.probabilities()[1] > 0.5
+        }).collect::<Vec<bool>>()
+    };
+    assert_eq!(outcomes(true), outcomes(false));
+}
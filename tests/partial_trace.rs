@@ -0,0 +1,62 @@
+use nalgebra::dmatrix;
+use bra_ket::*;
+
+#[test]
+fn bell_pair_is_locally_mixed() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    let mut state = StateVector::new(2);
+    program.run(&mut state).unwrap();
+
+    let (o, h) = (c!(0., 0.), c!(0.5, 0.));
+    let mixed = DensityMatrix::try_from(dmatrix![h, o; o, h]).unwrap();
+    assert_eq!(&state.reduced_density_matrix(&[0]).unwrap(), &mixed);
+    assert_eq!(&DensityMatrix::from(state).partial_trace(&[0]).unwrap(), &mixed);
+}
+
+#[test]
+fn product_state_factors() {
+    let mut a = Program::new();
+    a.ry(0, 0.3);
+    a.rz(0, 1.2);
+    let mut b = Program::new();
+    b.rx(0, 0.8);
+    let mut c = Program::new();
+    c.h(0);
+    c.t(0);
+
+    let program = a.clone() | b.clone() | c.clone();
+    let mut state = StateVector::new(3);
+    program.run(&mut state).unwrap();
+
+    for (qubit, single) in [(0, a), (1, b), (2, c)] {
+        let mut expected = DensityMatrix::new(1);
+        single.run(&mut expected).unwrap();
+        assert_eq!(&state.reduced_density_matrix(&[qubit]).unwrap(), &expected);
+    }
+}
+
+#[test]
+fn reduced_and_partial_trace_agree() {
+    let mut program = qft(4);
+    program.cnot(1, 3);
+    program.ry(2, 0.4);
+    let mut state = StateVector::new(4);
+    program.run(&mut state).unwrap();
+    let density = DensityMatrix::from(state.clone());
+
+    assert_eq!(&state.reduced_density_matrix(&[0, 2]).unwrap(), &density.partial_trace(&[1, 3]).unwrap());
+    assert_eq!(&state.reduced_density_matrix(&[0, 1, 2, 3]).unwrap(), &density);
+    assert_eq!(&density.partial_trace(&[]).unwrap(), &density);
+
+    // reordering the kept qubits swaps them in the reduced state
+    let mut swap = Program::new();
+    swap.swap(0, 1);
+    let mut swapped = state.reduced_density_matrix(&[2, 0]).unwrap();
+    swap.run(&mut swapped).unwrap();
+    assert_eq!(&swapped, &state.reduced_density_matrix(&[0, 2]).unwrap());
+
+    let reduced = density.partial_trace(&[0, 1, 3]).unwrap();
+    assert!((reduced.density_matrix.trace().re - 1.).abs() < 1e-9);
+}
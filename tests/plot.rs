@@ -0,0 +1,48 @@
+#![cfg(feature = "plot")]
+
+use std::process::Command;
+
+use bra_ket::*;
+
+fn gnuplot_installed() -> bool {
+    Command::new("gnuplot").arg("--version").output().is_ok_and(|output| output.status.success())
+}
+
+#[test]
+fn unsupported_formats() {
+    let state = StateVector::new(1);
+    let path = std::env::temp_dir().join("bra_ket_plot.jpg");
+    assert!(matches!(state.plot_probabilities(&path), Err(BraKetError::Unsupported(_))));
+    assert!(matches!(DensityMatrix::new(1).plot_heatmap(&path), Err(BraKetError::Unsupported(_))));
+    assert!(!path.exists());
+}
+
+#[test]
+fn plots_are_written() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program.ry(2, PI / 3.);
+    let mut state = StateVector::new(3);
+    program.run(&mut state).unwrap();
+    let density_matrix = DensityMatrix::from(state.clone());
+
+    let directory = std::env::temp_dir().join(format!("bra_ket_plots_{}", std::process::id()));
+    std::fs::create_dir_all(&directory).unwrap();
+    let results = [
+        ("probabilities.png", state.plot_probabilities(directory.join("probabilities.png"))),
+        ("bloch.svg", state.plot_bloch_spheres(directory.join("bloch.svg"))),
+        ("cityscape.png", density_matrix.plot_cityscape(directory.join("cityscape.png"))),
+        ("heatmap.svg", density_matrix.plot_heatmap(directory.join("heatmap.svg"))),
+    ];
+    for (file, result) in results {
+        match gnuplot_installed() {
+            true => {
+                assert!(result.is_ok(), "{}: {:?}", file, result);
+                assert!(std::fs::metadata(directory.join(file)).unwrap().len() > 0);
+            }
+            false => assert!(matches!(result, Err(BraKetError::Io(_))), "{}: {:?}", file, result),
+        }
+    }
+    std::fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,80 @@
+use nalgebra::dvector;
+use bra_ket::*;
+
+fn assert_close(a: &RVector, b: &RVector) {
+    assert!((a - b).norm() < 1e-9, "{} != {}", a, b);
+}
+
+fn ghz(n: usize) -> Program {
+    let mut program = Program::new();
+    program.h(0);
+    for qubit in 0..n - 1 {
+        program.cnot(qubit, qubit + 1);
+    }
+    program
+}
+
+#[test]
+fn ghz_distribution() {
+    let mut state = StateVector::new(3);
+    ghz(3).run(&mut state).unwrap();
+    let before = state.clone();
+
+    let mut expected = RVector::zeros(8);
+    expected[0] = 0.5;
+    expected[7] = 0.5;
+    assert_close(&state.probabilities(), &expected);
+    assert_close(&state.marginal_probabilities(&[2, 0]).unwrap(), &dvector![0.5, 0., 0., 0.5]);
+    assert_close(&state.marginal_probabilities(&[1]).unwrap(), &dvector![0.5, 0.5]);
+    // computing the distribution does not collapse the state
+    assert_eq!(&state, &before);
+}
+
+#[test]
+fn marginal_bit_order() {
+    let mut program = Program::new();
+    program.x(1);
+    let mut state = StateVector::new(3);
+    program.run(&mut state).unwrap();
+    assert_close(&state.marginal_probabilities(&[1, 2]).unwrap(), &dvector![0., 1., 0., 0.]);
+    assert_close(&state.marginal_probabilities(&[2, 1]).unwrap(), &dvector![0., 0., 1., 0.]);
+    assert_close(&state.marginal_probabilities(&[]).unwrap(), &dvector![1.]);
+}
+
+#[test]
+fn density_matrix_agrees_with_state_vector() {
+    let mut program = qft(4);
+    program.ry(2, 0.7);
+    program.cnot(2, 0);
+    let mut state = StateVector::new(4);
+    let mut density = DensityMatrix::new(4);
+    program.run(&mut state).unwrap();
+    program.run(&mut density).unwrap();
+
+    assert_close(&density.probabilities(), &state.probabilities());
+    assert_close(&density.marginal_probabilities(&[0, 3]).unwrap(), &state.marginal_probabilities(&[0, 3]).unwrap());
+    for qubit in 0..4 {
+        assert!((density.get_probability(&qubit) - state.get_probability(&qubit)).abs() < 1e-9);
+        assert!((density.get_expectation(&qubit) - state.get_expectation(&qubit)).abs() < 1e-9);
+    }
+    assert!((density.probabilities().sum() - 1.).abs() < 1e-9);
+}
+
+#[test]
+fn mixed_state_distribution() {
+    let mut program = Program::new();
+    program.h(0);
+    program.measure(0);
+    program.x(1);
+    let mut density = DensityMatrix::new(2);
+    program.run(&mut density).unwrap();
+    assert_close(&density.probabilities(), &dvector![0., 0., 0.5, 0.5]);
+}
+
+#[test]
+fn marginal_of_missing_qubit() {
+    let result = StateVector::new(2).marginal_probabilities(&[2]);
+    assert_eq!(result.unwrap_err(), BraKetError::InvalidQubit { qubit: 2, number_of_qubits: 2 });
+    let result = StateVector::new(2).marginal_probabilities(&[1, 1]);
+    assert_eq!(result.unwrap_err(), BraKetError::RepeatedQubit(1));
+}
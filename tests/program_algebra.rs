@@ -0,0 +1,139 @@
+use rand::{thread_rng, Rng};
+use bra_ket::*;
+
+/// creates a random program using every kind of unitary gate, acting on all n qubits
+fn random_program(n: usize, number_of_gates: usize) -> Program {
+    let mut range = thread_rng();
+    let mut program = Program::new();
+    for qubit in 0..n {
+        program.h(qubit);
+    }
+    for _ in 0..number_of_gates {
+        let qubit = range.gen_range(0..n);
+        let other = (qubit + range.gen_range(1..n)) % n;
+        let angle = 2. * PI * range.gen::<Angle>();
+        match range.gen_range(0..12) {
+            0 => program.h(qubit),
+            1 => program.s(qubit),
+            2 => program.y(qubit),
+            3 => program.rx(qubit, angle),
+            4 => program.r(qubit, angle, 0.3, 1.2),
+            5 => program.cnot(qubit, other),
+            6 => program.cz(qubit, other),
+            7 => program.swap(qubit, other),
+            8 => program.iswap(qubit, other),
+            9 => program.siswap(qubit, other),
+            10 => program.add_gate(Operations::CRZ(qubit, other, angle)),
+            _ => program.x(qubit),
+        }
+    }
+    program
+}
+
+fn assert_equal(a: &CMatrix, b: &CMatrix) {
+    assert!((a - b).norm() < 1e-6, "{} != {}", a, b);
+}
+
+#[test]
+fn inverse_undoes_program() {
+    let n = 3;
+    let program = random_program(n, 30);
+    let identity = (program.clone() + program.inverse().unwrap()).unitary(n).unwrap();
+    assert_equal(&identity, &CMatrix::identity(1 << n, 1 << n));
+    assert_equal(&program.inverse().unwrap().unitary(n).unwrap(), &program.unitary(n).unwrap().adjoint());
+}
+
+#[test]
+fn controlled_program() {
+    let n = 2;
+    let program = random_program(n, 10);
+    let u = program.unitary(n).unwrap();
+    let dim = 1 << n;
+    let mut expected = CMatrix::identity(2 * dim, 2 * dim);
+    expected.view_mut((dim, dim), (dim, dim)).copy_from(&u);
+    assert_equal(&program.controlled(n).unwrap().unitary(n + 1).unwrap(), &expected);
+}
+
+#[test]
+fn controlled_gates_are_exact() {
+    let mut program = Program::new();
+    program.cnot(0, 1);
+    program.swap(1, 0);
+    program.cz(1, 0);
+    program.siswap(0, 1);
+    let u = program.unitary(2).unwrap();
+    // the control is qubit 2, the highest bit of the basis index
+    let controlled = program.controlled(2).unwrap().unitary(3).unwrap();
+    for i in 0..8 {
+        for j in 0..8 {
+            let expected = match (i >> 2, j >> 2) {
+                (1, 1) => u[(i & 3, j & 3)],
+                (0, 0) if i == j => Complex::new(1., 0.),
+                _ => Complex::new(0., 0.),
+            };
+            assert!((controlled[(i, j)] - expected).norm_sqr() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn power_composes() {
+    let n = 3;
+    let program = random_program(n, 10);
+    let u = program.unitary(n).unwrap();
+    assert_equal(&program.power(3).unwrap().unitary(n).unwrap(), &(&u * &u * &u));
+    assert_equal(&program.power(-2).unwrap().unitary(n).unwrap(), &(&u * &u).adjoint());
+    assert_equal(&(program.power(2).unwrap() + program.power(-2).unwrap()).unitary(n).unwrap(), &CMatrix::identity(1 << n, 1 << n));
+    assert!(program.power(0).unwrap().gates.is_empty());
+}
+
+#[test]
+fn measurement_cannot_be_inverted() {
+    let mut program = Program::new();
+    program.h(0);
+    program.measure(0);
+    assert!(matches!(program.inverse(), Err(BraKetError::Unsupported(_))));
+    assert!(matches!(program.power(-1), Err(BraKetError::Unsupported(_))));
+    assert!(matches!(program.controlled(1), Err(BraKetError::Unsupported(_))));
+    assert!(matches!(program.unitary(1), Err(BraKetError::Unsupported(_))));
+}
+
+#[test]
+fn unitary_needs_every_qubit() {
+    let mut program = Program::new();
+    program.cnot(0, 2);
+    assert_eq!(program.unitary(2).err(), Some(BraKetError::NotEnoughQubits { required: 3, available: 2 }));
+}
+
+#[test]
+fn control_cannot_be_a_target() {
+    let mut program = Program::new();
+    program.cnot(0, 1);
+    assert_eq!(program.controlled(1).err(), Some(BraKetError::RepeatedQubit(1)));
+}
+
+#[test]
+fn shift_and_remap() {
+    let program = random_program(2, 10);
+    assert_eq!(program.shift(3).which_qubits(), vec![&3, &4]);
+    let swapped = program.remap(&[1, 0]);
+    let mut swaps = Program::new();
+    swaps.swap(0, 1);
+    assert_equal(&swapped.unitary(2).unwrap(), &(swaps.clone() + program + swaps).unitary(2).unwrap());
+}
+
+#[test]
+fn tensor_product() {
+    let a = random_program(2, 10);
+    let b = random_program(3, 10);
+    let u = (a.clone() | b.clone()).unitary(5).unwrap();
+    // the second program acts on the higher qubits, so is the left factor of the kronecker product
+    assert_equal(&u, &b.unitary(3).unwrap().kronecker(&a.unitary(2).unwrap()));
+    assert_eq!((a.clone() | b.clone()).number_of_qubits(), 5);
+}
+
+#[test]
+#[should_panic]
+fn remap_must_be_injective() {
+    random_program(2, 10).remap(&[1, 1]);
+}
@@ -0,0 +1,135 @@
+use nalgebra::ComplexField;
+use bra_ket::*;
+
+/// checks that two unitaries are equal up to a global phase
+fn assert_equal_up_to_phase(u: &CMatrix, v: &CMatrix) {
+    let overlap = (u.adjoint() * v).trace().abs() / u.nrows() as Real;
+    assert!((1. - overlap).abs() < 1e-6, "unitaries differ, overlap {}", overlap);
+}
+
+#[test]
+fn exported_programs_read_back() {
+    let mut inner = Program::new();
+    inner.h(0);
+    inner.crz(0, 1, 0.7);
+    let block = inner.define("block");
+
+    let mut program = Program::new();
+    let a = program.quantum_register("a", 2);
+    program.u3(a[0], 0.1, -0.2, 0.3);
+    program.iswap(a[0], a[1]);
+    program.add_subcircuit(&block, &[a[1], 2]);
+    program.fsim(2, 0, 0.4, -1.1);
+    program.sxdg(1);
+    program.r(2, 0.5, 0.6, 0.7);
+    program.cphase(2, 1, -PI / 3.);
+
+    let read = Program::from_qasm(&program.to_qasm()).unwrap();
+    assert_eq!(read.quantum_registers[0], program.quantum_registers[0]);
+    assert!(read.gates.iter().any(|gate| matches!(gate, Operations::Subcircuit(s, _) if s.name == "block")));
+    assert_equal_up_to_phase(&read.unitary(3).unwrap(), &program.unitary(3).unwrap());
+}
+
+#[test]
+fn expressions_and_parameterised_gates() {
+    let source = "
+        OPENQASM 2.0;
+        include \"qelib1.inc\";
+        // a rotation with its angle halved
+        gate half(theta) a { ry(theta / 2) a; }
+        qreg q[1];
+        half(2 * pi / 3 - -sin(0) + 2^2 - 4) q[0];
+        rz(-pi/4) q[0];
+    ";
+    let program = Program::from_qasm(source).unwrap();
+    assert_eq!(program.gates.len(), 2);
+    match (&program.gates[0], &program.gates[1]) {
+        (Operations::RY(0, theta), Operations::RZ(0, lambda)) => {
+            assert!((theta - PI / 3.).abs() < 1e-12);
+            assert!((lambda + PI / 4.).abs() < 1e-12);
+        }
+        gates => panic!("unexpected gates {:?}", gates),
+    }
+}
+
+#[test]
+fn registers_are_broadcast() {
+    let source = "
+        OPENQASM 2.0;
+        include \"qelib1.inc\";
+        qreg a[2];
+        qreg b[2];
+        creg c[2];
+        h a;
+        cx a, b;
+        cx a[0], b;
+        measure b -> c;
+    ";
+    let program = Program::from_qasm(source).unwrap();
+    let expected = vec![
+        Operations::H(0),
+        Operations::H(1),
+        Operations::CNOT(0, 2),
+        Operations::CNOT(1, 3),
+        Operations::CNOT(0, 2),
+        Operations::CNOT(0, 3),
+        Operations::MeasureInto(2, 0),
+        Operations::MeasureInto(3, 1),
+    ];
+    assert_eq!(program.gates, expected);
+    assert_eq!(program.classical_registers[0].bits, vec![0, 1]);
+}
+
+#[test]
+fn toffoli_is_decomposed() {
+    let program = Program::from_qasm("OPENQASM 2.0; include \"qelib1.inc\"; qreg q[3]; ccx q[0], q[1], q[2];").unwrap();
+    let mut toffoli = CMatrix::identity(8, 8);
+    toffoli.swap_columns(3, 7);
+    assert_equal_up_to_phase(&program.unitary(3).unwrap(), &toffoli);
+}
+
+#[test]
+fn errors_point_at_the_line() {
+    let line = |source: &str| match Program::from_qasm(source) {
+        Err(BraKetError::InvalidQasm { line, .. }) => line,
+        result => panic!("expected an OpenQASM error, got {:?}", result),
+    };
+    let header = "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[2];\n";
+    assert_eq!(line(&format!("{}foo q[0];", header)), 4);
+    assert_eq!(line(&format!("{}h r[0];", header)), 4);
+    assert_eq!(line(&format!("{}\nh q[2];", header)), 5);
+    assert_eq!(line(&format!("{}cx q[0], q[0];", header)), 4);
+    assert_eq!(line(&format!("{}rx(theta) q[0];", header)), 4);
+    assert_eq!(line(&format!("{}creg c[2];\nif (c == 1) x q[0];", header)), 5);
+    assert_eq!(line(&format!("{}h q[0]", header)), 4);
+    assert_eq!(line("OPENQASM 3.0;"), 1);
+}
+
+#[test]
+fn gates_only_call_gates_defined_before_them() {
+    let message = |source: &str| match Program::from_qasm(source) {
+        Err(BraKetError::InvalidQasm { message, .. }) => message,
+        result => panic!("expected an OpenQASM error, got {:?}", result),
+    };
+    let header = "OPENQASM 2.0;\ninclude \"qelib1.inc\";\nqreg q[1];\n";
+    assert_eq!(message(&format!("{}gate g(a) x {{ g(a) x; }}\ng(0.1) q[0];", header)), "the gate g calls itself");
+    assert_eq!(message(&format!("{}gate g x {{ g x; }}", header)), "the gate g calls itself");
+    assert_eq!(message(&format!("{}gate f x {{ g x; }}\ngate g x {{ f x; }}", header)), "the gate f calls g, which is not defined before it");
+
+    // a gate defined later under the name of a standard gate is not seen by the earlier ones
+    let source = format!("{}gate g(a) x {{ rx(a) x; }}\ngate rx(a) x {{ g(a) x; }}\nrx(0.5) q[0];", header);
+    let program = Program::from_qasm(&source).unwrap();
+    let mut expected = Program::new();
+    expected.rx(0, 0.5);
+    assert_eq!(program.gates, expected.gates);
+}
+
+#[test]
+fn register_sizes_are_bounded() {
+    let read = |declarations: &str| Program::from_qasm(&format!("OPENQASM 2.0;\n{}", declarations));
+    assert!(read(&format!("qreg q[{}];", MAX_REGISTER_SIZE)).is_ok());
+    assert!(matches!(read("qreg q[100000000000000];"), Err(BraKetError::InvalidQasm { line: 2, .. })));
+    assert!(matches!(read("creg c[100000000000000];"), Err(BraKetError::InvalidQasm { line: 2, .. })));
+    let halves = format!("qreg a[{}];\nqreg b[{}];", MAX_REGISTER_SIZE / 2, MAX_REGISTER_SIZE / 2 + 1);
+    assert!(matches!(read(&halves), Err(BraKetError::InvalidQasm { line: 3, .. })));
+}
@@ -16,8 +16,38 @@ fn quantum_fourier_transform_n_qubit() {
     let qft_program = qft(n);
 
     let mut state = StateVector::new(n);
-    init_program.run(&mut state);
+    init_program.run(&mut state).unwrap();
     let _init_state = state.clone();
-    qft_program.run(&mut state);
+    qft_program.run(&mut state).unwrap();
 }
 
+
+#[test]
+fn quantum_fourier_transform_reverses_the_qubits_once() {
+    // after the rotations the qft swaps n / 2 pairs of qubits, reversing their order once
+    for n in 1..7 {
+        let swaps: Vec<Operations> = qft(n).gates.into_iter().filter(|gate| matches!(gate, Operations::SWAP(_, _))).collect();
+        let expected: Vec<Operations> = (0..n / 2).map(|i| Operations::SWAP(i, n - i - 1)).collect();
+        assert_eq!(swaps, expected, "n = {}", n);
+    }
+}
+
+
+#[test]
+fn quantum_fourier_transform_of_basis_states() {
+    // the qft maps |x> to the sum over y of exp(2 pi i x y / 2^n) |y> / sqrt(2^n), which requires
+    // swapping n / 2 pairs of qubits after the rotations, reversing the order of the qubits once
+    for n in 1..5 {
+        let dimension = 1 << n;
+        for x in 0..dimension {
+            let mut state = StateVector::basis(n, x).unwrap();
+            qft(n).run(&mut state).unwrap();
+            for y in 0..dimension {
+                let phase = 2. * PI * (x * y) as Real / dimension as Real;
+                let expected = Complex::new(phase.cos(), phase.sin()) / (dimension as Real).sqrt();
+                let amplitude = state.amplitude(y as u64).unwrap();
+                assert!((amplitude - expected).norm_sqr() < 1e-20, "n = {}, x = {}, y = {}: {} != {}", n, x, y, amplitude, expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+
+use bra_ket::*;
+
+fn assert_close(a: Real, b: Real, tolerance: Real) {
+    assert!((a - b).abs() < tolerance, "{} is not close to {}", a, b);
+}
+
+fn bell() -> Program {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program.measure_all();
+    program
+}
+
+#[test]
+fn confusion_matrices_of_uncorrelated_and_correlated_errors() {
+    let mut model = ReadoutModel::new(3);
+    model.set_qubit_error(0, 0.1, 0.2).unwrap();
+    let confusion = model.confusion_matrix();
+    assert_close(confusion[(0b001, 0b000)], 0.1, 1e-12);
+    assert_close(confusion[(0b000, 0b001)], 0.2, 1e-12);
+    assert_close(confusion[(0b111, 0b111)], 0.8, 1e-12);
+
+    // qubits 1 and 2 are read as 11 one time in ten when both are in |0>
+    let mut correlated = RMatrix::identity(4, 4);
+    correlated[(0, 0)] = 0.9;
+    correlated[(3, 0)] = 0.1;
+    model.set_correlated_error(&[1, 2], correlated).unwrap();
+    let confusion = model.confusion_matrix();
+    assert_close(confusion[(0b110, 0b000)], 0.9 * 0.1, 1e-12);
+    assert_close(confusion[(0b010, 0b000)], 0., 1e-12);
+    for column in confusion.column_iter() {
+        assert_close(column.sum(), 1., 1e-12);
+    }
+}
+
+#[test]
+fn invalid_models() {
+    let mut model = ReadoutModel::new(2);
+    assert_eq!(model.set_qubit_error(0, 1.5, 0.).err(), Some(BraKetError::NotStochastic));
+    assert_eq!(model.set_qubit_error(2, 0.1, 0.).err(), Some(BraKetError::InvalidQubit { qubit: 2, number_of_qubits: 2 }));
+    assert_eq!(model.set_correlated_error(&[0, 0], RMatrix::identity(4, 4)).err(), Some(BraKetError::RepeatedQubit(0)));
+    assert_eq!(model.set_correlated_error(&[0, 1], RMatrix::identity(2, 2)).err(), Some(BraKetError::WrongDimension { expected: 4, found: 2 }));
+
+    let mut singular = RMatrix::from_element(2, 2, 0.5);
+    model.set_correlated_error(&[0], singular.clone()).unwrap();
+    let counts = BTreeMap::from([("00".to_owned(), 10)]);
+    assert_eq!(model.mitigate_by_inversion(&counts, Endianness::Little).err(), Some(BraKetError::NotInvertible));
+    singular[(0, 0)] = 0.4;
+    assert!(model.set_correlated_error(&[1], singular).is_err());
+}
+
+#[test]
+fn applying_errors_to_counts() {
+    let counts = bell().sample(&StateVector::new(2), 20000, 1).unwrap();
+    let model = ReadoutModel::uniform(2, 0.05, 0.1).unwrap();
+    let noisy = model.apply(&counts, 2, Endianness::Little).unwrap();
+    assert_eq!(noisy.values().sum::<usize>(), 20000);
+    // |00> is read as 01 with probability 0.95 * 0.05 and |11> with probability 0.1 * 0.9
+    let expected = 0.5 * 0.95 * 0.05 + 0.5 * 0.1 * 0.9;
+    assert_close(noisy["01"] as Real / 20000., expected, 0.01);
+    assert_eq!(model.apply(&counts, 2, Endianness::Little).unwrap(), noisy);
+    assert_eq!(ReadoutModel::new(2).apply(&counts, 2, Endianness::Little).unwrap(), counts);
+}
+
+#[test]
+fn calibration_circuits_estimate_the_model() {
+    let model = ReadoutModel::uniform(2, 0.05, 0.1).unwrap();
+    let circuits = calibration_circuits(2);
+    assert_eq!(circuits.len(), 4);
+    assert!(circuits.iter().all(|circuit| circuit.number_of_qubits() == 2));
+    assert!(circuits[0].to_qasm().contains("qreg q[2];"));
+    let counts: Vec<BTreeMap<String, usize>> = circuits
+        .iter()
+        .enumerate()
+        .map(|(n, circuit)| model.apply(&circuit.sample(&StateVector::new(2), 50000, 0).unwrap(), n as u64, Endianness::Little).unwrap())
+        .collect();
+    let estimated = ReadoutModel::from_calibration(&counts, Endianness::Little).unwrap();
+    let difference = estimated.confusion_matrix() - model.confusion_matrix();
+    assert!(difference.amax() < 0.01);
+
+    let [zeros, ones] = tensored_calibration_circuits(2);
+    assert_eq!((zeros.number_of_qubits(), ones.number_of_qubits()), (2, 2));
+    let zeros = model.apply(&zeros.sample(&StateVector::new(2), 50000, 0).unwrap(), 5, Endianness::Little).unwrap();
+    let ones = model.apply(&ones.sample(&StateVector::new(2), 50000, 0).unwrap(), 6, Endianness::Little).unwrap();
+    let estimated = ReadoutModel::from_tensored_calibration(&zeros, &ones, Endianness::Little).unwrap();
+    let difference = estimated.confusion_matrix() - model.confusion_matrix();
+    assert!(difference.amax() < 0.01);
+}
+
+#[test]
+fn mitigation_recovers_the_ideal_distribution() {
+    let mut correlated = RMatrix::identity(4, 4);
+    correlated[(0, 3)] = 0.05;
+    correlated[(3, 3)] = 0.95;
+    let mut model = ReadoutModel::new(2);
+    model.set_correlated_error(&[0, 1], correlated).unwrap();
+    let uncorrelated = ReadoutModel::uniform(2, 0.08, 0.15).unwrap();
+    for model in [model, uncorrelated] {
+        let counts = bell().sample(&StateVector::new(2), 40000, 3).unwrap();
+        let noisy = model.apply(&counts, 4, Endianness::Big).unwrap();
+        let inverted = model.mitigate_by_inversion(&noisy, Endianness::Big).unwrap();
+        assert_close(inverted.values().sum(), 1., 1e-9);
+        let least_squares = model.mitigate_by_least_squares(&noisy, Endianness::Big).unwrap();
+        assert_close(least_squares.values().sum(), 1., 1e-9);
+        assert!(least_squares.values().all(|p| *p >= 0.));
+        for corrected in [inverted, least_squares] {
+            assert_close(corrected["00"], 0.5, 0.02);
+            assert_close(corrected["11"], 0.5, 0.02);
+            assert_close(corrected.get("01").copied().unwrap_or(0.), 0., 0.02);
+        }
+    }
+}
+
+#[test]
+fn least_squares_removes_negative_quasi_probabilities() {
+    let model = ReadoutModel::uniform(1, 0.2, 0.2).unwrap();
+    // fewer ones than the errors alone would give, so inversion makes the probability of 1 negative
+    let counts = BTreeMap::from([("0".to_owned(), 90), ("1".to_owned(), 10)]);
+    let inverted = model.mitigate_by_inversion(&counts, Endianness::Little).unwrap();
+    assert!(inverted["1"] < 0.);
+    let least_squares = model.mitigate_by_least_squares(&counts, Endianness::Little).unwrap();
+    assert_close(least_squares["0"], 1., 1e-6);
+    assert!(least_squares.get("1").is_none_or(|p| p.abs() < 1e-6));
+}
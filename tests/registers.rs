@@ -0,0 +1,122 @@
+use bra_ket::*;
+
+#[test]
+fn registers_are_allocated_after_each_other() {
+    let mut program = Program::new();
+    let a = program.quantum_register("a", 2);
+    let b = program.quantum_register("b", 3);
+    assert_eq!(a.qubits, vec![0, 1]);
+    assert_eq!(&b[..], &[2, 3, 4]);
+    assert_eq!(program.number_of_qubits(), 5);
+    assert_eq!(program.qubit_name(3), Some(("b", 1)));
+
+    let c = program.classical_register("c", 2);
+    program.measure_into(0, 5);
+    let d = program.classical_register("d", 1);
+    assert_eq!(c.bits, vec![0, 1]);
+    assert_eq!(d.bits, vec![6]);
+}
+
+#[test]
+#[should_panic]
+fn register_names_are_unique() {
+    let mut program = Program::new();
+    program.quantum_register("a", 2);
+    program.quantum_register("a", 1);
+}
+
+#[test]
+fn measure_register_into_named_bits() {
+    let mut program = Program::new();
+    let a = program.quantum_register("a", 3);
+    let c = program.classical_register("c", 3);
+    program.apply_each(&a[1..], Operations::X);
+    program.measure_register(&a, &c);
+
+    let mut state = StateVector::new(3);
+    program.run(&mut state).unwrap();
+    assert_eq!(state.get_measured_register(&c).unwrap(), 0b110);
+    assert!(!state.get_classical_bit(c[0]).unwrap());
+}
+
+#[test]
+fn measure_bell_pair() {
+    let mut program = Program::new();
+    let a = program.quantum_register("a", 1);
+    let b = program.quantum_register("b", 1);
+    let c = program.classical_register("c", 2);
+    program.h(a[0]);
+    program.apply_pairwise(&a, &b, Operations::CNOT);
+    program.measure_register(&[a[0], b[0]], &c);
+
+    for _ in 0..10 {
+        let mut state = StateVector::new(2);
+        program.run(&mut state).unwrap();
+        let value = state.get_measured_register(&c).unwrap();
+        assert!(value == 0 || value == 3);
+    }
+
+    // the density matrix has no classical bits to record outcomes in, so running it only dephases it,
+    // but the program can be sampled
+    let mut state = DensityMatrix::new(2);
+    program.run(&mut state).unwrap();
+    assert_eq!(state.probabilities().iter().map(|p| (p * 2.).round() as usize).collect::<Vec<_>>(), vec![1, 0, 0, 1]);
+    let counts = program.sample(&DensityMatrix::new(2), 100, 0).unwrap();
+    assert!(counts.keys().all(|label| label == "00" || label == "11"));
+}
+
+#[test]
+fn registers_follow_composition() {
+    let mut a = Program::new();
+    let x = a.quantum_register("x", 2);
+    let c = a.classical_register("c", 2);
+    a.measure_register(&x, &c);
+    let mut b = Program::new();
+    let y = b.quantum_register("y", 1);
+    let d = b.classical_register("d", 1);
+    b.measure_register(&y, &d);
+
+    let program = a.clone() | b;
+    assert_eq!(program.qubit_name(2), Some(("y", 0)));
+    assert_eq!(program.classical_bit_name(2), Some(("d", 0)));
+    assert_eq!(program.gates.last(), Some(&Operations::MeasureInto(2, 2)));
+
+    let shifted = a.shift(3);
+    assert_eq!(shifted.quantum_registers[0].qubits, vec![3, 4]);
+    assert_eq!((a.clone() + a).quantum_registers.len(), 1);
+}
+
+#[test]
+fn qasm_export_uses_register_names() {
+    let mut program = Program::new();
+    let a = program.quantum_register("a", 2);
+    let c = program.classical_register("c", 2);
+    program.h(a[0]);
+    program.cnot(a[0], a[1]);
+    program.h(2);
+    program.measure_register(&a, &c);
+    program.measure(2);
+
+    let qasm = program.to_qasm();
+    for line in [
+        "qreg a[2];",
+        "qreg q[1];",
+        "creg c[2];",
+        "creg c_[3];",
+        "cx a[0],a[1];",
+        "h q[0];",
+        "measure a[1] -> c[1];",
+        "measure q[0] -> c_[2];",
+    ] {
+        assert!(qasm.lines().any(|l| l == line), "{} missing from\n{}", line, qasm);
+    }
+}
+
+#[test]
+fn qasm_export_skips_empty_barriers() {
+    let mut program = Program::new();
+    program.barrier();
+    let qasm = program.to_qasm();
+    assert!(!qasm.contains("barrier"), "{}", qasm);
+    assert!(Program::from_qasm(&qasm).is_ok());
+}
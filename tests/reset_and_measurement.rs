@@ -0,0 +1,95 @@
+use nalgebra::{dmatrix, dvector};
+use rand::{thread_rng, Rng};
+use bra_ket::*;
+
+#[test]
+fn reset_single_qubit_state_vector() {
+    let mut range = thread_rng();
+    for _ in 0..10 {
+        let angle = PI * range.gen::<Angle>();
+        let mut program = Program::new();
+        program.ry(0, angle);
+        program.reset(0);
+
+        let mut state = StateVector::new(1);
+        program.run(&mut state).unwrap();
+        assert_eq!(&state, &StateVector::try_from(dvector![c!(1., 0.), c!(0., 0.)]).unwrap());
+    }
+}
+
+#[test]
+fn reset_entangled_qubit() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program.reset(0);
+
+    // the density matrix is left with qubit 1 maximally mixed
+    let mut density = DensityMatrix::new(2);
+    program.run(&mut density).unwrap();
+    let (o, h) = (c!(0., 0.), c!(0.5, 0.));
+    let expected = dmatrix![h, o, o, o; o, o, o, o; o, o, h, o; o, o, o, o];
+    assert_eq!(&density, &DensityMatrix::try_from(expected).unwrap());
+
+    // the state vector is left in one of the two branches
+    for _ in 0..10 {
+        let mut state = StateVector::new(2);
+        program.run(&mut state).unwrap();
+        let (p0, p1) = (state.state_vector[0].norm_sqr(), state.state_vector[2].norm_sqr());
+        assert!((p0 - 1.).abs() < 1e-9 || (p1 - 1.).abs() < 1e-9);
+    }
+}
+
+#[test]
+fn ancilla_reuse() {
+    let mut program = Program::new();
+    program.x(0);
+    for bit in 0..3 {
+        program.cnot(0, 1);
+        program.measure_into(1, bit);
+        program.reset(1);
+    }
+    let mut state = StateVector::new(2);
+    program.run(&mut state).unwrap();
+    assert_eq!(state.classical_bits, vec![Some(true); 3]);
+    assert!((state.state_vector[1].norm_sqr() - 1.).abs() < 1e-9);
+}
+
+#[test]
+fn measure_in_x_and_y_bases() {
+    for _ in 0..10 {
+        let mut program = Program::new();
+        program.h(0);
+        program.x(1);
+        program.h(1);
+        program.h(2);
+        program.s(2);
+        program.measure_x(0);
+        program.measure_x(1);
+        program.measure_y(2);
+
+        let mut state = StateVector::new(3);
+        program.run(&mut state).unwrap();
+        assert!(!state.get_measured_qubit_state(0).unwrap());
+        assert!(state.get_measured_qubit_state(1).unwrap());
+        assert!(!state.get_measured_qubit_state(2).unwrap());
+
+        // measuring an eigenstate leaves it unchanged
+        let mut expected = StateVector::new(3);
+        program.gates.truncate(5);
+        program.run(&mut expected).unwrap();
+        assert_eq!(&state, &expected);
+    }
+}
+
+#[test]
+fn measure_x_dephases_density_matrix() {
+    let mut program = Program::new();
+    program.measure_x(0);
+
+    // |0> is an equal mixture of |+> and |->, which is maximally mixed
+    let mut density = DensityMatrix::new(1);
+    program.run(&mut density).unwrap();
+    let (o, h) = (c!(0., 0.), c!(0.5, 0.));
+    assert_eq!(&density, &DensityMatrix::try_from(dmatrix![h, o; o, h]).unwrap());
+}
@@ -0,0 +1,117 @@
+use nalgebra::ComplexField;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use bra_ket::*;
+
+/// creates a random program of single and two qubit gates acting on arbitrary pairs of qubits
+fn random_program(range: &mut StdRng, n: usize, number_of_gates: usize) -> Program {
+    let mut program = Program::new();
+    for _ in 0..number_of_gates {
+        let qubit = range.gen_range(0..n);
+        let other = (qubit + range.gen_range(1..n)) % n;
+        match range.gen_range(0..5) {
+            0 => program.ry(qubit, 2. * PI * range.gen::<Angle>()),
+            1 => program.h(qubit),
+            2 => program.cnot(qubit, other),
+            3 => program.cz(qubit, other),
+            _ => program.siswap(qubit, other),
+        }
+    }
+    program
+}
+
+/// checks every two qubit gate in the program acts on coupled qubits
+fn assert_routed(program: &Program, coupling_map: &CouplingMap) {
+    for gate in program.gates.iter() {
+        let mut single_gate = Program::new();
+        single_gate.add_gate(gate.clone());
+        if let [a, b] = single_gate.which_qubits()[..] {
+            assert!(coupling_map.are_connected(*a, *b), "{:?} acts on uncoupled qubits", gate);
+        }
+    }
+}
+
+/// checks the routed program produces the same state as the original, once the layouts are accounted for
+fn assert_equivalent(range: &mut StdRng, program: &Program, routing: &Routing, number_of_qubits: usize) {
+    let mut preparation = Program::new();
+    let mut routed_preparation = Program::new();
+    for qubit in 0..number_of_qubits {
+        let (phi, theta, omega) = (range.gen(), range.gen(), range.gen());
+        preparation.r(qubit, phi, theta, omega);
+        routed_preparation.r(routing.initial_layout[qubit], phi, theta, omega);
+    }
+
+    let mut state = StateVector::new(number_of_qubits);
+    (preparation + program.clone()).run(&mut state).unwrap();
+    let mut routed_state = StateVector::new(number_of_qubits);
+    (routed_preparation + routing.program.clone()).run(&mut routed_state).unwrap();
+
+    for n in 0..1 << number_of_qubits {
+        // the physical basis state corresponding to the logical basis state n
+        let m: usize = (0..number_of_qubits)
+            .map(|l| ((n >> l) & 1) << routing.final_layout[l])
+            .sum();
+        assert!((state.state_vector[n] - routed_state.state_vector[m]).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn line() {
+    let mut range = StdRng::seed_from_u64(28);
+    let coupling_map = CouplingMap::line(5);
+    for _ in 0..5 {
+        let program = random_program(&mut range, 5, 30);
+        let routing = program.route(&coupling_map).unwrap();
+        assert_routed(&routing.program, &coupling_map);
+        assert_equivalent(&mut range, &program, &routing, 5);
+    }
+}
+
+#[test]
+fn grid() {
+    let mut range = StdRng::seed_from_u64(29);
+    let coupling_map = CouplingMap::grid(2, 3);
+    for _ in 0..5 {
+        let program = random_program(&mut range, 6, 30);
+        let routing = program.route(&coupling_map).unwrap();
+        assert_routed(&routing.program, &coupling_map);
+        assert_equivalent(&mut range, &program, &routing, 6);
+    }
+}
+
+#[test]
+fn heavy_hex() {
+    let coupling_map = CouplingMap::heavy_hex(2, 3);
+    let program = random_program(&mut StdRng::seed_from_u64(30), 6, 40);
+    let routing = program.route(&coupling_map).unwrap();
+    assert_routed(&routing.program, &coupling_map);
+    assert_eq!(routing.final_layout.len(), 12);
+}
+
+#[test]
+fn no_swaps_needed() {
+    let mut program = Program::new();
+    program.h(0);
+    for n in 0..4 {
+        program.cnot(n, n + 1);
+    }
+    let routing = program.route(&CouplingMap::line(5)).unwrap();
+    assert_eq!(routing.number_of_swaps, 0);
+    assert_eq!(routing.program.gates.len(), program.gates.len());
+}
+
+#[test]
+fn invalid_coupling_maps_and_programs() {
+    assert_eq!(CouplingMap::new(3, vec![(0, 3)]), Err(BraKetError::InvalidQubit { qubit: 3, number_of_qubits: 3 }));
+    assert_eq!(CouplingMap::new(3, vec![(1, 1)]), Err(BraKetError::RepeatedQubit(1)));
+
+    let mut program = Program::new();
+    program.cnot(0, 3);
+    assert_eq!(program.route(&CouplingMap::line(3)), Err(BraKetError::NotEnoughQubits { required: 4, available: 3 }));
+
+    let disconnected = CouplingMap::new(4, vec![(0, 1), (2, 3)]).unwrap();
+    let mut program = Program::new();
+    program.cnot(0, 1);
+    program.cnot(1, 2);
+    assert!(matches!(program.route(&disconnected), Err(BraKetError::Unsupported(_))));
+}
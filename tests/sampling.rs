@@ -0,0 +1,78 @@
+use bra_ket::*;
+
+fn bell() -> Program {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program
+}
+
+#[test]
+fn counts_of_final_measurements() {
+    let mut program = bell();
+    program.x(2);
+    program.measure(0);
+    program.measure(1);
+    let counts = program.sample(&StateVector::new(3), 1000, 7).unwrap();
+    assert_eq!(counts.keys().collect::<Vec<_>>(), vec!["00", "11"]);
+    assert_eq!(counts.values().sum::<usize>(), 1000);
+    assert!(counts["00"] > 400 && counts["11"] > 400);
+    assert_eq!(program.sample(&StateVector::new(3), 1000, 7).unwrap(), counts);
+    assert_eq!(program.sample(&DensityMatrix::new(3), 1000, 7).unwrap(), counts);
+}
+
+#[test]
+fn counts_are_labelled_by_classical_bits() {
+    let mut program = Program::new();
+    let q = program.quantum_register("q", 2);
+    let c = program.classical_register("c", 3);
+    program.x(q[0]);
+    program.measure_register(&q, &c[1..]);
+
+    let mut state = StateVector::new(2);
+    assert_eq!(program.sample(&state, 10, 0).unwrap().into_iter().collect::<Vec<_>>(), vec![("010".to_owned(), 10)]);
+    state.set_endianness(Endianness::Big);
+    assert_eq!(program.sample(&state, 10, 0).unwrap().into_iter().collect::<Vec<_>>(), vec![("010".to_owned(), 10)]);
+
+    // without measurements every qubit is measured at the end
+    let mut program = Program::new();
+    program.x(0);
+    assert_eq!(program.sample(&state, 5, 0).unwrap().into_iter().collect::<Vec<_>>(), vec![("10".to_owned(), 5)]);
+}
+
+#[test]
+fn mid_circuit_measurements_cannot_be_sampled() {
+    let mut program = bell();
+    program.measure(0);
+    program.x(0);
+    assert!(matches!(program.sample(&StateVector::new(2), 10, 0), Err(BraKetError::Unsupported(_))));
+    assert!(matches!(bell().sample(&StateVector::new(1), 10, 0), Err(BraKetError::NotEnoughQubits { .. })));
+}
+
+#[test]
+fn final_measurements_are_removed() {
+    let mut program = bell();
+    program.measure(1);
+    program.x(1);
+    program.measure_all();
+    program.measure(0);
+    let expected = [&bell().gates[..], &[Operations::Measure(1), Operations::X(1)]].concat();
+    assert_eq!(program.without_final_measurements().gates, expected);
+}
+
+#[test]
+fn seeded_measurements_are_reproducible() {
+    let mut program = Program::new();
+    for qubit in 0..8 {
+        program.h(qubit);
+    }
+    program.measure_all();
+    let outcome = |seed| {
+        set_seed(seed);
+        let mut state = StateVector::new(8);
+        program.run(&mut state).unwrap();
+        state.get_measured_overall_state().unwrap()
+    };
+    assert_eq!(outcome(3), outcome(3));
+    assert_ne!((0..4).map(outcome).collect::<Vec<_>>(), vec![outcome(0); 4]);
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "serde")]
+
+use bra_ket::*;
+use serde_json::json;
+
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    serde_json::from_str(&serde_json::to_string(value).unwrap()).unwrap()
+}
+
+#[test]
+fn programs_round_trip() {
+    let mut program = qft(3);
+    program.add_gate(Operations::ArbitrarySingle(1, Matrix2x2::new(c!(0.6, 0.), c!(0., 0.8), c!(0., 0.8), c!(0.6, 0.))));
+    program.add_gate(Operations::ArbitaryTwo(0, 2, Matrix4x4::identity() * c!(0., 1.)));
+    program.u3(2, 0.1, 0.2, 0.3);
+    program.add_gate(Operations::Barrier);
+    let block = program.define("block");
+    let mut outer = Program::new();
+    let q = outer.quantum_register("q", 3);
+    let c = outer.classical_register("c", 2);
+    outer.add_subcircuit(&block, &q);
+    outer.measure_into(q[0], c[1]);
+    outer.measure_all();
+    outer.set_fusion(Fusion::SingleQubit);
+
+    assert_eq!(round_trip(&outer), outer);
+}
+
+#[test]
+fn gate_schema() {
+    assert_eq!(serde_json::to_value(Operations::H(2)).unwrap(), json!({"gate": "H", "args": 2}));
+    assert_eq!(serde_json::to_value(Operations::CRZ(0, 1, 0.5)).unwrap(), json!({"gate": "CRZ", "args": [0, 1, 0.5]}));
+    assert_eq!(serde_json::to_value(Operations::MeasureAll).unwrap(), json!({"gate": "MeasureAll"}));
+
+    let x = Operations::ArbitrarySingle(0, Matrix2x2::new(c!(0., 0.), c!(1., 0.), c!(0., 1.), c!(0., 0.)));
+    let json = json!({"gate": "ArbitrarySingle", "args": [0, [[[0., 0.], [1., 0.]], [[0., 1.], [0., 0.]]]]});
+    assert_eq!(serde_json::to_value(&x).unwrap(), json);
+    assert_eq!(serde_json::from_value::<Operations>(json).unwrap(), x);
+
+    let program: Program = serde_json::from_value(json!({
+        "gates": [{"gate": "H", "args": 0}, {"gate": "CNOT", "args": [0, 1]}],
+        "fusion": "TwoQubit",
+        "quantum_registers": [{"name": "q", "qubits": [0, 1]}],
+        "classical_registers": []
+    }))
+    .unwrap();
+    assert_eq!(program.gates, vec![Operations::H(0), Operations::CNOT(0, 1)]);
+}
+
+#[test]
+fn states_round_trip() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program.rz(1, 0.7);
+    program.measure_into(1, 0);
+    let mut state = StateVector::new(2);
+    program.run(&mut state).unwrap();
+    state.set_endianness(Endianness::Big);
+
+    let copy = round_trip(&state);
+    assert_eq!(&copy, &state);
+    assert_eq!(copy.endianness, Endianness::Big);
+    assert_eq!(copy.classical_bits, state.classical_bits);
+
+    let density = DensityMatrix::random(2, 5);
+    assert_eq!(&round_trip(&density), &density);
+
+    let json = serde_json::to_value(StateVector::new(1)).unwrap();
+    assert_eq!(json["amplitudes"], json!([[1., 0.], [0., 0.]]));
+    let json = serde_json::to_value(DensityMatrix::new(1)).unwrap();
+    assert_eq!(json["elements"], json!([[[1., 0.], [0., 0.]], [[0., 0.], [0., 0.]]]));
+}
+
+#[test]
+fn invalid_states_are_rejected() {
+    let unnormalised = json!({"number_of_qubits": 1, "amplitudes": [[1., 0.], [1., 0.]]});
+    assert!(serde_json::from_value::<StateVector>(unnormalised).is_err());
+    let short = json!({"number_of_qubits": 2, "amplitudes": [[1., 0.], [0., 0.]]});
+    assert!(serde_json::from_value::<StateVector>(short).is_err());
+    let ragged = json!({"number_of_qubits": 1, "elements": [[[1., 0.], [0., 0.]], [[0., 0.]]]});
+    assert!(serde_json::from_value::<DensityMatrix>(ragged).is_err());
+    let wrong_matrix = json!({"gate": "ArbitrarySingle", "args": [0, [[[1., 0.]]]]});
+    assert!(serde_json::from_value::<Operations>(wrong_matrix).is_err());
+    let huge = json!({"number_of_qubits": 64, "amplitudes": [[1., 0.]]});
+    assert!(serde_json::from_value::<StateVector>(huge).is_err());
+    let huge = json!({"number_of_qubits": 200, "elements": [[[1., 0.]]]});
+    assert!(serde_json::from_value::<DensityMatrix>(huge).is_err());
+}
+
+#[test]
+fn invalid_subcircuits_are_rejected() {
+    let subcircuit = |gates: serde_json::Value| json!({"gate": "Subcircuit", "args": [{"name": "s", "number_of_qubits": 1, "gates": gates}, [0]]});
+    assert!(serde_json::from_value::<Operations>(subcircuit(json!([{"gate": "H", "args": 0}]))).is_ok());
+    assert!(serde_json::from_value::<Operations>(subcircuit(json!([{"gate": "MeasureAll"}]))).is_err());
+    assert!(serde_json::from_value::<Operations>(subcircuit(json!([{"gate": "CNOT", "args": [0, 1]}]))).is_err());
+
+    // the size is checked before the qubits are allocated
+    let huge = json!({"gate": "Subcircuit", "args": [{"name": "s", "number_of_qubits": 100000000000000u64, "gates": []}, [0]]});
+    let error = serde_json::from_value::<Operations>(huge).unwrap_err();
+    assert!(error.to_string().contains("a register of 100000000000000 is larger than"), "{}", error);
+}
+
+#[test]
+fn arbitrary_two_is_spelled_correctly() {
+    let gate = Operations::ArbitaryTwo(0, 1, Matrix4x4::identity());
+    assert_eq!(serde_json::to_value(&gate).unwrap()["gate"], json!("ArbitraryTwo"));
+    let mut old = serde_json::to_value(&gate).unwrap();
+    old["gate"] = json!("ArbitaryTwo");
+    assert_eq!(serde_json::from_value::<Operations>(old).unwrap(), gate);
+}
@@ -0,0 +1,137 @@
+use nalgebra::dvector;
+use bra_ket::*;
+
+#[test]
+fn basis_states() {
+    let mut program = Program::new();
+    program.x(0);
+    program.x(2);
+    let mut expected = StateVector::new(3);
+    program.run(&mut expected).unwrap();
+
+    assert_eq!(&StateVector::basis(3, 5).unwrap(), &expected);
+    assert_eq!(&StateVector::from_bitstring("101").unwrap(), &expected);
+    assert_eq!(&StateVector::from_bitstring("001").unwrap(), &StateVector::basis(3, 1).unwrap());
+    assert_eq!(&DensityMatrix::from_bitstring("101").unwrap(), &DensityMatrix::from(expected));
+    assert_eq!(&DensityMatrix::basis(2, 0).unwrap(), &DensityMatrix::new(2));
+}
+
+#[test]
+fn basis_state_out_of_range() {
+    assert!(matches!(StateVector::basis(2, 4), Err(BraKetError::InvalidBasisLabel { .. })));
+}
+
+#[test]
+fn states_too_large_to_allocate() {
+    assert_eq!(&StateVector::try_new(3).unwrap(), &StateVector::new(3));
+    assert_eq!(&DensityMatrix::try_new(3).unwrap(), &DensityMatrix::new(3));
+    for number_of_qubits in [60, 64, usize::MAX] {
+        assert_eq!(StateVector::try_new(number_of_qubits).unwrap_err(), BraKetError::TooManyQubits(number_of_qubits));
+    }
+    for number_of_qubits in [31, 32, usize::MAX] {
+        assert_eq!(DensityMatrix::try_new(number_of_qubits).unwrap_err(), BraKetError::TooManyQubits(number_of_qubits));
+    }
+}
+
+#[test]
+fn invalid_bitstring() {
+    assert!(matches!(StateVector::from_bitstring("012"), Err(BraKetError::InvalidBasisLabel { .. })));
+}
+
+#[test]
+fn product_states_from_bloch_angles() {
+    let angles = [(0.3, 1.2), (2.1, -0.4), (PI, 0.)];
+    let mut program = Program::new();
+    for (qubit, (theta, phi)) in angles.iter().enumerate() {
+        program.ry(qubit, *theta);
+        program.phase(qubit, *phi);
+    }
+    let mut expected = StateVector::new(3);
+    program.run(&mut expected).unwrap();
+
+    assert_eq!(&StateVector::product(&angles), &expected);
+    assert_eq!(&DensityMatrix::product(&angles), &DensityMatrix::from(expected));
+    assert_eq!(&StateVector::product(&[(0., 0.); 2]), &StateVector::new(2));
+}
+
+#[test]
+fn random_states_are_seeded_and_valid() {
+    let state = StateVector::random(3, 7);
+    assert_eq!(&state, &StateVector::random(3, 7));
+    assert_ne!(&state, &StateVector::random(3, 8));
+    assert!((state.state_vector.norm_squared() - 1.).abs() < 1e-9);
+
+    let mixed = DensityMatrix::random(2, 7);
+    assert_eq!(&mixed, &DensityMatrix::random(2, 7));
+    assert!((mixed.density_matrix.trace().re - 1.).abs() < 1e-9);
+    assert_eq!(&mixed.density_matrix.adjoint(), &mixed.density_matrix);
+    assert!(!mixed.is_pure());
+    assert!(mixed.probabilities().iter().all(|p| *p >= 0.));
+}
+
+#[test]
+fn tensor_products() {
+    let a = StateVector::random(1, 1);
+    let b = StateVector::random(2, 2);
+    let joint = a.tensor(&b);
+    assert_eq!(joint.number_of_qubits, 3);
+    assert_eq!(&joint.reduced_density_matrix(&[0]).unwrap(), &DensityMatrix::from(a.clone()));
+    assert_eq!(&joint.reduced_density_matrix(&[1, 2]).unwrap(), &DensityMatrix::from(b.clone()));
+
+    // preparing the parts with programs combined by | gives the same state
+    let mut program = Program::new();
+    program.h(0);
+    let mut other = Program::new();
+    other.ry(0, 0.4);
+    other.cnot(0, 1);
+    let (mut first, mut second, mut both) = (StateVector::new(1), StateVector::new(2), StateVector::new(3));
+    program.run(&mut first).unwrap();
+    other.run(&mut second).unwrap();
+    (program | other).run(&mut both).unwrap();
+    assert_eq!(&first.tensor(&second), &both);
+
+    let (c, d) = (DensityMatrix::random(1, 3), DensityMatrix::random(1, 4));
+    let joint = c.tensor(&d);
+    assert_eq!(&joint.partial_trace(&[1]).unwrap(), &c);
+    assert_eq!(&joint.partial_trace(&[0]).unwrap(), &d);
+    assert_eq!(&DensityMatrix::from(first.clone()).tensor(&DensityMatrix::from(second)), &DensityMatrix::from(both));
+}
+
+#[test]
+fn cloned_density_matrices_are_independent() {
+    let original = DensityMatrix::new(1);
+    let mut copy = original.clone();
+    let mut program = Program::new();
+    program.x(0);
+    program.run(&mut copy).unwrap();
+    assert_eq!(&original, &DensityMatrix::new(1));
+    assert_ne!(&copy, &original);
+}
+
+#[test]
+fn cloned_state_vectors_keep_measurements() {
+    let mut program = Program::new();
+    program.x(0);
+    program.measure(0);
+    program.measure_into(1, 2);
+    let mut state = StateVector::new(2);
+    program.run(&mut state).unwrap();
+
+    let copy = state.clone();
+    assert_eq!(copy.endianness(), state.endianness());
+    assert!(copy.get_measured_qubit_state(0).unwrap());
+    assert!(!copy.get_classical_bit(2).unwrap());
+    assert_eq!(copy.get_classical_bit(1), Err(BraKetError::BitNotMeasured(1)));
+}
+
+#[test]
+fn unnormalised_vector() {
+    let result = StateVector::try_from(dvector![c!(1., 0.), c!(1., 0.)]);
+    assert_eq!(result.unwrap_err(), BraKetError::NotNormalised(2.));
+}
+
+#[test]
+fn vector_of_wrong_length() {
+    let result = StateVector::try_from(dvector![c!(1., 0.), c!(0., 0.), c!(0., 0.)]);
+    assert_eq!(result.unwrap_err(), BraKetError::NotPowerOfTwo(3));
+}
@@ -15,12 +15,12 @@ fn x0() {
 
         let mut program = Program::new();
         program.rx(0, angle);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         let (c, s) = ((angle / 2.).cos(), (angle / 2.).sin());
 
         let other_state =
-            StateVector::from(dvector![c!(c, 0.), c!(0., -s)]);
+            StateVector::try_from(dvector![c!(c, 0.), c!(0., -s)]).unwrap();
 
         assert_eq!(&state, &other_state);
     }
@@ -35,12 +35,12 @@ fn y0() {
 
         let mut program = Program::new();
         program.ry(0, angle);
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
 
         let (c, s) = ((angle / 2.).cos(), (angle / 2.).sin());
 
         let other_state =
-            StateVector::from(dvector![c!(c, 0.), c!(s, 0.)]);
+            StateVector::try_from(dvector![c!(c, 0.), c!(s, 0.)]).unwrap();
 
         assert_eq!(&state, &other_state);
     }
@@ -59,7 +59,7 @@ fn measure() {
     let mut p_estimated = 0.;
 
     for _ in 1..n {
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
         p_estimated += state.state_vector[0].modulus_squared() / (n as Real);
     }
 
@@ -67,7 +67,7 @@ fn measure() {
     let std = (p_required * (1. - &p_required) / (n as Real)).sqrt();
 
     assert!(
-        (p_estimated - &p_required).abs() < 3. * std,
+        (p_estimated - p_required).abs() < 3. * std,
         "estimated probabily {} not consistent with the required {}",
         p_estimated,
         p_required
@@ -85,7 +85,7 @@ fn measure_all() {
 
     let n = 100;
     for _ in 1..n {
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
     }
     let p_required = 0.5;
     let _std = (p_required * (1. - &p_required) / (n as Real)).sqrt();
@@ -100,3 +100,24 @@ fn measure_all() {
 }
 
 
+
+/// tests that measuring an unequal superposition collapses onto the measured basis state
+#[test]
+fn measure_collapses() {
+    let mut range = thread_rng();
+    for _ in 1..10 {
+        let angle = PI * range.gen::<Angle>();
+        let mut state = StateVector::new(1);
+
+        let mut program = Program::new();
+        program.ry(0, angle);
+        program.measure(0);
+        program.run(&mut state).unwrap();
+
+        let other_state = match state.get_measured_qubit_state(0).unwrap() {
+            false => StateVector::try_from(dvector![c!(1., 0.), c!(0., 0.)]).unwrap(),
+            true => StateVector::try_from(dvector![c!(0., 0.), c!(1., 0.)]).unwrap(),
+        };
+        assert_eq!(&state, &other_state);
+    }
+}
@@ -9,14 +9,14 @@ fn x0_cnot01() {
     let mut program = Program::new();
     program.x(0);
     program.cnot(0, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = StateVector::from(dvector![
+    let required_state = StateVector::try_from(dvector![
         c!(0., 0.),
         c!(0., 0.),
         c!(0., 0.),
         c!(1., 0.)
-    ]);
+    ]).unwrap();
 
     assert_eq!(&required_state, &state);
 }
@@ -27,14 +27,14 @@ fn h0_h1() {
     let mut program = Program::new();
     program.h(0);
     program.h(1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = StateVector::from(dvector![
+    let required_state = StateVector::try_from(dvector![
         c!(1. / 2., 0.),
         c!(1. / 2., 0.),
         c!(1. / 2., 0.),
         c!(1. / 2., 0.)
-    ]);
+    ]).unwrap();
 
     assert_eq!(&required_state, &state);
 }
@@ -53,7 +53,7 @@ fn h0_h1_measure() {
     let mut p_estimated = 0.;
 
     for _ in 1..n {
-        program.run(&mut state);
+        program.run(&mut state).unwrap();
         p_estimated += state.state_vector[0].modulus_squared() / (n as Real);
     }
 
@@ -61,7 +61,7 @@ fn h0_h1_measure() {
     let std = (p_required * (1. - &p_required) / (n as Real)).sqrt();
 
     assert!(
-        (p_estimated - &p_required).abs() < 3. * std,
+        (p_estimated - p_required).abs() < 3. * std,
         "estimated probabily {} not consistent with the required {}",
         p_estimated,
         p_required
@@ -83,8 +83,8 @@ fn h0_cnot_measure_all() {
     // running the program n times and recording the overall measured qubit state into hashmap
     let n = 100;
     let counts = (0..n).map(|_| {
-            program.run(&mut state);
-            state.get_measured_overall_state()
+            program.run(&mut state).unwrap();
+            state.get_measured_overall_state().unwrap()
         }).counts();
 
     // running a three sigma hypothesis test that the probability of measuring the |0> state is 0.5
@@ -92,7 +92,7 @@ fn h0_cnot_measure_all() {
     let std = (p_required * (1. - p_required) / n as Real).sqrt();
     let p_estimated = counts[&0] as Real / n as Real;
     assert!(
-        (p_estimated - &p_required).abs() < 3. * std,
+        (p_estimated - p_required).abs() < 3. * std,
         "estimated probabily {} not consistent with the required {}",
         p_estimated,
         p_required
@@ -114,8 +114,8 @@ fn measure_all() {
     // running the program n times and recording the overall measured qubit state into hashmap
     let n = 100;
     let counts = (0..n).map(|_| {
-        program.run(&mut state);
-        state.get_measured_overall_state()
+        program.run(&mut state).unwrap();
+        state.get_measured_overall_state().unwrap()
     }).counts();
 
     let p_required = 0.25;
@@ -123,7 +123,7 @@ fn measure_all() {
     let p_estimated = counts[&0] as Real / n as Real;
 
     assert!(
-        (p_estimated - &p_required).abs() < 3. * std,
+        (p_estimated - p_required).abs() < 3. * std,
         "estimated probabily {} not consistent with the required {}",
         p_estimated,
         p_required
@@ -9,9 +9,9 @@ fn ghz_0() {
     program.h(0);
     program.cnot(0, 1);
     program.cnot(0, 2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = StateVector::from(dvector![
+    let required_state = StateVector::try_from(dvector![
         c!(1. / SQRT_2, 0.),
         c!(0., 0.),
         c!(0., 0.),
@@ -20,7 +20,7 @@ fn ghz_0() {
         c!(0., 0.),
         c!(0., 0.),
         c!(1. / SQRT_2, 0.)
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -32,9 +32,9 @@ fn ghz_1() {
     program.h(1);
     program.cnot(1, 0);
     program.cnot(1, 2);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = StateVector::from(dvector![
+    let required_state = StateVector::try_from(dvector![
         c!(1. / SQRT_2, 0.),
         c!(0., 0.),
         c!(0., 0.),
@@ -43,7 +43,7 @@ fn ghz_1() {
         c!(0., 0.),
         c!(0., 0.),
         c!(1. / SQRT_2, 0.)
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -55,9 +55,9 @@ fn ghz_2() {
     program.h(2);
     program.cnot(2, 0);
     program.cnot(2, 1);
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = StateVector::from(dvector![
+    let required_state = StateVector::try_from(dvector![
         c!(1. / SQRT_2, 0.),
         c!(0., 0.),
         c!(0., 0.),
@@ -66,7 +66,7 @@ fn ghz_2() {
         c!(0., 0.),
         c!(0., 0.),
         c!(1. / SQRT_2, 0.)
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
 
@@ -86,9 +86,9 @@ fn deutsch_jozsa_balanced() {
     program.h(0);
     program.h(1);
 
-    program.run(&mut state);
+    program.run(&mut state).unwrap();
 
-    let required_state = StateVector::from(dvector![
+    let required_state = StateVector::try_from(dvector![
         c!(0., 0.),
         c!(0., 0.),
         c!(0., 0.),
@@ -97,6 +97,6 @@ fn deutsch_jozsa_balanced() {
         c!(0., 0.),
         c!(0., 0.),
         c!(-1. / SQRT_2, 0.)
-    ]);
+    ]).unwrap();
     assert_eq!(&required_state, &state);
 }
@@ -0,0 +1,67 @@
+use bra_ket::*;
+
+fn assert_equal(a: &CMatrix, b: &CMatrix) {
+    assert!((a - b).norm() < 1e-6, "{} != {}", a, b);
+}
+
+#[test]
+fn subcircuit_runs_on_its_qubits() {
+    let block = qft(3).define("qft3");
+    let mut program = Program::new();
+    program.add_subcircuit(&block, &[1, 2, 3]);
+    assert_eq!(program.gates.len(), 1);
+    assert_equal(&program.unitary(4).unwrap(), &qft(3).shift(1).unitary(4).unwrap());
+
+    // placing the subcircuit on permuted qubits is the same as remapping it
+    let mut permuted = Program::new();
+    permuted.add_subcircuit(&block, &[2, 0, 1]);
+    assert_equal(&permuted.unitary(3).unwrap(), &qft(3).remap(&[2, 0, 1]).unitary(3).unwrap());
+}
+
+#[test]
+fn subcircuit_algebra_and_metrics() {
+    let block = qft(3).define("qft3");
+    let mut program = Program::new();
+    program.h(0);
+    program.add_subcircuit(&block, &[0, 1, 2]);
+
+    assert_equal(&(program.clone() + program.inverse().unwrap()).unitary(3).unwrap(), &CMatrix::identity(8, 8));
+    assert_equal(&program.flatten().controlled(3).unwrap().unitary(4).unwrap(), &program.controlled(3).unwrap().unitary(4).unwrap());
+    assert_eq!(program.depth(), program.flatten().depth());
+    assert_eq!(program.layers().len(), program.depth());
+    assert_eq!(program.layers().concat().len(), program.flatten().gates.len());
+    assert_eq!(program.gate_counts()["H"], 4);
+}
+
+#[test]
+fn nested_subcircuits_in_qasm() {
+    let mut inner = Program::new();
+    inner.h(0);
+    inner.cnot(0, 1);
+    let bell = inner.define("bell");
+    let mut outer = Program::new();
+    outer.add_subcircuit(&bell, &[0, 1]);
+    outer.add_subcircuit(&bell, &[1, 2]);
+    let pair = outer.define("pairs");
+
+    let mut program = Program::new();
+    program.add_subcircuit(&pair, &[3, 1, 2]);
+    program.measure_all();
+
+    let qasm = program.to_qasm();
+    let lines: Vec<&str> = qasm.lines().collect();
+    let bell_definition = lines.iter().position(|l| *l == "gate bell q0,q1 {").unwrap();
+    let pairs_definition = lines.iter().position(|l| *l == "gate pairs q0,q1,q2 {").unwrap();
+    assert!(bell_definition < pairs_definition);
+    assert!(lines.contains(&"  bell q1,q2;"));
+    assert!(lines.contains(&"pairs q[3],q[1],q[2];"));
+    assert_eq!(lines.iter().filter(|l| l.starts_with("gate bell")).count(), 1);
+}
+
+#[test]
+#[should_panic]
+fn subcircuit_needs_matching_qubits() {
+    let block = qft(3).define("qft3");
+    let mut program = Program::new();
+    program.add_subcircuit(&block, &[0, 1]);
+}
@@ -0,0 +1,119 @@
+use nalgebra::{ComplexField, DMatrix};
+use rand::prelude::*;
+use bra_ket::*;
+
+/// creates a random unitary from the QR decomposition of a random complex matrix
+fn random_unitary(range: &mut StdRng, dim: usize) -> CMatrix {
+    let m = DMatrix::from_fn(dim, dim, |_, _| c!(range.gen::<Real>() - 0.5, range.gen::<Real>() - 0.5));
+    m.qr().q()
+}
+
+/// checks that two unitaries are equal up to a global phase
+fn assert_equal_up_to_phase(u: &CMatrix, v: &CMatrix) {
+    let overlap = (u.adjoint() * v).trace().abs() / u.nrows() as Real;
+    assert!((1. - overlap).abs() < 1e-6, "unitaries differ, overlap {}", overlap);
+}
+
+/// checks that every gate in the program belongs to the native gate set
+fn assert_native(program: &Program, gate_set: &NativeGateSet) {
+    for gate in program.gates.iter() {
+        let native = match gate {
+            Operations::RZ(_, _) => true,
+            Operations::SX(_) => true,
+            Operations::CZ(_, _) => *gate_set == NativeGateSet::CZ,
+            Operations::SISWAP(_, _) => *gate_set == NativeGateSet::SISWAP,
+            Operations::Measure(_) | Operations::MeasureAll | Operations::Barrier => true,
+            _ => false,
+        };
+        assert!(native, "{:?} is not in the native gate set {:?}", gate, gate_set);
+    }
+}
+
+fn count_entanglers(program: &Program) -> usize {
+    program.gates.iter().filter(|gate| matches!(gate, Operations::CZ(_, _) | Operations::SISWAP(_, _))).count()
+}
+
+#[test]
+fn single_qubit_gates() {
+    let mut range = StdRng::seed_from_u64(0);
+    let mut program = Program::new();
+    program.h(0);
+    program.r(1, range.gen(), range.gen(), range.gen());
+    program.s(0);
+    program.y(1);
+    program.rx(0, range.gen());
+    let u = random_unitary(&mut range, 2);
+    program.add_gate(Operations::ArbitrarySingle(1, Matrix2x2::from_fn(|i, j| u[(i, j)])));
+
+    for gate_set in [NativeGateSet::CZ, NativeGateSet::SISWAP] {
+        let transpiled = program.transpile(&gate_set);
+        assert_native(&transpiled, &gate_set);
+        assert_eq!(count_entanglers(&transpiled), 0);
+        assert_equal_up_to_phase(&program.unitary(2).unwrap(), &transpiled.unitary(2).unwrap());
+    }
+}
+
+#[test]
+fn arbitrary_two_qubit_gates() {
+    let mut range = StdRng::seed_from_u64(1);
+    for _ in 0..20 {
+        let u = random_unitary(&mut range, 4);
+        let mut program = Program::new();
+        program.add_gate(Operations::ArbitaryTwo(0, 1, Matrix4x4::from_fn(|i, j| u[(i, j)])));
+
+        for gate_set in [NativeGateSet::CZ, NativeGateSet::SISWAP] {
+            let transpiled = program.transpile(&gate_set);
+            assert_native(&transpiled, &gate_set);
+            assert!(count_entanglers(&transpiled) <= 3);
+            assert_equal_up_to_phase(&program.unitary(2).unwrap(), &transpiled.unitary(2).unwrap());
+        }
+    }
+}
+
+#[test]
+fn standard_two_qubit_gates() {
+    let mut program = Program::new();
+    program.cnot(0, 1);
+    let mut iswap = Program::new();
+    iswap.iswap(1, 0);
+    let mut swap = Program::new();
+    swap.swap(0, 1);
+
+    for (program, cz_count, siswap_count) in [(program, 1, 2), (iswap, 2, 2), (swap, 3, 3)] {
+        let transpiled = program.transpile(&NativeGateSet::CZ);
+        assert_eq!(count_entanglers(&transpiled), cz_count);
+        assert_equal_up_to_phase(&program.unitary(2).unwrap(), &transpiled.unitary(2).unwrap());
+
+        let transpiled = program.transpile(&NativeGateSet::SISWAP);
+        assert_eq!(count_entanglers(&transpiled), siswap_count);
+        assert_equal_up_to_phase(&program.unitary(2).unwrap(), &transpiled.unitary(2).unwrap());
+    }
+}
+
+#[test]
+fn three_qubit_program() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program.add_gate(Operations::CRZ(1, 2, PI / 3.));
+    program.siswap(2, 0);
+    program.ry(2, 0.4);
+    program.cz(0, 2);
+
+    for gate_set in [NativeGateSet::CZ, NativeGateSet::SISWAP] {
+        let transpiled = program.transpile(&gate_set);
+        assert_native(&transpiled, &gate_set);
+        assert_equal_up_to_phase(&program.unitary(3).unwrap(), &transpiled.unitary(3).unwrap());
+    }
+}
+
+#[test]
+fn measurements_are_preserved() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(0, 1);
+    program.measure_all();
+
+    let transpiled = program.transpile(&NativeGateSet::CZ);
+    assert_eq!(transpiled.gates.last(), Some(&Operations::MeasureAll));
+}
@@ -0,0 +1,88 @@
+use bra_ket::*;
+
+#[test]
+fn valid_programs_pass() {
+    let mut program = qft(3);
+    program.rxx(0, 2, 0.3);
+    program.add_gate(Operations::ArbitrarySingle(1, Matrix2x2::identity()));
+    program.measure_all();
+    assert_eq!(program.validate(3), Ok(()));
+    assert_eq!(Program::new().validate(0), Ok(()));
+}
+
+#[test]
+fn every_issue_is_reported_with_its_gate() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cnot(1, 1);
+    program.rx(0, Angle::NAN);
+    program.add_gate(Operations::ArbitrarySingle(0, Matrix2x2::identity() * c!(2., 0.)));
+    program.u3(4, 0.1, Angle::INFINITY, 0.2);
+
+    let issues = program.validate(3).unwrap_err();
+    assert_eq!(issues.len(), 5);
+    assert_eq!(issues[0], ValidationIssue::RepeatedQubit { gate: 1, qubit: 1 });
+    assert!(matches!(issues[1], ValidationIssue::NotFinite { gate: 2, .. }));
+    assert_eq!(issues[2], ValidationIssue::NotUnitary { gate: 3 });
+    assert_eq!(issues[3], ValidationIssue::QubitOutOfRange { gate: 4, qubit: 4 });
+    assert_eq!(issues[4], ValidationIssue::NotFinite { gate: 4, angle: Angle::INFINITY });
+    assert_eq!(issues.iter().map(|issue| issue.gate()).collect::<Vec<usize>>(), vec![1, 2, 3, 4, 4]);
+}
+
+#[test]
+fn subcircuits_are_checked_inside() {
+    let mut inner = Program::new();
+    inner.h(0);
+    inner.add_gate(Operations::ArbitaryTwo(0, 1, Matrix4x4::zeros()));
+    let block = inner.define("broken");
+
+    let mut program = Program::new();
+    program.x(0);
+    program.add_subcircuit(&block, &[2, 0]);
+    assert_eq!(program.validate(3), Err(vec![ValidationIssue::NotUnitary { gate: 1 }]));
+}
+
+#[test]
+fn subcircuits_are_placed_on_their_number_of_qubits() {
+    let mut inner = Program::new();
+    inner.cnot(0, 1);
+    let block = inner.define("pair");
+
+    let mut program = Program::new();
+    program.add_gate(Operations::Subcircuit(block, vec![0]));
+    let issues = program.validate(2).unwrap_err();
+    assert_eq!(issues, vec![ValidationIssue::WrongPlacement { gate: 0, expected: 2, found: 1 }]);
+    assert_eq!(issues[0].to_string(), "gate 0 places a subcircuit of 2 qubits on 1");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+fn run_validates_in_debug_builds() {
+    let mut program = Program::new();
+    program.h(0);
+    program.cz(0, 0);
+    let mut state = StateVector::new(1);
+    let error = program.run(&mut state).unwrap_err();
+    assert_eq!(error, BraKetError::InvalidProgram(vec![ValidationIssue::RepeatedQubit { gate: 1, qubit: 0 }]));
+    assert_eq!(error.to_string(), "invalid program: gate 1 uses qubit 0 twice");
+    assert_eq!(&state, &StateVector::new(1));
+}
+
+#[test]
+fn classical_bits_beyond_the_limit_are_rejected() {
+    let mut program = Program::new();
+    program.h(0);
+    program.measure_into(0, MAX_CLASSICAL_BITS - 1);
+    assert_eq!(program.validate(1), Ok(()));
+    program.measure_into(0, usize::MAX);
+    assert_eq!(program.validate(1), Err(vec![ValidationIssue::BitOutOfRange { gate: 2, bit: usize::MAX }]));
+    assert_eq!(program.number_of_classical_bits(), usize::MAX);
+
+    // run and sample fail before touching the state or allocating the bits
+    let mut state = StateVector::new(1);
+    assert_eq!(program.run(&mut state), Err(BraKetError::InvalidBit(usize::MAX)));
+    assert_eq!(&state, &StateVector::new(1));
+    assert_eq!(program.sample(&StateVector::new(1), 10, 0), Err(BraKetError::InvalidBit(usize::MAX)));
+    let mut state = DensityMatrix::new(1);
+    assert_eq!(program.run_with_noise(&mut state, &NoiseModel::new()), Err(BraKetError::InvalidBit(usize::MAX)));
+}